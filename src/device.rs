@@ -4,51 +4,320 @@
 //! product IDs, claims the appropriate interface (UVC for 4K X, HID for 4K S),
 //! and returns a handle ready for control transfers.  The [`Drop`] impl
 //! releases the interface and reattaches the kernel driver on cleanup.
+//! Transfers go through a [`Transport`], libusb's unless another one is
+//! given to [`ElgatoDevice::with_transport`].
+
+use std::fmt;
+use std::os::fd::RawFd;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
 
 use rusb::{Context, Device, DeviceHandle, UsbContext};
 
+use crate::device_builder::ElgatoDeviceBuilder;
+use crate::device_set::DeviceResult;
 use crate::error::ElgatoError;
+use crate::profile::{Profile, SettingChange};
 use crate::protocol::*;
 use crate::settings::*;
+use crate::status::{StatusCache, UsbSpeedStatus};
+use crate::status::needs_write;
+use crate::transport::{Transport, UsbTransport};
+use crate::uvc::XuControlInfo;
 
 /// Result of device discovery (internal).
-struct FoundDevice {
-    device: Device<Context>,
-    model: DeviceModel,
-    pid: u16,
+pub(crate) struct FoundDevice {
+    pub(crate) device: Device<Context>,
+    pub(crate) model: DeviceModel,
+    pub(crate) pid: u16,
+}
+
+/// A supported device as the host sees it, read from cached descriptors
+/// without opening it.  See [`ElgatoDevice::detect_all`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DetectedDevice {
+    pub model: DeviceModel,
+    pub pid: u16,
+    pub bus_number: u8,
+    pub address: u8,
+    pub revision: HardwareRevision,
+    /// Speed the link negotiated, if the host knows it.
+    pub link_speed: Option<UsbSpeedStatus>,
+    /// Hub port path from the root hub, as in sysfs names like `2-1.4`.
+    pub port_numbers: Vec<u8>,
+}
+
+/// Hardware revision of a device, taken from the USB `bcdDevice` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HardwareRevision {
+    /// Raw BCD-encoded `bcdDevice` value (e.g. `0x0100` for 1.00).
+    pub bcd_device: u16,
+}
+
+impl HardwareRevision {
+    fn from_version(v: rusb::Version) -> Self {
+        let bcd_device = (((v.major() / 10) as u16) << 12)
+            | (((v.major() % 10) as u16) << 8)
+            | ((v.minor() as u16) << 4)
+            | v.sub_minor() as u16;
+        Self { bcd_device }
+    }
+}
+
+impl fmt::Display for HardwareRevision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:x}.{:02x}", self.bcd_device >> 8, self.bcd_device & 0xff)
+    }
+}
+
+/// How an [`ElgatoDevice`] sends control transfers.  See
+/// [`ElgatoDevice::set_transfer_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransferPolicy {
+    /// Time each control transfer may take.  Defaults to one second.
+    pub timeout: Duration,
+    /// How many times a transfer that timed out or hit an I/O error is
+    /// sent again.  Defaults to none.
+    pub retries: u32,
+}
+
+impl Default for TransferPolicy {
+    fn default() -> Self {
+        Self { timeout: USB_TIMEOUT, retries: 0 }
+    }
+}
+
+/// What [`ElgatoDevice`] does with the kernel driver bound to the interface
+/// it needs: `uvcvideo` on the 4K X, `usbhid` on the 4K S.  Set through
+/// [`ElgatoDeviceBuilder`](crate::ElgatoDeviceBuilder).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct KernelDriverPolicy {
+    /// Detach a bound driver when opening, or fail with
+    /// [`ElgatoError::KernelDriverActive`].
+    pub(crate) detach: bool,
+    /// Bind the driver again when the handle is dropped.
+    pub(crate) reattach: bool,
+}
+
+impl Default for KernelDriverPolicy {
+    fn default() -> Self {
+        Self { detach: true, reattach: true }
+    }
 }
 
 /// Handle to an opened Elgato capture card.
 pub struct ElgatoDevice {
-    pub(crate) handle: DeviceHandle<Context>,
+    pub(crate) transport: Box<dyn Transport>,
     pub(crate) model: DeviceModel,
     pub(crate) pid: u16,
+    pub(crate) bus_number: u8,
+    pub(crate) address: u8,
+    pub(crate) revision: HardwareRevision,
+    pub(crate) link_speed: Option<UsbSpeedStatus>,
+    pub(crate) status_cache: Mutex<StatusCache>,
+    pub(crate) transfer_policy: Mutex<TransferPolicy>,
+    pub(crate) driver_policy: KernelDriverPolicy,
 }
 
 impl ElgatoDevice {
     /// Scan the USB bus, open the first supported device, and claim its interface.
+    ///
+    /// Use [`builder`](Self::builder) to pick a particular card or change
+    /// how it is opened.
     pub fn open() -> Result<Self, ElgatoError> {
+        Self::builder().open()
+    }
+
+    /// Choose which card to open and how: by serial number, bus or model,
+    /// with a different USB timeout, retries, or kernel driver handling.
+    pub fn builder() -> ElgatoDeviceBuilder {
+        ElgatoDeviceBuilder::default()
+    }
+
+    /// Scan the USB bus and return the model of the first supported device,
+    /// without opening it.  Only cached descriptors are read, so nothing is
+    /// sent to the device.
+    pub fn detect_model() -> Result<DeviceModel, ElgatoError> {
         let context = Context::new()?;
+        Self::find_devices(&context)?
+            .first()
+            .map(|found| found.model)
+            .ok_or(ElgatoError::DeviceNotFound)
+    }
 
-        let found = Self::find_device(&context)?;
-        let handle = found.device.open()?;
-        let model = found.model;
-        let pid = found.pid;
+    /// Every supported device on the bus, without opening any of them.
+    /// Only cached descriptors are read, so this works without permission
+    /// to open the cards and doesn't disturb a running capture.
+    pub fn detect_all() -> Result<Vec<DetectedDevice>, ElgatoError> {
+        let context = Context::new()?;
+        Self::find_devices(&context)?
+            .into_iter()
+            .map(|found| {
+                Ok(DetectedDevice {
+                    model: found.model,
+                    pid: found.pid,
+                    bus_number: found.device.bus_number(),
+                    address: found.device.address(),
+                    revision: HardwareRevision::from_version(found.device.device_descriptor()?.device_version()),
+                    link_speed: link_speed_from(found.device.speed()),
+                    port_numbers: found.device.port_numbers().unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
 
-        let interface_num = match model {
-            DeviceModel::Elgato4KX => UVC_INTERFACE,
-            DeviceModel::Elgato4KS => HID_INTERFACE,
+    /// Scan the USB bus and open every supported device, with one
+    /// [`DeviceResult`] per card so a card that can't be opened doesn't hide
+    /// the others.
+    ///
+    /// Returns [`ElgatoError::DeviceNotFound`] if no device is connected.
+    pub fn open_all() -> Result<Vec<DeviceResult<Self>>, ElgatoError> {
+        Self::builder().open_all()
+    }
+
+    /// Open the device at a devnode or sysfs path and claim its interface.
+    ///
+    /// Accepts either a usbfs devnode (`/dev/bus/usb/003/012`) or a sysfs
+    /// device directory (`/sys/bus/usb/devices/3-2`), which is what udev
+    /// rules and scripts usually have at hand.  The device is matched by bus
+    /// number and address, so no other device's descriptors are read.
+    pub fn open_path(path: impl AsRef<Path>) -> Result<Self, ElgatoError> {
+        let path = path.as_ref();
+        let invalid = |reason: String| ElgatoError::InvalidDevicePath {
+            path: path.display().to_string(),
+            reason,
         };
 
-        let kernel_driver_was_active = handle.kernel_driver_active(interface_num as u8)?;
+        let (bus_number, address) = resolve_device_path(path).map_err(invalid)?;
 
-        if kernel_driver_was_active {
-            handle.detach_kernel_driver(interface_num as u8)?;
-        }
+        let context = Context::new()?;
+        let device = context
+            .devices()?
+            .iter()
+            .find(|d| d.bus_number() == bus_number && d.address() == address)
+            .ok_or_else(|| invalid(format!("no USB device at bus {} address {}", bus_number, address)))?;
 
-        handle.claim_interface(interface_num as u8)?;
+        let desc = device.device_descriptor()?;
+        let pid = desc.product_id();
+        let model = match model_for_ids(desc.vendor_id(), pid) {
+            Some(model) => model,
+            None => {
+                return Err(invalid(format!(
+                    "{:04x}:{:04x} is not an Elgato 4K X or 4K S",
+                    desc.vendor_id(),
+                    pid
+                )));
+            }
+        };
+
+        Self::open_found(FoundDevice { device, model, pid }, KernelDriverPolicy::default())
+    }
+
+    /// Open a device from an already-opened usbfs file descriptor.
+    ///
+    /// Intended for sandboxed setups (Flatpak portals, privileged helpers)
+    /// where the process is handed a descriptor for `/dev/bus/usb/BBB/DDD`
+    /// but has no rights to enumerate the bus.  Uses libusb's
+    /// `libusb_wrap_sys_device`, so no device scan takes place.  In a sandbox
+    /// without any usbfs access, call `rusb::disable_device_discovery()`
+    /// before the first device is opened.
+    ///
+    /// # Safety
+    ///
+    /// `fd` must be an open usbfs device descriptor and must stay open for
+    /// the lifetime of the returned handle.  libusb does not take ownership
+    /// of it; the caller closes it after the handle is dropped.
+    pub unsafe fn open_from_fd(fd: RawFd) -> Result<Self, ElgatoError> {
+        let context = Context::new()?;
+        // SAFETY: upheld by the caller, see the function's safety contract.
+        let handle = unsafe { context.open_device_with_fd(fd)? };
+
+        let device = handle.device();
+        let desc = device.device_descriptor()?;
+        let pid = desc.product_id();
+        let model = model_for_ids(desc.vendor_id(), pid).ok_or_else(|| {
+            ElgatoError::InvalidDevicePath {
+                path: format!("fd {}", fd),
+                reason: format!("{:04x}:{:04x} is not an Elgato 4K X or 4K S", desc.vendor_id(), pid),
+            }
+        })?;
+
+        Self::claim(handle, &device, model, pid, KernelDriverPolicy::default())
+    }
+
+    /// Open a discovered device and claim the interface for its model.
+    fn open_found(found: FoundDevice, driver: KernelDriverPolicy) -> Result<Self, ElgatoError> {
+        let handle = found.device.open()?;
+        Self::claim(handle, &found.device, found.model, found.pid, driver)
+    }
+
+    /// Detach the kernel driver from an opened handle (if `driver` allows)
+    /// and claim its interface.
+    pub(crate) fn claim(
+        handle: DeviceHandle<Context>,
+        device: &Device<Context>,
+        model: DeviceModel,
+        pid: u16,
+        driver: KernelDriverPolicy,
+    ) -> Result<Self, ElgatoError> {
+        let info = DetectedDevice {
+            model,
+            pid,
+            bus_number: device.bus_number(),
+            address: device.address(),
+            revision: HardwareRevision::from_version(device.device_descriptor()?.device_version()),
+            link_speed: link_speed_from(device.speed()),
+            port_numbers: device.port_numbers().unwrap_or_default(),
+        };
+        Self::claim_transport(Box::new(UsbTransport::new(handle)), &info, driver)
+    }
 
-        Ok(Self { handle, model, pid })
+    /// Drive a card through another [`Transport`]: a different USB backend,
+    /// or a mock in tests.  `device` describes the card behind it; its model
+    /// decides which interface is claimed and which protocol is spoken.
+    ///
+    /// The interface is claimed with the kernel driver detached, and
+    /// released (with the driver reattached) on drop, as for
+    /// [`open`](Self::open).
+    pub fn with_transport(transport: impl Transport + 'static, device: &DetectedDevice) -> Result<Self, ElgatoError> {
+        Self::claim_transport(Box::new(transport), device, KernelDriverPolicy::default())
+    }
+
+    /// Claim the interface for `device`'s model through `transport`.
+    fn claim_transport(
+        mut transport: Box<dyn Transport>,
+        device: &DetectedDevice,
+        driver: KernelDriverPolicy,
+    ) -> Result<Self, ElgatoError> {
+        let DetectedDevice { model, pid, bus_number, address, revision, link_speed, .. } = *device;
+        let interface_num = interface_for(model);
+        transport.claim(interface_num, driver.detach)?;
+        log::debug!(
+            "Claimed interface {} on {} (PID 0x{:04x}, revision {}) at bus {:03} address {:03}",
+            interface_num,
+            model,
+            pid,
+            revision,
+            bus_number,
+            address
+        );
+
+        Ok(Self {
+            transport,
+            model,
+            pid,
+            bus_number,
+            address,
+            revision,
+            link_speed,
+            status_cache: Mutex::new(StatusCache::default()),
+            transfer_policy: Mutex::new(TransferPolicy::default()),
+            driver_policy: driver,
+        })
     }
 
     /// The device model (4K X or 4K S).
@@ -61,6 +330,112 @@ impl ElgatoDevice {
         self.pid
     }
 
+    /// The hardware revision reported in the device descriptor (`bcdDevice`).
+    pub fn revision(&self) -> HardwareRevision {
+        self.revision
+    }
+
+    /// Whether the device enumerated with a USB 2.0 fallback PID.
+    ///
+    /// A degraded device still accepts most setting changes, but cannot
+    /// capture video and cannot switch USB speed modes.
+    pub fn is_degraded(&self) -> bool {
+        PIDS_USB2_FALLBACK.contains(&self.pid)
+    }
+
+    /// The speed the USB link actually negotiated, as reported by the host.
+    ///
+    /// Unlike the PID-derived mode in [`DeviceStatus::usb_speed`](crate::DeviceStatus::usb_speed),
+    /// this reflects the cable and port: a 4K X in 10Gbps mode on a 5Gbps
+    /// port reports [`UsbSpeedStatus::FiveGbps`] here.  `None` if the host
+    /// doesn't know or the link is slower than USB 2.0 High-Speed.
+    pub fn link_speed(&self) -> Option<UsbSpeedStatus> {
+        self.link_speed
+    }
+
+    /// The USB bus number the device is attached to.
+    pub fn bus_number(&self) -> u8 {
+        self.bus_number
+    }
+
+    /// The device address on its USB bus.
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// Reset the USB port the card is on, the same as unplugging it and
+    /// plugging it back in.  Useful for a card that stopped answering.
+    ///
+    /// The card re-enumerates afterwards, so the handle is consumed; call
+    /// [`open`](Self::open) again (after a few seconds) to keep using it.
+    /// Settings the card stores survive the reset.
+    pub fn reset_usb(mut self) -> Result<(), ElgatoError> {
+        match self.transport.reset() {
+            // libusb reports a device that came back as a different device
+            // (e.g. after a speed change) as not found; the reset still happened.
+            Ok(()) | Err(rusb::Error::NotFound) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The timeout and retry count used for control transfers.
+    pub fn transfer_policy(&self) -> TransferPolicy {
+        *self.transfer_policy.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Change the timeout and retry count used for control transfers.
+    ///
+    /// The defaults suit a card plugged straight into the host.  Behind a
+    /// flaky hub, a longer timeout or a couple of retries can help.  Only
+    /// timeouts and I/O errors are retried; a stalled request is the card
+    /// refusing it, and sending it again won't change that.
+    pub fn set_transfer_policy(&self, policy: TransferPolicy) {
+        *self.transfer_policy.lock().unwrap_or_else(|e| e.into_inner()) = policy;
+    }
+
+    // --- Control transfers ---
+
+    /// Host-to-device control transfer under the [`TransferPolicy`].
+    pub(crate) fn write_control(
+        &self,
+        request_type: u8,
+        request: u8,
+        value: u16,
+        index: u16,
+        data: &[u8],
+    ) -> rusb::Result<usize> {
+        self.with_retries(|timeout| self.transport.write_control(request_type, request, value, index, data, timeout))
+    }
+
+    /// Device-to-host control transfer under the [`TransferPolicy`].
+    pub(crate) fn read_control(
+        &self,
+        request_type: u8,
+        request: u8,
+        value: u16,
+        index: u16,
+        buf: &mut [u8],
+    ) -> rusb::Result<usize> {
+        self.with_retries(|timeout| self.transport.read_control(request_type, request, value, index, buf, timeout))
+    }
+
+    /// Run `transfer` with the policy's timeout, again after [`RETRY_DELAY`]
+    /// while it fails with a retryable error and retries are left.
+    fn with_retries<T>(&self, mut transfer: impl FnMut(Duration) -> rusb::Result<T>) -> rusb::Result<T> {
+        let policy = self.transfer_policy();
+        let mut attempt = 0;
+        loop {
+            match transfer(policy.timeout) {
+                Err(e) if attempt < policy.retries && is_retryable(e) => {
+                    attempt += 1;
+                    log::debug!("Control transfer failed ({}); retry {} of {}", e, attempt, policy.retries);
+                    std::thread::sleep(RETRY_DELAY);
+                }
+                result => return result,
+            }
+        }
+    }
+
     // --- High-level typed setters ---
     //
     // Each method constructs the correct UVC/HID payload internally and
@@ -72,7 +447,7 @@ impl ElgatoDevice {
     /// Supported on both 4K X and 4K S.
     pub fn set_hdmi_range(&self, range: EdidRangePolicy) -> Result<(), ElgatoError> {
         match self.model {
-            DeviceModel::Elgato4KX => self.set_uvc_setting(range.payload_4kx()),
+            DeviceModel::Elgato4KX => self.set_uvc_setting(&range.payload_4kx()),
             DeviceModel::Elgato4KS => self.send_hid_packet(&range.payload_4ks()),
         }
     }
@@ -82,7 +457,7 @@ impl ElgatoDevice {
     /// Supported on both 4K X and 4K S.
     pub fn set_edid_source(&self, source: EdidSource) -> Result<(), ElgatoError> {
         match self.model {
-            DeviceModel::Elgato4KX => self.set_uvc_setting(source.payload_4kx()),
+            DeviceModel::Elgato4KX => self.set_uvc_setting(&source.payload_4kx()),
             DeviceModel::Elgato4KS => self.send_hid_packet(&source.payload_4ks()),
         }
     }
@@ -92,7 +467,7 @@ impl ElgatoDevice {
     /// Supported on both 4K X and 4K S.
     pub fn set_hdr_mapping(&self, mode: HdrToneMapping) -> Result<(), ElgatoError> {
         match self.model {
-            DeviceModel::Elgato4KX => self.set_uvc_setting(mode.payload_4kx()),
+            DeviceModel::Elgato4KX => self.set_uvc_setting(&mode.payload_4kx()),
             DeviceModel::Elgato4KS => self.send_hid_packet(&mode.payload_4ks()),
         }
     }
@@ -107,7 +482,34 @@ impl ElgatoDevice {
                 model: "4K S",
             });
         }
-        self.set_uvc_setting(mode.payload_4kx())
+        self.set_uvc_setting(&mode.payload_4kx())
+    }
+
+    /// Select a custom EDID preset slot.
+    ///
+    /// **4K X only.** Returns [`ElgatoError::UnsupportedFeature`] on the 4K S.
+    /// Slot 0 is the default EDID (same as [`CustomEdidMode::Off`]).
+    pub fn set_custom_edid_preset(&self, preset: CustomEdidPreset) -> Result<(), ElgatoError> {
+        if self.model != DeviceModel::Elgato4KX {
+            return Err(ElgatoError::UnsupportedFeature {
+                feature: "Custom EDID",
+                model: "4K S",
+            });
+        }
+        self.set_uvc_setting(&preset.payload_4kx())
+    }
+
+    /// Offer the source one of the built-in EDID preset slots.
+    ///
+    /// **4K X only.** Returns [`ElgatoError::UnsupportedFeature`] on the 4K S.
+    pub fn set_input_limit(&self, limit: InputLimit) -> Result<(), ElgatoError> {
+        if self.model != DeviceModel::Elgato4KX {
+            return Err(ElgatoError::UnsupportedFeature {
+                feature: "Input limit",
+                model: "4K S",
+            });
+        }
+        self.set_custom_edid_preset(limit.preset())
     }
 
     /// Set the audio input source.
@@ -136,6 +538,55 @@ impl ElgatoDevice {
         self.send_hid_packet(&scaler.payload_4ks())
     }
 
+    // --- Idempotent setters ---
+    //
+    // Each `set_*_if_changed` reads the current value first and skips the
+    // write when it already matches, avoiding a needless re-negotiation
+    // that can briefly drop the signal.  When the value can't be read (not
+    // readable on this model, or an unrecognized byte) the write happens.
+    // They return whether a write was sent.
+
+    /// [`set_hdmi_range`](Self::set_hdmi_range), skipped if already set.
+    pub fn set_hdmi_range_if_changed(&self, range: EdidRangePolicy) -> Result<bool, ElgatoError> {
+        if !needs_write(&self.current_hdmi_range()?, &range) {
+            return Ok(false);
+        }
+        self.set_hdmi_range(range).map(|_| true)
+    }
+
+    /// [`set_edid_source`](Self::set_edid_source), skipped if already set.
+    /// Always writes on the 4K X, which can't report the source.
+    pub fn set_edid_source_if_changed(&self, source: EdidSource) -> Result<bool, ElgatoError> {
+        if !needs_write(&self.current_edid_source()?, &source) {
+            return Ok(false);
+        }
+        self.set_edid_source(source).map(|_| true)
+    }
+
+    /// [`set_hdr_mapping`](Self::set_hdr_mapping), skipped if already set.
+    pub fn set_hdr_mapping_if_changed(&self, mode: HdrToneMapping) -> Result<bool, ElgatoError> {
+        if !needs_write(&self.current_hdr_mapping()?, &mode) {
+            return Ok(false);
+        }
+        self.set_hdr_mapping(mode).map(|_| true)
+    }
+
+    /// [`set_audio_input`](Self::set_audio_input), skipped if already set.
+    pub fn set_audio_input_if_changed(&self, input: AudioInput) -> Result<bool, ElgatoError> {
+        if !needs_write(&self.current_audio_input()?, &input) {
+            return Ok(false);
+        }
+        self.set_audio_input(input).map(|_| true)
+    }
+
+    /// [`set_video_scaler`](Self::set_video_scaler), skipped if already set.
+    pub fn set_video_scaler_if_changed(&self, scaler: VideoScaler) -> Result<bool, ElgatoError> {
+        if !needs_write(&self.current_video_scaler()?, &scaler) {
+            return Ok(false);
+        }
+        self.set_video_scaler(scaler).map(|_| true)
+    }
+
     /// Set the USB speed mode.
     ///
     /// **4K X only.** Returns [`ElgatoError::UnsupportedFeature`] on the 4K S.
@@ -144,6 +595,9 @@ impl ElgatoDevice {
     ///
     /// The device will disconnect and re-enumerate with a different product ID
     /// after changing speed modes.
+    ///
+    /// Returns [`ElgatoError::Usb2Fallback`] if the device is currently on a
+    /// USB 2.0 link, since neither speed mode can be reached from there.
     pub fn set_usb_speed(&self, speed: UsbSpeed) -> Result<(), ElgatoError> {
         if self.model != DeviceModel::Elgato4KX {
             return Err(ElgatoError::UnsupportedFeature {
@@ -151,49 +605,518 @@ impl ElgatoDevice {
                 model: "4K S",
             });
         }
+        if self.is_degraded() {
+            return Err(ElgatoError::Usb2Fallback {
+                feature: "USB speed switching",
+            });
+        }
         let _ack = self.send_at_command(AT_CMD_SET_USB_SPEED, &speed.at_input())?;
         Ok(())
     }
 
-    fn find_device(context: &Context) -> Result<FoundDevice, ElgatoError> {
+    /// Apply every setting in a [`Bundle`], spaced by [`SETTING_APPLY_DELAY`].
+    ///
+    /// Settings the model doesn't support (the video scaler on the 4K X)
+    /// are skipped.  Stops at the first failed write.
+    pub fn apply_bundle(&self, bundle: Bundle) -> Result<(), ElgatoError> {
+        self.set_hdmi_range(bundle.hdmi_range())?;
+        std::thread::sleep(SETTING_APPLY_DELAY);
+        self.set_edid_source(bundle.edid_source())?;
+        std::thread::sleep(SETTING_APPLY_DELAY);
+        self.set_hdr_mapping(bundle.hdr_mapping())?;
+        if let Some(scaler) = bundle.video_scaler().filter(|_| self.model == DeviceModel::Elgato4KS) {
+            std::thread::sleep(SETTING_APPLY_DELAY);
+            self.set_video_scaler(scaler)?;
+        }
+        Ok(())
+    }
+
+    /// Write one [`SettingChange`] with the matching `set_*` method.
+    pub fn apply_change(&self, change: &SettingChange) -> Result<(), ElgatoError> {
+        match *change {
+            SettingChange::HdmiRange(range) => self.set_hdmi_range(range),
+            SettingChange::EdidSource(source) => self.set_edid_source(source),
+            SettingChange::HdrMapping(mode) => self.set_hdr_mapping(mode),
+            SettingChange::CustomEdid(mode) => self.set_custom_edid(mode),
+            SettingChange::CustomEdidPreset(preset) => self.set_custom_edid_preset(preset),
+            SettingChange::InputLimit(limit) => self.set_input_limit(limit),
+            SettingChange::Picture(control, value) => self.set_picture_control(control, value),
+            SettingChange::AudioInput(input) => self.set_audio_input(input),
+            SettingChange::VideoScaler(scaler) => self.set_video_scaler(scaler),
+            SettingChange::UsbSpeed(speed) => self.set_usb_speed(speed),
+        }
+    }
+
+    /// Apply every setting in a [`Profile`] in [`Profile::apply_order`],
+    /// spaced by [`SETTING_APPLY_DELAY`].
+    ///
+    /// Unlike [`apply_bundle`](Self::apply_bundle), nothing is skipped: a
+    /// setting the model doesn't support fails with
+    /// [`ElgatoError::UnsupportedFeature`].  Stops at the first failed write.
+    pub fn apply_profile(&self, profile: &Profile) -> Result<(), ElgatoError> {
+        for (i, change) in profile.apply_order().iter().enumerate() {
+            if i > 0 {
+                std::thread::sleep(SETTING_APPLY_DELAY);
+            }
+            self.apply_change(change)?;
+        }
+        Ok(())
+    }
+
+    /// The live value of every setting that can be read back and written
+    /// again: [`SettingChange::from_status`] plus, on the 4K X, the picture
+    /// controls.  Settings that can't be read are left out.
+    pub fn current_settings(&self) -> Result<Vec<SettingChange>, ElgatoError> {
+        let mut settings = SettingChange::from_status(&self.read_status()?);
+        if self.model == DeviceModel::Elgato4KX {
+            for control in PictureControl::ALL {
+                if let Ok(value) = self.picture_control(control) {
+                    settings.push(SettingChange::Picture(control, value));
+                }
+            }
+        }
+        Ok(settings)
+    }
+
+    // --- Raw escape hatch (feature `unsafe-raw`) ---
+
+    /// Send a raw settings write to the 4K S: `[06 06 06 55 02] [sub_cmd] [value]`.
+    ///
+    /// For sub-commands this crate doesn't wrap yet.  The packet is framed
+    /// and padded here; sub-commands known to reset or hang the MCU are
+    /// refused with [`ElgatoError::RawCommandRejected`].  Anything else is
+    /// sent as-is, so check docs/LOW_CONFIDENCE_COMMANDS.md first.
+    ///
+    /// **4K S only.**
+    #[cfg(feature = "unsafe-raw")]
+    pub fn set_raw_hid_setting(&self, sub_cmd: u8, value: u8) -> Result<(), ElgatoError> {
+        if self.model != DeviceModel::Elgato4KS {
+            return Err(ElgatoError::UnsupportedFeature {
+                feature: "Raw HID settings",
+                model: "4K X",
+            });
+        }
+        self.send_hid_packet(&crate::hid::raw_hid_packet(sub_cmd, value)?)
+    }
+
+    /// Send a raw setting write to the 4K X.
+    ///
+    /// `body` is the command without its framing, e.g. `[1f 00 00 00 01]`
+    /// for HDR tone mapping on.  The `a1` header, length indicator, and LRC
+    /// are added here, and the write uses the usual trigger + payload
+    /// sequence.
+    ///
+    /// **4K X only.**
+    #[cfg(feature = "unsafe-raw")]
+    pub fn set_raw_uvc_payload(&self, body: &[u8]) -> Result<(), ElgatoError> {
+        if self.model != DeviceModel::Elgato4KX {
+            return Err(ElgatoError::UnsupportedFeature {
+                feature: "Raw UVC payloads",
+                model: "4K S",
+            });
+        }
+        self.set_uvc_setting(&crate::uvc::raw_uvc_payload(body)?)
+    }
+
+    /// Send a raw HID output report to the 4K S, zero-padded to 255 bytes.
+    ///
+    /// Unlike [`set_raw_hid_setting`](Self::set_raw_hid_setting) the report
+    /// can be anything, header included.  Settings writes to sub-commands
+    /// known to reset or hang the MCU are still refused.
+    ///
+    /// **4K S only.**
+    #[cfg(feature = "unsafe-raw")]
+    pub fn send_raw_hid(&self, report: &[u8]) -> Result<(), ElgatoError> {
+        if self.model != DeviceModel::Elgato4KS {
+            return Err(ElgatoError::UnsupportedFeature {
+                feature: "Raw HID reports",
+                model: "4K X",
+            });
+        }
+        self.send_hid_packet(&crate::hid::raw_hid_report(report)?)
+    }
+
+    /// Send bytes to the 4K X exactly as given, with the usual trigger +
+    /// payload sequence.
+    ///
+    /// Unlike [`set_raw_uvc_payload`](Self::set_raw_uvc_payload) nothing is
+    /// framed: the `a1` header and LRC must already be there (see
+    /// [`raw_uvc_payload`](crate::raw_uvc_payload) and [`lrc`](crate::lrc)).
+    ///
+    /// **4K X only.**
+    #[cfg(feature = "unsafe-raw")]
+    pub fn send_raw_uvc(&self, payload: &[u8]) -> Result<(), ElgatoError> {
+        if self.model != DeviceModel::Elgato4KX {
+            return Err(ElgatoError::UnsupportedFeature {
+                feature: "Raw UVC payloads",
+                model: "4K S",
+            });
+        }
+        crate::uvc::check_raw_uvc_bytes(payload)?;
+        self.set_uvc_setting(payload)
+    }
+
+    // --- Picture controls (UVC Processing Unit) ---
+
+    /// Processing Unit ID for `control`, checking the device advertises it.
+    fn picture_unit(&self, control: PictureControl) -> Result<u8, ElgatoError> {
+        if self.model != DeviceModel::Elgato4KX {
+            return Err(ElgatoError::UnsupportedFeature {
+                feature: "Picture controls",
+                model: "4K S",
+            });
+        }
+        let (unit_id, controls) = self.processing_unit()?;
+        if controls & (1 << control.control_bit()) == 0 {
+            return Err(ElgatoError::UnsupportedFeature {
+                feature: control.name(),
+                model: "4K X",
+            });
+        }
+        Ok(unit_id)
+    }
+
+    /// Read the current value of a picture control.
+    ///
+    /// **4K X only.** Returns [`ElgatoError::UnsupportedFeature`] on the 4K S
+    /// or if the device does not advertise the control.
+    pub fn picture_control(&self, control: PictureControl) -> Result<i32, ElgatoError> {
+        let unit_id = self.picture_unit(control)?;
+        Ok(control.decode(self.get_pu_control(unit_id, control.selector(), UVC_GET_CUR)?))
+    }
+
+    /// Read the valid range, step, and default of a picture control.
+    ///
+    /// **4K X only.**
+    pub fn picture_control_range(&self, control: PictureControl) -> Result<ControlRange, ElgatoError> {
+        let unit_id = self.picture_unit(control)?;
+        let get = |request| -> Result<i32, ElgatoError> {
+            Ok(control.decode(self.get_pu_control(unit_id, control.selector(), request)?))
+        };
+        Ok(ControlRange {
+            min: get(UVC_GET_MIN)?,
+            max: get(UVC_GET_MAX)?,
+            step: get(UVC_GET_RES)?,
+            default: get(UVC_GET_DEF)?,
+        })
+    }
+
+    /// Set a picture control.
+    ///
+    /// **4K X only.** Returns [`ElgatoError::ValueOutOfRange`] if `value` is
+    /// outside the range reported by [`picture_control_range`](Self::picture_control_range).
+    pub fn set_picture_control(&self, control: PictureControl, value: i32) -> Result<(), ElgatoError> {
+        let range = self.picture_control_range(control)?;
+        if !range.contains(value) {
+            return Err(ElgatoError::ValueOutOfRange {
+                control: control.name(),
+                value,
+                min: range.min,
+                max: range.max,
+            });
+        }
+        let unit_id = self.picture_unit(control)?;
+        self.set_pu_control(unit_id, control.selector(), control.encode(value))
+    }
+
+    // --- Extension Unit capabilities ---
+
+    /// Query what the firmware reports about one Extension Unit selector.
+    ///
+    /// Only read requests are issued, so this is safe for exploring.
+    ///
+    /// **4K X only.** Returns [`ElgatoError::UnsupportedFeature`] on the 4K S.
+    pub fn xu_control_info(&self, selector: u8) -> Result<XuControlInfo, ElgatoError> {
+        if self.model != DeviceModel::Elgato4KX {
+            return Err(ElgatoError::UnsupportedFeature {
+                feature: "UVC Extension Unit queries",
+                model: "4K S",
+            });
+        }
+        self.query_xu_control(selector)
+    }
+
+    /// Query every selector the Extension Unit descriptor advertises.
+    ///
+    /// **4K X only.**
+    pub fn xu_controls(&self) -> Result<Vec<XuControlInfo>, ElgatoError> {
+        if self.model != DeviceModel::Elgato4KX {
+            return Err(ElgatoError::UnsupportedFeature {
+                feature: "UVC Extension Unit queries",
+                model: "4K S",
+            });
+        }
+        let controls = self.extension_unit_controls()?;
+        (0..64u8)
+            .filter(|bit| controls & (1 << bit) != 0)
+            .map(|bit| self.query_xu_control(bit + 1))
+            .collect()
+    }
+
+    /// Collect every supported device on the bus, in enumeration order.
+    pub(crate) fn find_devices(context: &Context) -> Result<Vec<FoundDevice>, ElgatoError> {
+        let mut found = Vec::new();
+
         for device in context.devices()?.iter() {
             let desc = match device.device_descriptor() {
                 Ok(d) => d,
                 Err(_) => continue,
             };
-            if desc.vendor_id() != VENDOR_ID {
-                continue;
+            let pid = desc.product_id();
+            if let Some(model) = model_for_ids(desc.vendor_id(), pid) {
+                found.push(FoundDevice { device, model, pid });
             }
+        }
 
-            let pid = desc.product_id();
+        Ok(found)
+    }
+}
 
-            for &(known_pid, _) in PIDS_4KX {
-                if pid == known_pid {
-                    return Ok(FoundDevice { device, model: DeviceModel::Elgato4KX, pid });
-                }
-            }
+/// Map a USB vendor/product ID pair to a supported device model.
+fn model_for_ids(vid: u16, pid: u16) -> Option<DeviceModel> {
+    if vid != VENDOR_ID {
+        return None;
+    }
+    if PIDS_4KX.iter().any(|&(known_pid, _)| pid == known_pid) {
+        Some(DeviceModel::Elgato4KX)
+    } else if PIDS_4KS.iter().any(|&(known_pid, _)| pid == known_pid) {
+        Some(DeviceModel::Elgato4KS)
+    } else {
+        None
+    }
+}
 
-            for &(known_pid, _) in PIDS_4KS {
-                if pid == known_pid {
-                    return Ok(FoundDevice { device, model: DeviceModel::Elgato4KS, pid });
-                }
-            }
-        }
+/// Whether a failed control transfer is worth sending again.
+fn is_retryable(error: rusb::Error) -> bool {
+    matches!(error, rusb::Error::Timeout | rusb::Error::Io | rusb::Error::Busy | rusb::Error::Interrupted)
+}
+
+/// The interface a model is controlled through: UVC on the 4K X, HID on the 4K S.
+fn interface_for(model: DeviceModel) -> u8 {
+    match model {
+        DeviceModel::Elgato4KX => UVC_INTERFACE as u8,
+        DeviceModel::Elgato4KS => HID_INTERFACE as u8,
+    }
+}
+
+/// Map the host-reported link speed to the speeds the cards can run at.
+fn link_speed_from(speed: rusb::Speed) -> Option<UsbSpeedStatus> {
+    match speed {
+        rusb::Speed::High => Some(UsbSpeedStatus::Usb2),
+        rusb::Speed::Super => Some(UsbSpeedStatus::FiveGbps),
+        rusb::Speed::SuperPlus => Some(UsbSpeedStatus::TenGbps),
+        _ => None,
+    }
+}
 
-        Err(ElgatoError::DeviceNotFound)
+/// Resolve a devnode or sysfs path to a `(bus_number, address)` pair.
+fn resolve_device_path(path: &Path) -> Result<(u8, u8), String> {
+    if path.starts_with("/sys") {
+        let read = |name: &str| -> Result<u8, String> {
+            std::fs::read_to_string(path.join(name))
+                .map_err(|e| format!("cannot read {}: {}", name, e))?
+                .trim()
+                .parse()
+                .map_err(|_| format!("{} is not a number", name))
+        };
+        return Ok((read("busnum")?, read("devnum")?));
+    }
+    parse_devnode_path(path)
+}
+
+/// Parse a usbfs devnode path of the form `.../BBB/DDD`.
+fn parse_devnode_path(path: &Path) -> Result<(u8, u8), String> {
+    let mut components = path.iter().rev().filter_map(|c| c.to_str());
+    let address = components.next();
+    let bus = components.next();
+    match (bus.and_then(|b| b.parse().ok()), address.and_then(|a| a.parse().ok())) {
+        (Some(bus), Some(address)) => Ok((bus, address)),
+        _ => Err("expected /dev/bus/usb/BBB/DDD or a /sys/bus/usb/devices entry".to_string()),
     }
 }
 
 impl Drop for ElgatoDevice {
     fn drop(&mut self) {
-        let interface_num = match self.model {
-            DeviceModel::Elgato4KX => UVC_INTERFACE,
-            DeviceModel::Elgato4KS => HID_INTERFACE,
-        };
+        self.transport.release(interface_for(self.model), self.driver_policy.reattach);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status::{Setting, StatusField};
+
+    #[test]
+    fn hardware_revision_from_version() {
+        let rev = HardwareRevision::from_version(rusb::Version::from_bcd(0x0102));
+        assert_eq!(rev.bcd_device, 0x0102);
+        assert_eq!(rev.to_string(), "1.02");
+
+        let rev = HardwareRevision::from_version(rusb::Version::from_bcd(0x1234));
+        assert_eq!(rev.bcd_device, 0x1234);
+        assert_eq!(rev.to_string(), "12.34");
+    }
+
+    #[test]
+    fn devnode_path_parsing() {
+        assert_eq!(parse_devnode_path(Path::new("/dev/bus/usb/003/012")), Ok((3, 12)));
+        assert_eq!(parse_devnode_path(Path::new("/dev/bus/usb/001/001/")), Ok((1, 1)));
+        assert!(parse_devnode_path(Path::new("/dev/video0")).is_err());
+        assert!(parse_devnode_path(Path::new("012")).is_err());
+    }
 
-        let _ = self.handle.release_interface(interface_num as u8);
+    #[test]
+    fn link_speed_mapping() {
+        assert_eq!(link_speed_from(rusb::Speed::SuperPlus), Some(UsbSpeedStatus::TenGbps));
+        assert_eq!(link_speed_from(rusb::Speed::Super), Some(UsbSpeedStatus::FiveGbps));
+        assert_eq!(link_speed_from(rusb::Speed::High), Some(UsbSpeedStatus::Usb2));
+        assert_eq!(link_speed_from(rusb::Speed::Full), None);
+        assert_eq!(link_speed_from(rusb::Speed::Unknown), None);
+    }
+
+    #[test]
+    fn model_lookup_by_ids() {
+        assert_eq!(model_for_ids(0x0fd9, 0x009c), Some(DeviceModel::Elgato4KX));
+        assert_eq!(model_for_ids(0x0fd9, 0x00af), Some(DeviceModel::Elgato4KS));
+        assert_eq!(model_for_ids(0x0fd9, 0x1234), None);
+        assert_eq!(model_for_ids(0x046d, 0x009c), None);
+    }
+
+    #[test]
+    fn only_transient_errors_are_retried() {
+        assert!(is_retryable(rusb::Error::Timeout));
+        assert!(is_retryable(rusb::Error::Io));
+        assert!(!is_retryable(rusb::Error::Pipe));
+        assert!(!is_retryable(rusb::Error::NoDevice));
+        assert!(!is_retryable(rusb::Error::Access));
+    }
+
+    #[test]
+    fn default_transfer_policy() {
+        assert_eq!(TransferPolicy::default(), TransferPolicy { timeout: USB_TIMEOUT, retries: 0 });
+    }
+
+    #[test]
+    fn kernel_driver_is_detached_and_reattached_by_default() {
+        assert_eq!(KernelDriverPolicy::default(), KernelDriverPolicy { detach: true, reattach: true });
+    }
+
+    // --- Mock transport ---
+
+    use std::sync::Arc;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Event {
+        Claim { interface: u8, detach: bool },
+        Write { request: u8, index: u16, data: Vec<u8> },
+        Release { interface: u8, reattach: bool },
+    }
+
+    /// Records what it's asked to do; the first `timeouts` writes time out.
+    #[derive(Default)]
+    struct MockTransport {
+        events: Arc<Mutex<Vec<Event>>>,
+        timeouts: Arc<Mutex<u32>>,
+    }
+
+    impl Transport for MockTransport {
+        fn write_control(&self, _: u8, request: u8, _: u16, index: u16, data: &[u8], _: Duration) -> rusb::Result<usize> {
+            let mut timeouts = self.timeouts.lock().unwrap();
+            if *timeouts > 0 {
+                *timeouts -= 1;
+                return Err(rusb::Error::Timeout);
+            }
+            self.events.lock().unwrap().push(Event::Write { request, index, data: data.to_vec() });
+            Ok(data.len())
+        }
+
+        fn read_control(&self, _: u8, _: u8, _: u16, _: u16, _: &mut [u8], _: Duration) -> rusb::Result<usize> {
+            Err(rusb::Error::Pipe)
+        }
+
+        fn claim(&mut self, interface: u8, detach: bool) -> Result<(), ElgatoError> {
+            self.events.lock().unwrap().push(Event::Claim { interface, detach });
+            Ok(())
+        }
+
+        fn release(&mut self, interface: u8, reattach: bool) {
+            self.events.lock().unwrap().push(Event::Release { interface, reattach });
+        }
+    }
+
+    fn detected(model: DeviceModel) -> DetectedDevice {
+        DetectedDevice {
+            model,
+            pid: 0x00af,
+            bus_number: 1,
+            address: 2,
+            revision: HardwareRevision::default(),
+            link_speed: None,
+            port_numbers: vec![],
+        }
+    }
+
+    #[test]
+    fn mock_transport_sees_claim_writes_and_release() {
+        let mock = MockTransport::default();
+        let events = Arc::clone(&mock.events);
+        let device = ElgatoDevice::with_transport(mock, &detected(DeviceModel::Elgato4KS)).unwrap();
+        device.set_hdr_mapping(HdrToneMapping::On).unwrap();
+        drop(device);
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0], Event::Claim { interface: HID_INTERFACE as u8, detach: true });
+        assert_eq!(
+            events[1],
+            Event::Write { request: HID_SET_REPORT, index: HID_INTERFACE, data: HdrToneMapping::On.payload_4ks().to_vec() }
+        );
+        assert_eq!(events[2], Event::Release { interface: HID_INTERFACE as u8, reattach: true });
+    }
+
+    #[test]
+    fn timed_out_writes_are_retried_through_the_transport() {
+        let mock = MockTransport::default();
+        let (events, timeouts) = (Arc::clone(&mock.events), Arc::clone(&mock.timeouts));
+        let device = ElgatoDevice::with_transport(mock, &detected(DeviceModel::Elgato4KS)).unwrap();
+        *timeouts.lock().unwrap() = 1;
+        assert!(matches!(device.set_hdr_mapping(HdrToneMapping::Off), Err(ElgatoError::HidTransfer(_))));
+
+        device.set_transfer_policy(TransferPolicy { retries: 2, ..TransferPolicy::default() });
+        *timeouts.lock().unwrap() = 2;
+        device.set_hdr_mapping(HdrToneMapping::Off).unwrap();
+        assert_eq!(events.lock().unwrap().iter().filter(|e| matches!(e, Event::Write { .. })).count(), 1);
+    }
+
+    #[test]
+    fn failed_4kx_read_is_an_error_not_unsupported() {
+        let device = ElgatoDevice::with_transport(MockTransport::default(), &detected(DeviceModel::Elgato4KX)).unwrap();
+        assert!(matches!(device.read_setting(Setting::HdrToneMapping), Err(ElgatoError::UvcTransfer(_))));
+        assert!(matches!(device.read_setting(Setting::HdmiColorRange), Err(ElgatoError::UvcTransfer(_))));
+        let status = device.read_status().unwrap();
+        assert!(matches!(status.hdr_tone_mapping, StatusField::ReadFailed(_)));
+    }
+
+    #[test]
+    fn readable_settings_follow_model() {
+        let x = ElgatoDevice::with_transport(MockTransport::default(), &detected(DeviceModel::Elgato4KX)).unwrap();
+        assert!(x.can_read(Setting::HdrToneMapping));
+        assert!(!x.can_read(Setting::EdidSource));
+        // The mock's PID isn't a 4K X speed-mode PID.
+        assert!(!x.can_read(Setting::UsbSpeed));
+        let s = ElgatoDevice::with_transport(MockTransport::default(), &detected(DeviceModel::Elgato4KS)).unwrap();
+        assert!(s.can_read(Setting::AudioInput));
+        assert!(s.can_read(Setting::FirmwareVersion));
+    }
 
-        // Best-effort reattach — will fail on platforms without kernel drivers
-        let _ = self.handle.attach_kernel_driver(interface_num as u8);
+    #[test]
+    fn descriptors_and_reset_default_to_unsupported() {
+        let mut device = ElgatoDevice::with_transport(MockTransport::default(), &detected(DeviceModel::Elgato4KX)).unwrap();
+        assert!(matches!(device.extension_unit_controls(), Err(ElgatoError::Usb(rusb::Error::NotSupported))));
+        assert_eq!(device.transport.reset(), Err(rusb::Error::NotSupported));
     }
 }
@@ -0,0 +1,216 @@
+//! [`ElgatoDeviceBuilder`]: choose which card to open and how.
+//!
+//! [`ElgatoDevice::open`] takes the first card on the bus with the default
+//! timeout and detaches the kernel driver.  The builder picks a card by
+//! serial number, bus or model when several are connected, and sets the
+//! [`TransferPolicy`] and kernel driver handling before the interface is
+//! claimed.
+
+use std::time::Duration;
+
+use rusb::{Context, DeviceHandle};
+
+use crate::device::{ElgatoDevice, FoundDevice, KernelDriverPolicy, TransferPolicy};
+use crate::device_set::DeviceResult;
+use crate::error::ElgatoError;
+use crate::settings::DeviceModel;
+
+/// Options for opening an [`ElgatoDevice`], from [`ElgatoDevice::builder`].
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use elgato4k_linux::{DeviceModel, ElgatoDevice};
+///
+/// let device = ElgatoDevice::builder()
+///     .model(DeviceModel::Elgato4KX)
+///     .timeout(Duration::from_secs(3))
+///     .retries(2)
+///     .detach_kernel_driver(false)
+///     .open()?;
+/// # Ok::<(), elgato4k_linux::ElgatoError>(())
+/// ```
+///
+/// There is no verbosity setting: the library logs through the
+/// [`log`](https://docs.rs/log) crate, and the application's logger decides
+/// what is shown.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ElgatoDeviceBuilder {
+    serial: Option<String>,
+    bus_number: Option<u8>,
+    model: Option<DeviceModel>,
+    transfer: TransferPolicy,
+    driver: KernelDriverPolicy,
+}
+
+impl ElgatoDeviceBuilder {
+    /// Only open the card with this USB serial number, as `lsusb -v` shows
+    /// it (`iSerial`).  Each candidate is opened to read it.
+    pub fn serial(mut self, serial: impl Into<String>) -> Self {
+        self.serial = Some(serial.into());
+        self
+    }
+
+    /// Only open cards on this USB bus.
+    pub fn bus(mut self, bus_number: u8) -> Self {
+        self.bus_number = Some(bus_number);
+        self
+    }
+
+    /// Only open cards of this model.
+    pub fn model(mut self, model: DeviceModel) -> Self {
+        self.model = Some(model);
+        self
+    }
+
+    /// Time each control transfer may take.  Defaults to one second.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.transfer.timeout = timeout;
+        self
+    }
+
+    /// Retry a transfer that timed out or hit an I/O error up to `retries`
+    /// times.  Defaults to none.  See [`TransferPolicy::retries`].
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.transfer.retries = retries;
+        self
+    }
+
+    /// Whether to detach a kernel driver bound to the card's interface
+    /// (`uvcvideo` on the 4K X, `usbhid` on the 4K S).  Detaching
+    /// `uvcvideo` interrupts any program capturing from the card.  When
+    /// off, opening fails with [`ElgatoError::KernelDriverActive`] instead.
+    /// Defaults to on.
+    pub fn detach_kernel_driver(mut self, detach: bool) -> Self {
+        self.driver.detach = detach;
+        self
+    }
+
+    /// Whether to bind the kernel driver again when the device is dropped.
+    /// When off, the interface stays without a driver until the card is
+    /// replugged.  Defaults to on.
+    pub fn reattach_kernel_driver(mut self, reattach: bool) -> Self {
+        self.driver.reattach = reattach;
+        self
+    }
+
+    /// Open the first matching card and claim its interface.
+    ///
+    /// Returns [`ElgatoError::DeviceNotFound`] if no card matches.  With a
+    /// [`serial`](Self::serial) filter, cards that can't be opened are
+    /// skipped, since the requested card may come later; the first such
+    /// error is returned only if no card matches.
+    pub fn open(&self) -> Result<ElgatoDevice, ElgatoError> {
+        let context = Context::new()?;
+        let mut first_error = None;
+        for found in ElgatoDevice::find_devices(&context)? {
+            let handle = match self.open_if_matching(&found) {
+                Ok(Some(handle)) => handle,
+                Ok(None) => continue,
+                Err(e) if self.serial.is_some() => {
+                    log::debug!("Skipping a card whose serial number can't be checked: {}", e);
+                    first_error.get_or_insert(e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            return self.claim(handle, &found);
+        }
+        Err(first_error.unwrap_or(ElgatoError::DeviceNotFound))
+    }
+
+    /// Open every matching card, in bus enumeration order, with one
+    /// [`DeviceResult`] per card: a card that can't be opened or claimed
+    /// doesn't stop the others.
+    ///
+    /// Returns [`ElgatoError::DeviceNotFound`] if no card matches.  With a
+    /// [`serial`](Self::serial) filter, a card that can't be opened is
+    /// reported, since its serial number couldn't be checked.
+    pub fn open_all(&self) -> Result<Vec<DeviceResult<ElgatoDevice>>, ElgatoError> {
+        let context = Context::new()?;
+        let mut cards = Vec::new();
+        for found in ElgatoDevice::find_devices(&context)? {
+            let (model, pid) = (found.model, found.pid);
+            let (bus_number, address) = (found.device.bus_number(), found.device.address());
+            let result = match self.open_if_matching(&found) {
+                Ok(Some(handle)) => self.claim(handle, &found),
+                Ok(None) => continue,
+                Err(e) => Err(e),
+            };
+            cards.push(DeviceResult { model, pid, bus_number, address, result });
+        }
+        if cards.is_empty() {
+            return Err(ElgatoError::DeviceNotFound);
+        }
+        Ok(cards)
+    }
+
+    /// Whether a card matches the filters that need no open handle.
+    fn matches(&self, model: DeviceModel, bus_number: u8) -> bool {
+        self.model.is_none_or(|m| m == model) && self.bus_number.is_none_or(|b| b == bus_number)
+    }
+
+    /// Open `found` if it matches, checking the serial number once it's open.
+    /// The interface isn't claimed yet.
+    fn open_if_matching(&self, found: &FoundDevice) -> Result<Option<DeviceHandle<Context>>, ElgatoError> {
+        if !self.matches(found.model, found.device.bus_number()) {
+            return Ok(None);
+        }
+        let handle = found.device.open()?;
+        if let Some(serial) = &self.serial {
+            if read_serial(&handle, found).as_deref() != Some(serial.as_str()) {
+                return Ok(None);
+            }
+        }
+        Ok(Some(handle))
+    }
+
+    /// Claim a card opened by [`open_if_matching`](Self::open_if_matching).
+    fn claim(&self, handle: DeviceHandle<Context>, found: &FoundDevice) -> Result<ElgatoDevice, ElgatoError> {
+        let device = ElgatoDevice::claim(handle, &found.device, found.model, found.pid, self.driver)?;
+        device.set_transfer_policy(self.transfer);
+        Ok(device)
+    }
+}
+
+/// The card's serial number string, if it has one and it can be read.
+fn read_serial(handle: &DeviceHandle<Context>, found: &FoundDevice) -> Option<String> {
+    let descriptor = found.device.device_descriptor().ok()?;
+    handle.read_serial_number_string_ascii(&descriptor).ok()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_plain_open() {
+        let builder = ElgatoDevice::builder();
+        assert_eq!(builder.transfer, TransferPolicy::default());
+        assert_eq!(builder.driver, KernelDriverPolicy { detach: true, reattach: true });
+        assert!(builder.matches(DeviceModel::Elgato4KX, 1));
+        assert!(builder.matches(DeviceModel::Elgato4KS, 4));
+    }
+
+    #[test]
+    fn model_and_bus_filters() {
+        let builder = ElgatoDevice::builder().model(DeviceModel::Elgato4KS).bus(3);
+        assert!(builder.matches(DeviceModel::Elgato4KS, 3));
+        assert!(!builder.matches(DeviceModel::Elgato4KX, 3));
+        assert!(!builder.matches(DeviceModel::Elgato4KS, 2));
+    }
+
+    #[test]
+    fn transfer_and_driver_settings() {
+        let builder = ElgatoDevice::builder()
+            .timeout(Duration::from_secs(3))
+            .retries(2)
+            .detach_kernel_driver(false)
+            .reattach_kernel_driver(false);
+        assert_eq!(builder.transfer, TransferPolicy { timeout: Duration::from_secs(3), retries: 2 });
+        assert_eq!(builder.driver, KernelDriverPolicy { detach: false, reattach: false });
+    }
+}
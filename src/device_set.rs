@@ -0,0 +1,201 @@
+//! Fan-out control of several capture cards at once.
+//!
+//! [`ElgatoDeviceSet`] opens every supported card on the bus and runs the same
+//! operation against each of them, collecting one [`DeviceResult`] per card
+//! instead of stopping at the first failure.  A card that couldn't be opened
+//! stays in the set and reports its open error from every operation.
+//! Consecutive operations on the set are spaced by [`SETTING_APPLY_DELAY`]
+//! so callers don't have to sleep between setting changes themselves.
+
+use std::cell::Cell;
+use std::time::Instant;
+
+use crate::device::ElgatoDevice;
+use crate::error::ElgatoError;
+use crate::protocol::SETTING_APPLY_DELAY;
+use crate::settings::DeviceModel;
+use crate::status::DeviceStatus;
+
+/// Outcome of an operation on one device in an [`ElgatoDeviceSet`].
+#[derive(Debug)]
+pub struct DeviceResult<T> {
+    /// Model of the device the operation ran on.
+    pub model: DeviceModel,
+    /// USB product ID of the device.
+    pub pid: u16,
+    /// USB bus number of the device.
+    pub bus_number: u8,
+    /// Address of the device on its bus.
+    pub address: u8,
+    /// The per-device result.
+    pub result: Result<T, ElgatoError>,
+}
+
+impl<T> DeviceResult<T> {
+    /// Run `op` on the value of a successful result, keeping the device
+    /// fields; a failed result passes its error through.
+    pub fn and_then<U>(self, op: impl FnOnce(T) -> Result<U, ElgatoError>) -> DeviceResult<U> {
+        DeviceResult {
+            model: self.model,
+            pid: self.pid,
+            bus_number: self.bus_number,
+            address: self.address,
+            result: self.result.and_then(op),
+        }
+    }
+}
+
+/// A group of opened Elgato capture cards controlled together.
+///
+/// Each card keeps its interface claimed for the lifetime of the set and is
+/// released (with the kernel driver reattached) when the set is dropped.
+pub struct ElgatoDeviceSet {
+    cards: Vec<DeviceResult<ElgatoDevice>>,
+    last_apply: Cell<Option<Instant>>,
+}
+
+impl ElgatoDeviceSet {
+    /// Open every supported device on the bus.
+    ///
+    /// Returns [`ElgatoError::DeviceNotFound`] if no device is connected.  A
+    /// card that fails to open is kept in the set with its error; see
+    /// [`ElgatoDevice::open_all`].
+    pub fn open() -> Result<Self, ElgatoError> {
+        Ok(Self::from_results(ElgatoDevice::open_all()?))
+    }
+
+    /// Build a set from devices that were already opened.
+    pub fn from_devices(devices: Vec<ElgatoDevice>) -> Self {
+        let cards = devices
+            .into_iter()
+            .map(|device| DeviceResult {
+                model: device.model(),
+                pid: device.pid(),
+                bus_number: device.bus_number(),
+                address: device.address(),
+                result: Ok(device),
+            })
+            .collect();
+        Self::from_results(cards)
+    }
+
+    /// Build a set from the outcome of opening each card, as
+    /// [`ElgatoDevice::open_all`] returns it.
+    pub fn from_results(cards: Vec<DeviceResult<ElgatoDevice>>) -> Self {
+        Self {
+            cards,
+            last_apply: Cell::new(None),
+        }
+    }
+
+    /// The opened devices in this set, in bus enumeration order.
+    pub fn devices(&self) -> impl Iterator<Item = &ElgatoDevice> {
+        self.cards.iter().filter_map(|card| card.result.as_ref().ok())
+    }
+
+    /// Number of cards in the set, including any that failed to open.
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Whether the set contains no cards.
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Run `op` against every device and collect the per-device results.
+    /// A card that failed to open gets its open error instead.
+    ///
+    /// If the previous call to `apply` finished less than
+    /// [`SETTING_APPLY_DELAY`] ago, this waits out the remainder first so
+    /// back-to-back setting changes are paced the same way the CLI paces them.
+    ///
+    /// ```no_run
+    /// use elgato4k_linux::{ElgatoDeviceSet, HdrToneMapping};
+    ///
+    /// let set = ElgatoDeviceSet::open()?;
+    /// for r in set.apply(|d| d.set_hdr_mapping(HdrToneMapping::Off)) {
+    ///     if let Err(e) = r.result {
+    ///         eprintln!("{} (PID 0x{:04x}): {}", r.model, r.pid, e);
+    ///     }
+    /// }
+    /// # Ok::<(), elgato4k_linux::ElgatoError>(())
+    /// ```
+    pub fn apply<T, F>(&self, op: F) -> Vec<DeviceResult<T>>
+    where
+        F: Fn(&ElgatoDevice) -> Result<T, ElgatoError>,
+    {
+        if let Some(last) = self.last_apply.get() {
+            let elapsed = last.elapsed();
+            if elapsed < SETTING_APPLY_DELAY {
+                std::thread::sleep(SETTING_APPLY_DELAY - elapsed);
+            }
+        }
+
+        let results = self
+            .cards
+            .iter()
+            .map(|card| DeviceResult {
+                model: card.model,
+                pid: card.pid,
+                bus_number: card.bus_number,
+                address: card.address,
+                result: card.result.as_ref().map_err(Clone::clone).and_then(&op),
+            })
+            .collect();
+
+        self.last_apply.set(Some(Instant::now()));
+        results
+    }
+
+    /// Read the status of every device in the set.
+    pub fn read_status(&self) -> Vec<DeviceResult<DeviceStatus>> {
+        self.apply(ElgatoDevice::read_status)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_set_applies_nothing() {
+        let set = ElgatoDeviceSet::from_devices(Vec::new());
+        assert!(set.is_empty());
+        assert_eq!(set.len(), 0);
+        let results = set.apply(|_| Ok(()));
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn cards_that_failed_to_open_keep_reporting_their_error() {
+        let failed = DeviceResult {
+            model: DeviceModel::Elgato4KX,
+            pid: 0x009c,
+            bus_number: 2,
+            address: 5,
+            result: Err(ElgatoError::Usb(rusb::Error::Access)),
+        };
+        let set = ElgatoDeviceSet::from_results(vec![failed]);
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.devices().count(), 0);
+        for _ in 0..2 {
+            let results = set.apply(|_| Ok(()));
+            assert_eq!(results.len(), 1);
+            assert_eq!((results[0].bus_number, results[0].address), (2, 5));
+            assert!(matches!(results[0].result, Err(ElgatoError::Usb(rusb::Error::Access))));
+        }
+    }
+
+    #[test]
+    fn and_then_keeps_device_fields() {
+        let result = DeviceResult { model: DeviceModel::Elgato4KS, pid: 0x00af, bus_number: 1, address: 3, result: Ok(2) };
+        let doubled = result.and_then(|n| Ok(n * 2));
+        assert_eq!((doubled.pid, doubled.address), (0x00af, 3));
+        assert_eq!(doubled.result.unwrap(), 4);
+    }
+}
@@ -0,0 +1,438 @@
+//! Setup diagnostics behind `elgato4k-linux doctor`.
+//!
+//! [`diagnose`] works from what the host already knows about each card —
+//! cached descriptors, sysfs, usbfs permissions, `/proc` — and only opens
+//! the device for the final firmware read, so it still has something to say
+//! when the tool itself can't get at the card.  That read is skipped on a
+//! 4K X that is capturing, since opening it detaches `uvcvideo`.  Every
+//! check that doesn't pass carries a suggested fix.
+
+use std::fmt;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use crate::device::{DetectedDevice, ElgatoDevice};
+use crate::error::ElgatoError;
+use crate::protocol::*;
+use crate::settings::DeviceModel;
+use crate::status::UsbSpeedStatus;
+
+const SYSFS_USB_DEVICES: &str = "/sys/bus/usb/devices";
+
+/// How a [`Check`] came out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum CheckStatus {
+    Pass,
+    /// Works, but something is likely to bite later.
+    Warn,
+    /// Something that stops the card or the tool from working.
+    Fail,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Pass => "ok",
+            Self::Warn => "warn",
+            Self::Fail => "FAIL",
+        })
+    }
+}
+
+/// One diagnostic result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Check {
+    /// Which card the check is about, e.g. `4K X at bus 2 address 5`, or
+    /// `None` for host-wide checks.
+    pub device: Option<String>,
+    /// Short name, e.g. `permissions`.
+    pub name: String,
+    pub status: CheckStatus,
+    /// What was found.
+    pub detail: String,
+    /// What to do about it, for anything but [`CheckStatus::Pass`].
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn new(name: &str, status: CheckStatus, detail: impl Into<String>, fix: Option<String>) -> Self {
+        Self { device: None, name: name.to_string(), status, detail: detail.into(), fix }
+    }
+
+    fn pass(name: &str, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Pass, detail, None)
+    }
+
+    fn warn(name: &str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Warn, detail, Some(fix.into()))
+    }
+
+    fn fail(name: &str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Fail, detail, Some(fix.into()))
+    }
+}
+
+/// Run every check against every connected card.
+///
+/// Never fails: problems, including a missing card or a broken libusb,
+/// are reported as failed checks.
+pub fn diagnose() -> Vec<Check> {
+    let devices = match ElgatoDevice::detect_all() {
+        Ok(devices) => devices,
+        Err(e) => {
+            return vec![Check::fail(
+                "usb",
+                format!("cannot scan the USB bus: {}", e),
+                "Check that libusb is installed and /dev/bus/usb is mounted",
+            )];
+        }
+    };
+    if devices.is_empty() {
+        return vec![Check::fail(
+            "device",
+            "no Elgato 4K X or 4K S on the USB bus",
+            "Check the cable and run `lsusb | grep 0fd9`.  A 4K X in 10Gbps mode (PID 009b) may not \
+             enumerate on older kernels; see \"Note on 10Gbps Mode\" in the README",
+        )];
+    }
+
+    let mut checks = Vec::new();
+    for device in &devices {
+        let label = format!("{} at bus {} address {}", device.model, device.bus_number, device.address);
+        let start = checks.len();
+        checks.extend(device_checks(device));
+        for check in &mut checks[start..] {
+            check.device = Some(label.clone());
+        }
+    }
+    checks
+}
+
+/// The checks for one card, in the order a user would fix them.
+fn device_checks(device: &DetectedDevice) -> Vec<Check> {
+    let mode = PIDS_4KX
+        .iter()
+        .chain(PIDS_4KS)
+        .find(|(pid, _)| *pid == device.pid)
+        .map_or("unknown mode", |(_, mode)| mode);
+    let mut checks = vec![
+        Check::pass(
+            "device",
+            format!("{} (PID 0x{:04x}, {}), revision {}", device.model, device.pid, mode, device.revision),
+        ),
+        link_check(device.model, device.pid, device.link_speed),
+    ];
+
+    let interfaces = sysfs_name(device.bus_number, &device.port_numbers).map(|name| interfaces(&name));
+    checks.push(driver_check(device, interfaces.as_deref()));
+    let capture = interfaces.as_deref().and_then(capture_check);
+    let capturing = capture.as_ref().is_some_and(|check| check.status != CheckStatus::Pass);
+    checks.extend(capture);
+
+    let devnode = PathBuf::from(format!("/dev/bus/usb/{:03}/{:03}", device.bus_number, device.address));
+    let access = permission_check(&devnode);
+    let accessible = access.status == CheckStatus::Pass;
+    checks.push(access);
+    if accessible {
+        checks.push(match firmware_skipped(device.model, capturing) {
+            Some(check) => check,
+            None => firmware_check(&devnode, device.model),
+        });
+    }
+    checks
+}
+
+/// USB 2.0 fallback, or a 10Gbps-mode 4K X on a slower link.
+fn link_check(model: DeviceModel, pid: u16, link_speed: Option<UsbSpeedStatus>) -> Check {
+    if PIDS_USB2_FALLBACK.contains(&pid) {
+        return Check::fail(
+            "usb link",
+            "USB 2.0 fallback: the card can't capture video over this link",
+            "Use the supplied USB-C cable and plug straight into a USB 3 port (not a hub), then replug",
+        );
+    }
+    match (model, pid, link_speed) {
+        (DeviceModel::Elgato4KX, 0x009b, Some(UsbSpeedStatus::FiveGbps)) => Check::warn(
+            "usb link",
+            "10Gbps mode, but the port only negotiated 5Gbps",
+            "Use a USB 3.2 Gen 2 port, or switch modes with `elgato4k-linux --usb-speed 5g`",
+        ),
+        (_, _, Some(speed)) => Check::pass("usb link", speed.to_string()),
+        (_, _, None) => Check::pass("usb link", "negotiated speed not reported by the host"),
+    }
+}
+
+/// Whether the control interface and `uvcvideo` have their drivers.
+fn driver_check(device: &DetectedDevice, interfaces: Option<&[Interface]>) -> Check {
+    let Some(interfaces) = interfaces.filter(|i| !i.is_empty()) else {
+        return Check::warn(
+            "kernel driver",
+            "cannot read the card's interfaces from sysfs",
+            format!("Check that {} is mounted", SYSFS_USB_DEVICES),
+        );
+    };
+
+    let control = match device.model {
+        DeviceModel::Elgato4KX => UVC_INTERFACE,
+        DeviceModel::Elgato4KS => HID_INTERFACE,
+    } as u8;
+    let uvc: Vec<String> = interfaces
+        .iter()
+        .filter(|i| i.driver.as_deref() == Some("uvcvideo"))
+        .map(|i| i.number.to_string())
+        .collect();
+
+    if uvc.is_empty() {
+        let mut fix = "Run `sudo modprobe uvcvideo`, then replug the card".to_string();
+        if device.pid == 0x009b {
+            fix.push_str(
+                ".  Kernels without the 10Gbps quirk need `usbcore.quirks=0fd9:009b:o`, \
+                 or switch to 5Gbps with `elgato4k-linux --usb-speed 5g`",
+            );
+        }
+        return Check::fail("kernel driver", "uvcvideo isn't bound, so there is no /dev/video device", fix);
+    }
+    if interfaces.iter().any(|i| i.number == control && i.driver.is_none()) {
+        return Check::warn(
+            "kernel driver",
+            format!("no driver on interface {}; an interrupted run may have left it detached", control),
+            "Unplug and replug the card",
+        );
+    }
+    Check::pass("kernel driver", format!("uvcvideo on interface {}", uvc.join(", ")))
+}
+
+/// Which `/dev/video*` nodes the card has and who holds them open.
+fn capture_check(interfaces: &[Interface]) -> Option<Check> {
+    let nodes: Vec<PathBuf> = interfaces.iter().flat_map(|i| i.video_nodes.iter().cloned()).collect();
+    if nodes.is_empty() {
+        return None;
+    }
+    let names = nodes.iter().map(|n| n.display().to_string()).collect::<Vec<_>>().join(", ");
+    let users = processes_using(&nodes);
+    if users.is_empty() {
+        let mut detail = format!("{} not in use", names);
+        if !is_root() {
+            detail.push_str(" (other users' programs are only visible with sudo)");
+        }
+        return Some(Check::pass("video capture", detail));
+    }
+    let users = users.iter().map(|(pid, name)| format!("{} (pid {})", name, pid)).collect::<Vec<_>>().join(", ");
+    Some(Check::warn(
+        "video capture",
+        format!("{} is open in {}", names, users),
+        "Settings apply while capturing, but --usb-speed re-enumerates the card and ends the stream; \
+         close those programs first",
+    ))
+}
+
+/// Whether this process may open the usbfs devnode read-write.
+fn permission_check(devnode: &Path) -> Check {
+    match std::fs::OpenOptions::new().read(true).write(true).open(devnode) {
+        Ok(_) => Check::pass("permissions", format!("{} is writable", devnode.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => Check::fail(
+            "permissions",
+            format!("cannot open {}: permission denied", devnode.display()),
+            "Run with sudo, or install the udev rules with `sudo elgato4k-linux udev --install`",
+        ),
+        Err(e) => Check::warn(
+            "permissions",
+            format!("cannot open {}: {}", devnode.display(), e),
+            "Replug the card and run doctor again",
+        ),
+    }
+}
+
+/// Why the firmware read is skipped, if it is.  Opening a 4K X detaches
+/// `uvcvideo` from its control interface, which ends a running capture; a
+/// diagnostic shouldn't break the setup it is checking.
+fn firmware_skipped(model: DeviceModel, capturing: bool) -> Option<Check> {
+    (capturing && model == DeviceModel::Elgato4KX).then(|| {
+        Check::warn(
+            "firmware",
+            "not read: opening the card would detach uvcvideo and end the capture",
+            "Close the programs using the card and run doctor again",
+        )
+    })
+}
+
+/// Open the card and read its firmware version.
+fn firmware_check(devnode: &Path, model: DeviceModel) -> Check {
+    let result = ElgatoDevice::open_path(devnode).and_then(|device| device.read_firmware_version());
+    match result {
+        Ok(Some(version)) => Check::pass("firmware", version.to_string()),
+        Ok(None) => Check::warn(
+            "firmware",
+            "the card answered, but not with a firmware version",
+            "Replug the card; if it persists, run with -vv and include the output in a bug report",
+        ),
+        Err(ElgatoError::Usb(rusb::Error::Busy)) => Check::fail(
+            "firmware",
+            "the control interface is claimed by another program",
+            format!(
+                "Close other tools talking to the {} (another elgato4k-linux, the Windows app in a VM)",
+                model
+            ),
+        ),
+        Err(e) => Check::fail(
+            "firmware",
+            format!("cannot talk to the card: {}", e),
+            "Replug the card; if it persists, run with -vv and include the output in a bug report",
+        ),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Host inspection
+// ---------------------------------------------------------------------------
+
+/// One interface of a card, from sysfs.
+#[derive(Debug, Default)]
+struct Interface {
+    number: u8,
+    driver: Option<String>,
+    video_nodes: Vec<PathBuf>,
+}
+
+/// The sysfs name of a device, e.g. `2-1.4` for bus 2, port 1, hub port 4.
+/// Root hubs (no ports) have no such name.
+fn sysfs_name(bus_number: u8, port_numbers: &[u8]) -> Option<String> {
+    if port_numbers.is_empty() {
+        return None;
+    }
+    let ports: Vec<String> = port_numbers.iter().map(u8::to_string).collect();
+    Some(format!("{}-{}", bus_number, ports.join(".")))
+}
+
+/// The interfaces under `/sys/bus/usb/devices/<name>:<config>.<n>`.
+fn interfaces(name: &str) -> Vec<Interface> {
+    let Ok(entries) = std::fs::read_dir(SYSFS_USB_DEVICES) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}:", name);
+    let mut found: Vec<Interface> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+        .filter_map(|entry| {
+            let path = entry.path();
+            let number = std::fs::read_to_string(path.join("bInterfaceNumber")).ok()?;
+            let driver = std::fs::read_link(path.join("driver"))
+                .ok()
+                .and_then(|link| link.file_name().map(|n| n.to_string_lossy().into_owned()));
+            let video_nodes = std::fs::read_dir(path.join("video4linux"))
+                .map(|nodes| nodes.filter_map(Result::ok).map(|n| Path::new("/dev").join(n.file_name())).collect())
+                .unwrap_or_default();
+            Some(Interface { number: u8::from_str_radix(number.trim(), 16).ok()?, driver, video_nodes })
+        })
+        .collect();
+    found.sort_by_key(|i| i.number);
+    found
+}
+
+/// `(pid, command name)` of every visible process with one of `nodes` open.
+fn processes_using(nodes: &[PathBuf]) -> Vec<(u32, String)> {
+    let Ok(processes) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    processes
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            let fds = std::fs::read_dir(entry.path().join("fd")).ok()?;
+            let holds = fds.filter_map(Result::ok).any(|fd| {
+                std::fs::read_link(fd.path()).is_ok_and(|target| nodes.contains(&target))
+            });
+            if !holds {
+                return None;
+            }
+            let name = std::fs::read_to_string(entry.path().join("comm")).unwrap_or_default();
+            Some((pid, name.trim().to_string()))
+        })
+        .collect()
+}
+
+fn is_root() -> bool {
+    std::fs::metadata("/proc/self").is_ok_and(|m| m.uid() == 0)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detected(model: DeviceModel, pid: u16) -> DetectedDevice {
+        DetectedDevice {
+            model,
+            pid,
+            bus_number: 2,
+            address: 5,
+            revision: Default::default(),
+            link_speed: None,
+            port_numbers: vec![1],
+        }
+    }
+
+    fn interface(number: u8, driver: Option<&str>) -> Interface {
+        Interface { number, driver: driver.map(str::to_string), ..Default::default() }
+    }
+
+    #[test]
+    fn sysfs_names_follow_port_path() {
+        assert_eq!(sysfs_name(2, &[1, 4]).as_deref(), Some("2-1.4"));
+        assert_eq!(sysfs_name(3, &[2]).as_deref(), Some("3-2"));
+        assert_eq!(sysfs_name(1, &[]), None);
+    }
+
+    #[test]
+    fn fallback_link_fails() {
+        let check = link_check(DeviceModel::Elgato4KX, 0x009d, Some(UsbSpeedStatus::Usb2));
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.fix.unwrap().contains("USB 3 port"));
+    }
+
+    #[test]
+    fn slow_link_in_10g_mode_warns() {
+        let check = link_check(DeviceModel::Elgato4KX, 0x009b, Some(UsbSpeedStatus::FiveGbps));
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert_eq!(link_check(DeviceModel::Elgato4KX, 0x009c, Some(UsbSpeedStatus::FiveGbps)).status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn missing_uvcvideo_fails_with_quirk_hint_in_10g_mode() {
+        let interfaces = [interface(0, None), interface(1, None)];
+        let check = driver_check(&detected(DeviceModel::Elgato4KX, 0x009b), Some(&interfaces));
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.fix.unwrap().contains("0fd9:009b:o"));
+    }
+
+    #[test]
+    fn detached_control_interface_warns() {
+        let interfaces = [interface(0, None), interface(1, Some("uvcvideo"))];
+        let check = driver_check(&detected(DeviceModel::Elgato4KX, 0x009c), Some(&interfaces));
+        assert_eq!(check.status, CheckStatus::Warn);
+
+        let interfaces = [interface(0, Some("uvcvideo")), interface(1, Some("uvcvideo"))];
+        let check = driver_check(&detected(DeviceModel::Elgato4KX, 0x009c), Some(&interfaces));
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert_eq!(check.detail, "uvcvideo on interface 0, 1");
+    }
+
+    #[test]
+    fn no_video_nodes_means_no_capture_check() {
+        assert!(capture_check(&[interface(0, Some("uvcvideo"))]).is_none());
+    }
+
+    #[test]
+    fn firmware_read_is_skipped_while_a_4kx_captures() {
+        assert_eq!(firmware_skipped(DeviceModel::Elgato4KX, true).unwrap().status, CheckStatus::Warn);
+        assert!(firmware_skipped(DeviceModel::Elgato4KX, false).is_none());
+        // The 4K S is controlled over HID, which capture doesn't use.
+        assert!(firmware_skipped(DeviceModel::Elgato4KS, true).is_none());
+    }
+}
@@ -0,0 +1,241 @@
+//! Dry runs: the control transfers a [`SettingChange`] would send, worked
+//! out from the payload builders without a device.
+//!
+//! Only the writes are listed.  Reads that follow a write (the AT command
+//! ACK after a USB speed change) are mentioned in the meaning text.
+
+use std::fmt;
+
+use crate::error::ElgatoError;
+use crate::profile::SettingChange;
+use crate::protocol::*;
+use crate::settings::{DeviceModel, PictureControl};
+use crate::uvc::{describe_uvc_frame, frame_at_command};
+
+/// One control transfer a write would send.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlannedTransfer {
+    /// The request, e.g. `UVC SET_CUR XU 4 selector 0x01`.
+    pub request: String,
+    /// The data stage.  HID reports go out zero-padded to 255 bytes; the
+    /// padding is left out here.
+    pub data: Vec<u8>,
+    /// What the bytes mean.
+    pub meaning: String,
+}
+
+impl PlannedTransfer {
+    fn new(request: impl Into<String>, data: &[u8], meaning: impl Into<String>) -> Self {
+        Self { request: request.into(), data: data.to_vec(), meaning: meaning.into() }
+    }
+
+    /// The data stage as space-separated hex, e.g. `a1 07 00 00`.
+    pub fn hex(&self) -> String {
+        self.data.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" ")
+    }
+}
+
+impl fmt::Display for PlannedTransfer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {}", self.request, self.hex())?;
+        write!(f, "    {}", self.meaning)
+    }
+}
+
+impl SettingChange {
+    /// The control transfers [`ElgatoDevice::apply_change`](crate::ElgatoDevice::apply_change)
+    /// would send for this change on `model`.
+    ///
+    /// Fails the same way applying would when the model doesn't support
+    /// the setting.  Checks that need the device (picture control ranges,
+    /// USB 2.0 fallback) are not made.
+    pub fn planned_transfers(&self, model: DeviceModel) -> Result<Vec<PlannedTransfer>, ElgatoError> {
+        let unsupported = |feature: &'static str| ElgatoError::UnsupportedFeature {
+            feature,
+            model: match model {
+                DeviceModel::Elgato4KX => "4K X",
+                DeviceModel::Elgato4KS => "4K S",
+            },
+        };
+
+        match (model, *self) {
+            (DeviceModel::Elgato4KX, change) => {
+                let payload = match change {
+                    Self::HdmiRange(range) => range.payload_4kx(),
+                    Self::EdidSource(source) => source.payload_4kx(),
+                    Self::HdrMapping(mode) => mode.payload_4kx(),
+                    Self::CustomEdid(mode) => mode.payload_4kx(),
+                    Self::CustomEdidPreset(preset) => preset.payload_4kx(),
+                    Self::InputLimit(limit) => limit.preset().payload_4kx(),
+                    Self::Picture(control, value) => return Ok(vec![picture_transfer(control, value)]),
+                    Self::UsbSpeed(speed) => {
+                        let payload = frame_at_command(AT_CMD_SET_USB_SPEED, &speed.at_input());
+                        let mut transfers = uvc_two_step(&payload, &format!("{} (AT_USB_Set_Force_Speed)", self));
+                        transfers[1].meaning.push_str("; the ACK is read back, then the card re-enumerates");
+                        return Ok(transfers);
+                    }
+                    Self::AudioInput(_) => return Err(unsupported("Audio input selection")),
+                    Self::VideoScaler(_) => return Err(unsupported("Video scaler")),
+                };
+                Ok(uvc_two_step(&payload, &self.to_string()))
+            }
+            (DeviceModel::Elgato4KS, change) => {
+                let packet = match change {
+                    Self::HdmiRange(range) => range.payload_4ks(),
+                    Self::EdidSource(source) => source.payload_4ks(),
+                    Self::HdrMapping(mode) => mode.payload_4ks(),
+                    Self::AudioInput(input) => input.payload_4ks(),
+                    Self::VideoScaler(scaler) => scaler.payload_4ks(),
+                    Self::CustomEdid(_) | Self::CustomEdidPreset(_) => return Err(unsupported("Custom EDID")),
+                    Self::InputLimit(_) => return Err(unsupported("Input limit")),
+                    Self::Picture(..) => return Err(unsupported("Picture controls")),
+                    Self::UsbSpeed(_) => return Err(unsupported("USB speed switching")),
+                };
+                let header = HID_WRITE_HEADER.len();
+                let meaning = format!(
+                    "write sub-command 0x{:02x} = 0x{:02x}: {}",
+                    packet[header],
+                    packet[header + 1],
+                    self
+                );
+                Ok(vec![PlannedTransfer::new(
+                    format!("HID SET_REPORT (Output) interface {}", HID_INTERFACE),
+                    &packet[..header + 2],
+                    meaning,
+                )])
+            }
+        }
+    }
+}
+
+#[cfg(feature = "unsafe-raw")]
+impl PlannedTransfer {
+    /// The transfers [`ElgatoDevice::send_raw_uvc`](crate::ElgatoDevice::send_raw_uvc)
+    /// would send for `payload`.
+    pub fn raw_uvc(payload: &[u8]) -> Result<Vec<Self>, ElgatoError> {
+        crate::uvc::check_raw_uvc_bytes(payload)?;
+        Ok(uvc_two_step(payload, "raw payload"))
+    }
+
+    /// The report [`ElgatoDevice::send_raw_hid`](crate::ElgatoDevice::send_raw_hid)
+    /// would send for `report`.
+    pub fn raw_hid(report: &[u8]) -> Result<Self, ElgatoError> {
+        crate::hid::raw_hid_report(report)?;
+        let meaning = match report.strip_prefix(&HID_WRITE_HEADER[..]) {
+            Some([sub_cmd, value, ..]) => format!("write sub-command 0x{:02x} = 0x{:02x}", sub_cmd, value),
+            _ => format!("raw report, {} bytes", report.len()),
+        };
+        Ok(Self::new(
+            format!("HID SET_REPORT (Output) interface {}", HID_INTERFACE),
+            report,
+            format!("{}; zero-padded to {} bytes", meaning, HID_PACKET_SIZE),
+        ))
+    }
+}
+
+/// Length trigger on selector 0x02, then the payload on selector 0x01.
+fn uvc_two_step(payload: &[u8], what: &str) -> Vec<PlannedTransfer> {
+    let request = |selector: u16| format!("UVC SET_CUR XU {} selector 0x{:02x}", UVC_ENTITY_ID, selector);
+    vec![
+        PlannedTransfer::new(
+            request(UVC_SELECTOR_TRIGGER),
+            &(payload.len() as u16).to_le_bytes(),
+            format!("announces a {}-byte payload", payload.len()),
+        ),
+        PlannedTransfer::new(
+            request(UVC_SELECTOR_VALUE),
+            payload,
+            format!("{}: {}", describe_uvc_frame(payload), what),
+        ),
+    ]
+}
+
+/// A Processing Unit SET_CUR.  The unit ID comes from the device's
+/// descriptors, so it isn't shown.
+fn picture_transfer(control: PictureControl, value: i32) -> PlannedTransfer {
+    PlannedTransfer::new(
+        format!("UVC SET_CUR Processing Unit selector 0x{:02x}", control.selector()),
+        &control.encode(value),
+        format!(
+            "{} = {} (16-bit LE; checked against the device's range when applied)",
+            control.name().to_lowercase(),
+            value
+        ),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{AudioInput, EdidRangePolicy, HdrToneMapping, UsbSpeed};
+
+    #[test]
+    fn uvc_setting_is_trigger_then_payload() {
+        let change = SettingChange::HdmiRange(EdidRangePolicy::Expand);
+        let transfers = change.planned_transfers(DeviceModel::Elgato4KX).unwrap();
+        let payload = EdidRangePolicy::Expand.payload_4kx();
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[0].request, "UVC SET_CUR XU 4 selector 0x02");
+        assert_eq!(transfers[0].data, (payload.len() as u16).to_le_bytes());
+        assert_eq!(transfers[1].request, "UVC SET_CUR XU 4 selector 0x01");
+        assert_eq!(transfers[1].data, payload);
+        assert!(transfers[1].meaning.contains("command 0x7c"), "{}", transfers[1].meaning);
+        assert!(transfers[1].meaning.ends_with("HDMI color range: Expand (Full)"));
+    }
+
+    #[test]
+    fn hid_setting_is_one_report() {
+        let change = SettingChange::HdrMapping(HdrToneMapping::On);
+        let transfers = change.planned_transfers(DeviceModel::Elgato4KS).unwrap();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].hex(), "06 06 06 55 02 0a 01");
+        assert_eq!(transfers[0].meaning, "write sub-command 0x0a = 0x01: HDR tone mapping: On");
+    }
+
+    #[test]
+    fn usb_speed_is_an_at_command() {
+        let transfers = SettingChange::UsbSpeed(UsbSpeed::TenGbps)
+            .planned_transfers(DeviceModel::Elgato4KX)
+            .unwrap();
+        assert_eq!(transfers[1].data, frame_at_command(AT_CMD_SET_USB_SPEED, &UsbSpeed::TenGbps.at_input()));
+        assert!(transfers[1].meaning.contains("re-enumerates"));
+    }
+
+    #[test]
+    fn picture_control_encodes_value() {
+        let transfers = SettingChange::Picture(PictureControl::Brightness, -2)
+            .planned_transfers(DeviceModel::Elgato4KX)
+            .unwrap();
+        assert_eq!(transfers[0].data, [0xfe, 0xff]);
+    }
+
+    #[test]
+    fn unsupported_settings_fail_like_applying() {
+        assert!(matches!(
+            SettingChange::AudioInput(AudioInput::Analog).planned_transfers(DeviceModel::Elgato4KX),
+            Err(ElgatoError::UnsupportedFeature { model: "4K X", .. })
+        ));
+        assert!(matches!(
+            SettingChange::UsbSpeed(UsbSpeed::FiveGbps).planned_transfers(DeviceModel::Elgato4KS),
+            Err(ElgatoError::UnsupportedFeature { model: "4K S", .. })
+        ));
+    }
+
+    #[cfg(feature = "unsafe-raw")]
+    #[test]
+    fn raw_plans_match_typed_settings() {
+        let payload = HdrToneMapping::On.payload_4kx();
+        let typed = SettingChange::HdrMapping(HdrToneMapping::On).planned_transfers(DeviceModel::Elgato4KX).unwrap();
+        let raw = PlannedTransfer::raw_uvc(&payload).unwrap();
+        assert_eq!(raw.iter().map(|t| &t.data).collect::<Vec<_>>(), typed.iter().map(|t| &t.data).collect::<Vec<_>>());
+
+        let hid = PlannedTransfer::raw_hid(&[0x06, 0x06, 0x06, 0x55, 0x02, 0x0a, 0x01]).unwrap();
+        assert_eq!(hid.meaning, "write sub-command 0x0a = 0x01; zero-padded to 255 bytes");
+        assert!(PlannedTransfer::raw_hid(&[0x06, 0x06, 0x06, 0x55, 0x02, 0x13]).is_err());
+    }
+}
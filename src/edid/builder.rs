@@ -0,0 +1,485 @@
+//! [`EdidBuilder`]: generate a complete EDID from a list of modes.
+//!
+//! The generated EDID is version 1.4 with a CTA-861 extension, followed by
+//! a DisplayID extension when some modes need one:
+//! - Base block: preferred timing, range limits, monitor name, and a second
+//!   timing (or a dummy descriptor).
+//! - CTA extension: VICs for standard modes, audio and speaker allocation,
+//!   HDMI (and HDMI Forum, above 340 MHz or for ALLM/VRR) vendor blocks, HDR static
+//!   metadata, and DTDs for any remaining non-CTA modes.
+//! - DisplayID extension, only when needed: modes whose pixel clock is too
+//!   high for a DTD and which have no VIC.
+
+use super::cta::{
+    CtaExtension, DataBlock, HdrStaticMetadata, ShortAudioDescriptor, VrrRange, audio_format,
+    OUI_HDMI, OUI_HDMI_FORUM,
+};
+use super::displayid::DisplayIdExtension;
+use super::timing::{cta_vic, DetailedTiming, VideoMode, DTD_MAX_PIXEL_CLOCK_KHZ};
+use super::{
+    block_checksum, Edid, EdidError, BASE_DESCRIPTOR_OFFSETS, EDID_BLOCK_SIZE, EDID_HEADER,
+    EXTENSION_COUNT_OFFSET,
+};
+
+/// Maximum TMDS clock of HDMI 1.4, above which the HDMI Forum block is needed.
+const HDMI14_MAX_TMDS_KHZ: u32 = 340_000;
+/// Maximum TMDS clock of HDMI 2.0, above which FRL is needed.
+const HDMI20_MAX_TMDS_KHZ: u32 = 600_000;
+
+/// sRGB chromaticity coordinates (EDID bytes 25-34).
+const SRGB_CHROMATICITY: [u8; 10] = [0xee, 0x91, 0xa3, 0x54, 0x4c, 0x99, 0x26, 0x0f, 0x50, 0x54];
+
+/// Extended tag of the Colorimetry data block.
+const EXT_TAG_COLORIMETRY: u8 = 5;
+
+/// Builder for a synthetic EDID.
+///
+/// The first mode added is the preferred mode.
+///
+/// ```
+/// use elgato4k_linux::edid::{EdidBuilder, HdrStaticMetadata, ShortAudioDescriptor, VideoMode};
+///
+/// let edid = EdidBuilder::new()
+///     .monitor_name("Capture 4K")
+///     .mode(VideoMode::new(3840, 2160, 60))
+///     .mode(VideoMode::new(1920, 1080, 120))
+///     .audio(ShortAudioDescriptor::lpcm(2))
+///     .hdr(HdrStaticMetadata::hdr10_hlg())
+///     .build()?;
+/// assert_eq!(edid.as_bytes().len(), 256);
+/// # Ok::<(), elgato4k_linux::edid::EdidError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct EdidBuilder {
+    manufacturer: String,
+    product_code: u16,
+    serial: u32,
+    year: u16,
+    monitor_name: String,
+    modes: Vec<VideoMode>,
+    audio: Vec<ShortAudioDescriptor>,
+    hdr: Option<HdrStaticMetadata>,
+    ycbcr444: bool,
+    ycbcr422: bool,
+    allm: bool,
+    vrr: Option<VrrRange>,
+}
+
+impl Default for EdidBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EdidBuilder {
+    pub fn new() -> Self {
+        Self {
+            manufacturer: "ELG".to_string(),
+            product_code: 0,
+            serial: 0,
+            year: 2024,
+            monitor_name: "Elgato".to_string(),
+            modes: Vec::new(),
+            audio: Vec::new(),
+            hdr: None,
+            ycbcr444: true,
+            ycbcr422: true,
+            allm: false,
+            vrr: None,
+        }
+    }
+
+    /// Three-letter PNP manufacturer ID (default `ELG`).
+    pub fn manufacturer(mut self, id: &str) -> Self {
+        self.manufacturer = id.to_string();
+        self
+    }
+
+    pub fn product_code(mut self, code: u16) -> Self {
+        self.product_code = code;
+        self
+    }
+
+    pub fn serial(mut self, serial: u32) -> Self {
+        self.serial = serial;
+        self
+    }
+
+    /// Year of manufacture (1990–2245).
+    pub fn year(mut self, year: u16) -> Self {
+        self.year = year;
+        self
+    }
+
+    /// Monitor name shown by the source (up to 13 ASCII characters).
+    pub fn monitor_name(mut self, name: &str) -> Self {
+        self.monitor_name = name.to_string();
+        self
+    }
+
+    /// Add a mode.  The first mode added becomes the preferred mode.
+    pub fn mode(mut self, mode: VideoMode) -> Self {
+        if !self.modes.contains(&mode) {
+            self.modes.push(mode);
+        }
+        self
+    }
+
+    /// Add an audio format.
+    pub fn audio(mut self, sad: ShortAudioDescriptor) -> Self {
+        self.audio.push(sad);
+        self
+    }
+
+    /// Advertise HDR support with the given static metadata block.
+    pub fn hdr(mut self, hdr: HdrStaticMetadata) -> Self {
+        self.hdr = Some(hdr);
+        self
+    }
+
+    /// Whether to advertise YCbCr 4:4:4 and 4:2:2 in addition to RGB.
+    pub fn ycbcr(mut self, ycbcr444: bool, ycbcr422: bool) -> Self {
+        self.ycbcr444 = ycbcr444;
+        self.ycbcr422 = ycbcr422;
+        self
+    }
+
+    /// Advertise Auto Low Latency Mode (off by default).
+    pub fn allm(mut self, allm: bool) -> Self {
+        self.allm = allm;
+        self
+    }
+
+    /// Advertise HDMI VRR over the given range (off by default).
+    ///
+    /// Consoles switch to VRR whenever it's offered, which many capture
+    /// pipelines can't follow — only enable it if the card passes it through.
+    pub fn vrr(mut self, range: Option<VrrRange>) -> Self {
+        self.vrr = range;
+        self
+    }
+
+    /// Assemble the EDID.
+    pub fn build(&self) -> Result<Edid, EdidError> {
+        if self.modes.is_empty() {
+            return Err(EdidError::NoModes);
+        }
+        let manufacturer = encode_manufacturer(&self.manufacturer)?;
+        let name = encode_text_descriptor(0xfc, &self.monitor_name)?;
+
+        // Every mode as (mode, vic, timing). VIC modes use the CTA timing.
+        let timings: Vec<(VideoMode, Option<u8>, DetailedTiming)> = self
+            .modes
+            .iter()
+            .map(|&m| {
+                let vic = m.vic();
+                let timing = vic
+                    .and_then(cta_vic)
+                    .map_or_else(|| DetailedTiming::cvt_rb(m), |v| v.timing());
+                (m, vic, timing)
+            })
+            .collect();
+
+        // Preferred DTD: the first mode whose timing fits in a DTD
+        let preferred_idx = timings
+            .iter()
+            .position(|(_, _, t)| t.pixel_clock_khz <= DTD_MAX_PIXEL_CLOCK_KHZ)
+            .ok_or(EdidError::PixelClockTooHigh {
+                pixel_clock_khz: timings[0].2.pixel_clock_khz,
+            })?;
+
+        // Non-CTA modes still needing a timing, in order.  Those too fast for
+        // a DTD go into a DisplayID extension instead.
+        let (fitting, fast): (Vec<_>, Vec<_>) = timings
+            .iter()
+            .enumerate()
+            .filter(|&(i, (_, vic, _))| i != preferred_idx && vic.is_none())
+            .map(|(i, &(_, _, t))| (i, t))
+            .partition(|(_, t)| t.pixel_clock_khz <= DTD_MAX_PIXEL_CLOCK_KHZ);
+        let mut extra_dtds: Vec<DetailedTiming> = fitting.into_iter().map(|(_, t)| t).collect();
+        let second_dtd = if extra_dtds.is_empty() { None } else { Some(extra_dtds.remove(0)) };
+        let displayid = (!fast.is_empty()).then(|| DisplayIdExtension {
+            timings: fast.into_iter().map(|(i, t)| (t, i == 0)).collect(),
+        });
+
+        let max_clock_khz = timings.iter().map(|(_, _, t)| t.pixel_clock_khz).max().unwrap_or(0);
+
+        let mut base = [0u8; EDID_BLOCK_SIZE];
+        base[..8].copy_from_slice(&EDID_HEADER);
+        base[8..10].copy_from_slice(&manufacturer.to_be_bytes());
+        base[10..12].copy_from_slice(&self.product_code.to_le_bytes());
+        base[12..16].copy_from_slice(&self.serial.to_le_bytes());
+        base[16] = 0; // week unspecified
+        base[17] = self.year.saturating_sub(1990).min(255) as u8;
+        base[18] = 1;
+        base[19] = 4;
+        // Digital input, HDMI-a, 8 or 10 bits per colour
+        base[20] = if self.hdr.is_some() { 0xb2 } else { 0xa2 };
+        base[23] = 0x78; // gamma 2.2
+        let encodings = (self.ycbcr444 as u8) | ((self.ycbcr422 as u8) << 1);
+        base[24] = (encodings << 3) | 0x02; // preferred timing is native
+        base[25..35].copy_from_slice(&SRGB_CHROMATICITY);
+        base[35] = 0x20; // established timing: 640x480@60
+        base[38..54].fill(0x01); // no standard timings
+
+        let descriptors = [
+            timings[preferred_idx].2.to_dtd()?,
+            range_limits_descriptor(&timings.iter().map(|(_, _, t)| *t).collect::<Vec<_>>()),
+            name,
+            match second_dtd {
+                Some(t) => t.to_dtd()?,
+                None => dummy_descriptor(),
+            },
+        ];
+        for (offset, d) in BASE_DESCRIPTOR_OFFSETS.iter().zip(descriptors.iter()) {
+            base[*offset..*offset + 18].copy_from_slice(d);
+        }
+        base[EXTENSION_COUNT_OFFSET] = 1 + displayid.is_some() as u8;
+        base[EDID_BLOCK_SIZE - 1] = block_checksum(&base[..EDID_BLOCK_SIZE - 1]);
+
+        let cta = self.cta_extension(&timings, preferred_idx, extra_dtds, max_clock_khz);
+
+        let mut bytes = base.to_vec();
+        bytes.extend_from_slice(&cta.to_block()?);
+        if let Some(displayid) = displayid {
+            bytes.extend_from_slice(&displayid.to_block()?);
+        }
+        Edid::from_bytes(bytes)
+    }
+
+    fn cta_extension(
+        &self,
+        timings: &[(VideoMode, Option<u8>, DetailedTiming)],
+        preferred_idx: usize,
+        extra_dtds: Vec<DetailedTiming>,
+        max_clock_khz: u32,
+    ) -> CtaExtension {
+        let mut ext = CtaExtension::new();
+        ext.ycbcr444 = self.ycbcr444;
+        ext.ycbcr422 = self.ycbcr422;
+        ext.basic_audio = self.audio.iter().any(|s| s.format_code == audio_format::LPCM);
+        ext.native_dtds = 1;
+
+        let svds: Vec<u8> = timings
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (_, vic, _))| {
+                vic.map(|v| if i == preferred_idx && v <= 64 { v | 0x80 } else { v })
+            })
+            .collect();
+        if !svds.is_empty() {
+            ext.data_blocks.push(DataBlock::Video(svds));
+        }
+
+        if !self.audio.is_empty() {
+            ext.data_blocks.push(DataBlock::Audio(self.audio.clone()));
+            let channels = self.audio.iter().map(|s| s.channels).max().unwrap_or(2);
+            ext.data_blocks.push(DataBlock::SpeakerAllocation([speaker_allocation(channels), 0, 0]));
+        }
+
+        // HDMI 1.x VSDB: physical address 1.0.0.0, deep colour, max TMDS clock
+        let deep_color = if self.hdr.is_some() {
+            0x10 | if self.ycbcr444 { 0x08 } else { 0x00 } // DC_30bit (+ DC_Y444)
+        } else {
+            0x00
+        };
+        let max_tmds_5mhz = max_clock_khz.min(HDMI14_MAX_TMDS_KHZ).div_ceil(5000) as u8;
+        ext.data_blocks.push(DataBlock::VendorSpecific {
+            oui: OUI_HDMI,
+            payload: vec![0x10, 0x00, deep_color, max_tmds_5mhz],
+        });
+
+        let hdmi2 = max_clock_khz > HDMI14_MAX_TMDS_KHZ;
+        if hdmi2 || self.allm || self.vrr.is_some() {
+            // A zero TMDS rate means "nothing above 340 MHz"
+            let max_tmds_5mhz = if hdmi2 {
+                max_clock_khz.min(HDMI20_MAX_TMDS_KHZ).div_ceil(5000) as u8
+            } else {
+                0
+            };
+            // SCDC present + scrambling below 340 MHz
+            let scdc = if hdmi2 { 0x88 } else { 0x00 };
+            // Max_FRL_Rate 6 = 12 Gbps x 4 lanes, needed for 4K120
+            let max_frl = if max_clock_khz > HDMI20_MAX_TMDS_KHZ { 6 << 4 } else { 0 };
+            let features = (self.allm as u8) << 1;
+            let mut payload = vec![0x01, max_tmds_5mhz, scdc, max_frl, features];
+            if let Some(vrr) = self.vrr {
+                payload.extend(vrr.to_bytes());
+            }
+            ext.data_blocks.push(DataBlock::VendorSpecific { oui: OUI_HDMI_FORUM, payload });
+        }
+
+        if let Some(hdr) = self.hdr {
+            // BT.2020 RGB / YCC / cYCC
+            ext.data_blocks.push(DataBlock::Extended { tag: EXT_TAG_COLORIMETRY, payload: vec![0xe0, 0x00] });
+            ext.data_blocks.push(DataBlock::HdrStaticMetadata(hdr));
+        }
+
+        ext.detailed_timings = extra_dtds;
+        ext
+    }
+}
+
+/// Speaker allocation byte for a channel count (FL/FR, LFE, FC, RL/RR, RLC/RRC).
+fn speaker_allocation(channels: u8) -> u8 {
+    match channels {
+        0..=2 => 0x01,
+        3..=6 => 0x0f,
+        _ => 0x4f,
+    }
+}
+
+/// Pack a three-letter PNP ID into the big-endian 5-bit-per-letter format.
+fn encode_manufacturer(id: &str) -> Result<u16, EdidError> {
+    let b = id.as_bytes();
+    if b.len() != 3 || !b.iter().all(u8::is_ascii_uppercase) {
+        return Err(EdidError::InvalidManufacturer(id.to_string()));
+    }
+    Ok(b.iter().fold(0u16, |acc, &c| (acc << 5) | (c - b'A' + 1) as u16))
+}
+
+/// Build a text display descriptor (`0xfc` name, `0xfe` unspecified text).
+fn encode_text_descriptor(tag: u8, text: &str) -> Result<[u8; 18], EdidError> {
+    if text.len() > 13 || !text.bytes().all(|c| c.is_ascii_graphic() || c == b' ') {
+        return Err(EdidError::InvalidMonitorName(text.to_string()));
+    }
+    let mut d = [0u8; 18];
+    d[3] = tag;
+    d[5..].fill(0x20);
+    d[5..5 + text.len()].copy_from_slice(text.as_bytes());
+    if text.len() < 13 {
+        d[5 + text.len()] = 0x0a;
+    }
+    Ok(d)
+}
+
+/// Display range limits descriptor (`0xfd`) covering every timing.
+fn range_limits_descriptor(timings: &[DetailedTiming]) -> [u8; 18] {
+    let hz = |t: &DetailedTiming| t.refresh_hz().round() as u32;
+    let khz = |t: &DetailedTiming| t.h_freq_khz();
+    let min_v = timings.iter().map(hz).min().unwrap_or(60).min(24);
+    let max_v = timings.iter().map(hz).max().unwrap_or(60);
+    let min_h = (timings.iter().map(khz).fold(f64::MAX, f64::min).floor() as u32).min(15);
+    let max_h = timings.iter().map(khz).fold(0.0, f64::max).ceil() as u32;
+    let max_clock_10mhz = timings.iter().map(|t| t.pixel_clock_khz).max().unwrap_or(0).div_ceil(10_000);
+
+    let mut d = [0u8; 18];
+    d[3] = 0xfd;
+    // Rate offsets (EDID 1.4): +255 on the max when the value doesn't fit a byte
+    let (max_v, v_flag) = if max_v > 255 { (max_v - 255, 0x02) } else { (max_v, 0x00) };
+    let (max_h, h_flag) = if max_h > 255 { (max_h - 255, 0x08) } else { (max_h, 0x00) };
+    d[4] = v_flag | h_flag;
+    d[5] = min_v as u8;
+    d[6] = max_v.min(255) as u8;
+    d[7] = min_h as u8;
+    d[8] = max_h.min(255) as u8;
+    d[9] = max_clock_10mhz.min(255) as u8;
+    d[10] = 0x01; // range limits only, no timing formula
+    d[11] = 0x0a;
+    d[12..].fill(0x20);
+    d
+}
+
+/// Dummy descriptor (`0x10`) filling an unused slot.
+pub(super) fn dummy_descriptor() -> [u8; 18] {
+    let mut d = [0u8; 18];
+    d[3] = 0x10;
+    d
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_valid_two_block_edid() {
+        let edid = EdidBuilder::new()
+            .mode(VideoMode::new(1920, 1080, 60))
+            .mode(VideoMode::new(2560, 1440, 60))
+            .audio(ShortAudioDescriptor::lpcm(2))
+            .build()
+            .unwrap();
+        assert_eq!(edid.block_count(), 2);
+        assert_eq!(edid.validate(), Ok(()));
+        assert_eq!(edid.manufacturer_id(), "ELG");
+
+        // Preferred DTD is the CTA 1080p60 timing, second slot holds 1440p
+        let dtds = edid.detailed_timings();
+        assert_eq!(dtds.len(), 2);
+        assert_eq!(dtds[0].pixel_clock_khz, 148_500);
+        assert_eq!(dtds[1].h_active, 2560);
+
+        let cta = edid.cta_extension().unwrap().unwrap();
+        assert_eq!(cta.vics(), vec![16]);
+        assert!(cta.basic_audio);
+        assert_eq!(cta.audio_descriptors(), vec![ShortAudioDescriptor::lpcm(2)]);
+    }
+
+    #[test]
+    fn high_clock_modes_get_hdmi_forum_block() {
+        let edid = EdidBuilder::new()
+            .mode(VideoMode::new(3840, 2160, 60))
+            .hdr(HdrStaticMetadata::hdr10_hlg())
+            .build()
+            .unwrap();
+        let cta = edid.cta_extension().unwrap().unwrap();
+        assert!(cta.data_blocks.iter().any(|db| matches!(db, DataBlock::VendorSpecific { oui: OUI_HDMI_FORUM, .. })));
+        assert_eq!(cta.hdr_static_metadata(), Some(&HdrStaticMetadata::hdr10_hlg()));
+    }
+
+    #[test]
+    fn allm_and_vrr_force_hdmi_forum_block() {
+        let edid = EdidBuilder::new()
+            .mode(VideoMode::new(1920, 1080, 60))
+            .allm(true)
+            .vrr(VrrRange::new(48, 120))
+            .build()
+            .unwrap();
+        let cta = edid.cta_extension().unwrap().unwrap();
+        let hf = cta.data_blocks.iter().find_map(|db| match db {
+            DataBlock::VendorSpecific { oui: OUI_HDMI_FORUM, payload } => Some(payload.clone()),
+            _ => None,
+        });
+        assert_eq!(hf, Some(vec![0x01, 0x00, 0x00, 0x00, 0x02, 48, 120]));
+    }
+
+    #[test]
+    fn fast_modes_go_to_displayid() {
+        let edid = EdidBuilder::new()
+            .mode(VideoMode::new(3440, 1440, 144))
+            .mode(VideoMode::new(3440, 1440, 60))
+            .build()
+            .unwrap();
+        assert_eq!(edid.block_count(), 3);
+        assert_eq!(edid.validate(), Ok(()));
+        assert_eq!(edid.detailed_timings()[0].v_active, 1440);
+
+        let displayid = edid.displayid_extension().unwrap().unwrap();
+        assert_eq!(displayid.timings.len(), 1);
+        let (t, preferred) = displayid.timings[0];
+        assert!(preferred);
+        assert_eq!((t.h_active, t.refresh_hz().round()), (3440, 144.0));
+    }
+
+    #[test]
+    fn rejects_bad_inputs() {
+        assert_eq!(EdidBuilder::new().build(), Err(EdidError::NoModes));
+        let b = EdidBuilder::new().mode(VideoMode::new(1920, 1080, 60));
+        assert!(matches!(b.clone().manufacturer("elg").build(), Err(EdidError::InvalidManufacturer(_))));
+        assert!(matches!(
+            b.monitor_name("A name that is far too long").build(),
+            Err(EdidError::InvalidMonitorName(_))
+        ));
+    }
+
+    #[test]
+    fn monitor_name_descriptor_padding() {
+        let d = encode_text_descriptor(0xfc, "Elgato").unwrap();
+        assert_eq!(&d[5..12], b"Elgato\n");
+        assert!(d[12..].iter().all(|&b| b == 0x20));
+    }
+}
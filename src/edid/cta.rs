@@ -0,0 +1,862 @@
+//! CTA-861 extension block: data blocks, short audio descriptors, and
+//! HDR static metadata.
+//!
+//! Layout of a CTA extension (always one 128-byte EDID block):
+//!   `[02] [revision] [dtd_offset] [flags] [data blocks...] [DTDs...] [pad] [checksum]`
+
+use std::fmt;
+
+use super::timing::{cta_vic, DetailedTiming};
+use super::{block_checksum, EdidError, EDID_BLOCK_SIZE};
+
+/// Tag byte of a CTA-861 extension block.
+pub const CTA_EXTENSION_TAG: u8 = 0x02;
+
+/// Data block tag codes (bits 7-5 of the block header).
+pub const TAG_AUDIO: u8 = 1;
+pub const TAG_VIDEO: u8 = 2;
+pub const TAG_VENDOR_SPECIFIC: u8 = 3;
+pub const TAG_SPEAKER_ALLOCATION: u8 = 4;
+pub const TAG_EXTENDED: u8 = 7;
+
+/// Extended tag code for the HDR Static Metadata data block.
+pub const EXT_TAG_HDR_STATIC_METADATA: u8 = 6;
+
+/// IEEE OUI of the HDMI 1.x vendor-specific data block (HDMI Licensing).
+pub const OUI_HDMI: u32 = 0x000c03;
+/// IEEE OUI of the HDMI Forum vendor-specific data block (HDMI 2.x).
+pub const OUI_HDMI_FORUM: u32 = 0xc45dd8;
+/// IEEE OUI of AMD's vendor-specific block, which carries the FreeSync range.
+pub const OUI_AMD: u32 = 0x00001a;
+
+/// Extended tag code for the HDMI Forum Sink Capability data block, which
+/// carries the same fields as the HF-VSDB after two reserved bytes.
+pub const EXT_TAG_HF_SCDB: u8 = 0x79;
+
+// ---------------------------------------------------------------------------
+// HDMI vendor-specific blocks
+// ---------------------------------------------------------------------------
+
+/// Decoded HDMI 1.x vendor-specific data block (OUI `00-0C-03`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HdmiVsdb {
+    /// CEC physical address, e.g. `[1, 0, 0, 0]` for 1.0.0.0.
+    pub physical_address: [u8; 4],
+    /// Sink accepts ACP/ISRC packets.
+    pub supports_ai: bool,
+    pub dc_48bit: bool,
+    pub dc_36bit: bool,
+    pub dc_30bit: bool,
+    /// Deep colour also applies to YCbCr 4:4:4.
+    pub dc_y444: bool,
+    /// Maximum TMDS clock, if the sink states one.
+    pub max_tmds_khz: Option<u32>,
+    /// HDMI_VICs (1–4: 4K24/25/30 and 4096x2160@24).
+    pub hdmi_vics: Vec<u8>,
+}
+
+impl HdmiVsdb {
+    /// Decode from the payload following the OUI.
+    pub fn parse(p: &[u8]) -> Option<Self> {
+        let (&pa_hi, &pa_lo) = (p.first()?, p.get(1)?);
+        let flags = p.get(2).copied().unwrap_or(0);
+        let max_tmds_khz = p.get(3).copied().filter(|&v| v != 0).map(|v| v as u32 * 5000);
+
+        let hdmi_vics = hdmi_vic_span(p)
+            .map(|(len_index, count)| p[len_index + 1..len_index + 1 + count].to_vec())
+            .unwrap_or_default();
+
+        Some(Self {
+            physical_address: [pa_hi >> 4, pa_hi & 0x0f, pa_lo >> 4, pa_lo & 0x0f],
+            supports_ai: flags & 0x80 != 0,
+            dc_48bit: flags & 0x40 != 0,
+            dc_36bit: flags & 0x20 != 0,
+            dc_30bit: flags & 0x10 != 0,
+            dc_y444: flags & 0x08 != 0,
+            max_tmds_khz,
+            hdmi_vics,
+        })
+    }
+}
+
+/// Locate the HDMI_VIC list in an HDMI 1.x VSDB payload (after the OUI).
+///
+/// Layout: physical address (2), flags, max TMDS, latency flags, optional
+/// latencies (2 or 4), 3D flags, then `HDMI_VIC_LEN << 5 | HDMI_3D_LEN`
+/// followed by the VICs.  Returns the index of that length byte and the
+/// VIC count, or `None` when HDMI_Video_present is clear.
+pub(super) fn hdmi_vic_span(p: &[u8]) -> Option<(usize, usize)> {
+    let latency_flags = *p.get(4)?;
+    if latency_flags & 0x20 == 0 {
+        return None;
+    }
+    let mut len_index = 6;
+    if latency_flags & 0x80 != 0 {
+        len_index += 2;
+    }
+    if latency_flags & 0x40 != 0 {
+        len_index += 2;
+    }
+    let count = (*p.get(len_index)? >> 5) as usize;
+    (len_index + 1 + count <= p.len()).then_some((len_index, count))
+}
+
+impl fmt::Display for HdmiVsdb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d] = self.physical_address;
+        write!(f, "HDMI 1.x, address {}.{}.{}.{}", a, b, c, d)?;
+        if let Some(khz) = self.max_tmds_khz {
+            write!(f, ", max TMDS {} MHz", khz / 1000)?;
+        }
+        let depths: Vec<&str> = [(self.dc_30bit, "30"), (self.dc_36bit, "36"), (self.dc_48bit, "48")]
+            .iter()
+            .filter(|(on, _)| *on)
+            .map(|&(_, bits)| bits)
+            .collect();
+        if !depths.is_empty() {
+            write!(f, ", deep colour {}-bit", depths.join("/"))?;
+        }
+        if !self.hdmi_vics.is_empty() {
+            write!(f, ", HDMI VICs {:?}", self.hdmi_vics)?;
+        }
+        Ok(())
+    }
+}
+
+/// Decoded HDMI Forum capabilities, from the HF-VSDB or HF-SCDB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HdmiForumVsdb {
+    pub version: u8,
+    /// Maximum TMDS character rate above 340 MHz, if any.
+    pub max_tmds_khz: Option<u32>,
+    /// Sink supports SCDC (required for scrambling above 340 MHz).
+    pub scdc_present: bool,
+    /// Max_FRL_Rate code: 0 = TMDS only, 1–6 = 3G×3 up to 12G×4.
+    pub max_frl_rate: u8,
+    /// Auto Low Latency Mode.
+    pub allm: bool,
+    /// Advertised VRR range, if any.
+    pub vrr: Option<VrrRange>,
+}
+
+impl HdmiForumVsdb {
+    /// Decode from the shared fields, starting at the Version byte.
+    pub fn parse(p: &[u8]) -> Option<Self> {
+        let version = *p.first()?;
+        let vrr = match (p.get(5), p.get(6)) {
+            (Some(&lo), Some(&max_lo)) => {
+                VrrRange::new(lo & 0x3f, ((lo as u16 >> 6) << 8) | max_lo as u16)
+            }
+            _ => None,
+        };
+        Some(Self {
+            version,
+            max_tmds_khz: p.get(1).copied().filter(|&v| v != 0).map(|v| v as u32 * 5000),
+            scdc_present: p.get(2).is_some_and(|f| f & 0x80 != 0),
+            max_frl_rate: p.get(3).map_or(0, |f| f >> 4),
+            allm: p.get(4).is_some_and(|f| f & 0x02 != 0),
+            vrr,
+        })
+    }
+}
+
+impl fmt::Display for HdmiForumVsdb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HDMI 2.x")?;
+        if let Some(khz) = self.max_tmds_khz {
+            write!(f, ", max TMDS {} MHz", khz / 1000)?;
+        }
+        if self.max_frl_rate != 0 {
+            write!(f, ", FRL rate {}", self.max_frl_rate)?;
+        }
+        if self.allm {
+            write!(f, ", ALLM")?;
+        }
+        if let Some(vrr) = self.vrr {
+            write!(f, ", VRR {}", vrr)?;
+        }
+        Ok(())
+    }
+}
+
+/// Variable refresh rate range advertised in the HDMI Forum block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VrrRange {
+    min_hz: u8,
+    max_hz: u16,
+}
+
+impl VrrRange {
+    /// `min_hz` is a 6-bit field (1–63) and `max_hz` a 10-bit one; the range
+    /// must not be empty.
+    pub fn new(min_hz: u8, max_hz: u16) -> Option<Self> {
+        ((1..=63).contains(&min_hz) && max_hz < 1024 && max_hz > min_hz as u16)
+            .then_some(Self { min_hz, max_hz })
+    }
+
+    pub fn min_hz(&self) -> u8 {
+        self.min_hz
+    }
+
+    pub fn max_hz(&self) -> u16 {
+        self.max_hz
+    }
+
+    /// The VRRmin / VRRmax bytes of the HDMI Forum block.
+    pub(crate) fn to_bytes(self) -> [u8; 2] {
+        [self.min_hz | ((self.max_hz >> 8) as u8) << 6, self.max_hz as u8]
+    }
+}
+
+/// Deserialized through [`VrrRange::new`], so out-of-range bounds fail.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for VrrRange {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct Bounds {
+            min_hz: u8,
+            max_hz: u16,
+        }
+        let Bounds { min_hz, max_hz } = Bounds::deserialize(deserializer)?;
+        Self::new(min_hz, max_hz)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid VRR range {}-{} Hz", min_hz, max_hz)))
+    }
+}
+
+impl fmt::Display for VrrRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{} Hz", self.min_hz, self.max_hz)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Short audio descriptors
+// ---------------------------------------------------------------------------
+
+/// Audio format codes used in short audio descriptors.
+pub mod audio_format {
+    pub const LPCM: u8 = 1;
+    pub const AC3: u8 = 2;
+    pub const DTS: u8 = 7;
+    pub const EAC3: u8 = 10;
+    pub const DTS_HD: u8 = 11;
+    pub const TRUEHD: u8 = 12;
+}
+
+/// Sample rate bits used in short audio descriptors.
+pub mod sample_rate {
+    pub const KHZ_32: u8 = 0x01;
+    pub const KHZ_44_1: u8 = 0x02;
+    pub const KHZ_48: u8 = 0x04;
+    pub const KHZ_88_2: u8 = 0x08;
+    pub const KHZ_96: u8 = 0x10;
+    pub const KHZ_176_4: u8 = 0x20;
+    pub const KHZ_192: u8 = 0x40;
+}
+
+/// One 3-byte short audio descriptor from an Audio Data Block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShortAudioDescriptor {
+    /// Audio format code (see [`audio_format`]).
+    pub format_code: u8,
+    /// Maximum channel count (1–8).
+    pub channels: u8,
+    /// Supported sample rates (see [`sample_rate`]).
+    pub sample_rates: u8,
+    /// Format-dependent third byte: bit depths for LPCM, max bitrate / 8 kbps
+    /// for AC-3/DTS, and format-specific flags otherwise.
+    pub detail: u8,
+}
+
+impl ShortAudioDescriptor {
+    /// LPCM at 32–48 kHz, 16/20/24-bit.
+    pub fn lpcm(channels: u8) -> Self {
+        Self {
+            format_code: audio_format::LPCM,
+            channels,
+            sample_rates: sample_rate::KHZ_32 | sample_rate::KHZ_44_1 | sample_rate::KHZ_48,
+            detail: 0x07,
+        }
+    }
+
+    /// Dolby Digital (AC-3) at 32–48 kHz, up to 640 kbps.
+    pub fn ac3(channels: u8) -> Self {
+        Self {
+            format_code: audio_format::AC3,
+            channels,
+            sample_rates: sample_rate::KHZ_32 | sample_rate::KHZ_44_1 | sample_rate::KHZ_48,
+            detail: (640 / 8) as u8,
+        }
+    }
+
+    /// Whether this is a compressed (bitstream) format rather than LPCM.
+    pub fn is_bitstream(&self) -> bool {
+        self.format_code != audio_format::LPCM
+    }
+
+    fn parse(b: &[u8]) -> Self {
+        Self {
+            format_code: (b[0] >> 3) & 0x0f,
+            channels: (b[0] & 0x07) + 1,
+            sample_rates: b[1] & 0x7f,
+            detail: b[2],
+        }
+    }
+
+    fn to_bytes(self) -> [u8; 3] {
+        [
+            ((self.format_code & 0x0f) << 3) | (self.channels.clamp(1, 8) - 1),
+            self.sample_rates & 0x7f,
+            self.detail,
+        ]
+    }
+}
+
+impl fmt::Display for ShortAudioDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.format_code {
+            audio_format::LPCM => "LPCM",
+            audio_format::AC3 => "AC-3",
+            audio_format::DTS => "DTS",
+            audio_format::EAC3 => "E-AC-3",
+            audio_format::DTS_HD => "DTS-HD",
+            audio_format::TRUEHD => "TrueHD",
+            _ => "Other",
+        };
+        write!(f, "{} {}ch", name, self.channels)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// HDR static metadata
+// ---------------------------------------------------------------------------
+
+/// EOTF bits in the HDR Static Metadata data block.
+pub mod eotf {
+    pub const SDR: u8 = 0x01;
+    pub const HDR_TRADITIONAL: u8 = 0x02;
+    pub const PQ: u8 = 0x04;
+    pub const HLG: u8 = 0x08;
+}
+
+/// HDR Static Metadata data block (CTA-861.3).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HdrStaticMetadata {
+    /// Supported EOTFs (see [`eotf`]).
+    pub eotfs: u8,
+    /// Supported static metadata descriptor types (bit 0 = Type 1).
+    pub metadata_types: u8,
+    /// Desired content max luminance (coded value).
+    pub max_luminance: Option<u8>,
+    /// Desired content max frame-average luminance (coded value).
+    pub max_frame_avg_luminance: Option<u8>,
+    /// Desired content min luminance (coded value).
+    pub min_luminance: Option<u8>,
+}
+
+impl HdrStaticMetadata {
+    /// SDR + PQ (HDR10) + HLG with Type 1 metadata and no luminance hints.
+    pub fn hdr10_hlg() -> Self {
+        Self {
+            eotfs: eotf::SDR | eotf::PQ | eotf::HLG,
+            metadata_types: 0x01,
+            max_luminance: None,
+            max_frame_avg_luminance: None,
+            min_luminance: None,
+        }
+    }
+
+    /// Set the desired content luminance hints from cd/m² values.
+    ///
+    /// The block stores coded values: max and frame-average luminance are
+    /// `50 * 2^(cv/32)`, min luminance is `max * (cv/255)^2 / 100`.  Values
+    /// are rounded to the nearest code, so reading them back is approximate.
+    pub fn with_luminance(mut self, max_nits: f64, max_frame_avg_nits: f64, min_nits: f64) -> Self {
+        let encode_max = |nits: f64| (32.0 * (nits / 50.0).log2()).round().clamp(0.0, 255.0) as u8;
+        let max = encode_max(max_nits);
+        self.max_luminance = Some(max);
+        self.max_frame_avg_luminance = Some(encode_max(max_frame_avg_nits));
+        let ratio = (min_nits * 100.0 / decode_max(max)).max(0.0).sqrt();
+        self.min_luminance = Some((255.0 * ratio).round().clamp(0.0, 255.0) as u8);
+        self
+    }
+
+    /// Desired content max luminance in cd/m².
+    pub fn max_luminance_nits(&self) -> Option<f64> {
+        self.max_luminance.map(decode_max)
+    }
+
+    /// Desired content max frame-average luminance in cd/m².
+    pub fn max_frame_avg_luminance_nits(&self) -> Option<f64> {
+        self.max_frame_avg_luminance.map(decode_max)
+    }
+
+    /// Desired content min luminance in cd/m² (needs the max value too).
+    pub fn min_luminance_nits(&self) -> Option<f64> {
+        let max = self.max_luminance_nits()?;
+        let cv = self.min_luminance? as f64;
+        Some(max * (cv / 255.0).powi(2) / 100.0)
+    }
+
+    fn parse(p: &[u8]) -> Self {
+        Self {
+            eotfs: p.first().copied().unwrap_or(0),
+            metadata_types: p.get(1).copied().unwrap_or(0),
+            max_luminance: p.get(2).copied(),
+            max_frame_avg_luminance: p.get(3).copied(),
+            min_luminance: p.get(4).copied(),
+        }
+    }
+
+    fn to_payload(self) -> Vec<u8> {
+        let mut p = vec![self.eotfs, self.metadata_types];
+        // Optional bytes are positional, so later ones force earlier ones out
+        let optional = [self.max_luminance, self.max_frame_avg_luminance, self.min_luminance];
+        let used = optional.iter().rposition(Option::is_some).map_or(0, |i| i + 1);
+        p.extend(optional[..used].iter().map(|v| v.unwrap_or(0)));
+        p
+    }
+}
+
+/// Decode a max / frame-average luminance code value to cd/m².
+fn decode_max(cv: u8) -> f64 {
+    50.0 * 2f64.powf(cv as f64 / 32.0)
+}
+
+// ---------------------------------------------------------------------------
+// Data blocks
+// ---------------------------------------------------------------------------
+
+/// A data block from the CTA extension's data block collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DataBlock {
+    /// Video Data Block: raw short video descriptors (VIC, native flag in bit 7).
+    Video(Vec<u8>),
+    /// Audio Data Block.
+    Audio(Vec<ShortAudioDescriptor>),
+    /// Speaker Allocation Data Block.
+    SpeakerAllocation([u8; 3]),
+    /// Vendor-Specific Data Block, with the payload following the 3-byte OUI.
+    VendorSpecific { oui: u32, payload: Vec<u8> },
+    /// HDR Static Metadata Data Block (extended tag 6).
+    HdrStaticMetadata(HdrStaticMetadata),
+    /// Any other extended-tag block, payload after the extended tag byte.
+    Extended { tag: u8, payload: Vec<u8> },
+    /// Any other block, payload after the header byte.
+    Other { tag: u8, payload: Vec<u8> },
+}
+
+/// Strip the native flag from a short video descriptor.
+pub fn svd_vic(svd: u8) -> u8 {
+    if (129..=192).contains(&svd) { svd & 0x7f } else { svd }
+}
+
+impl DataBlock {
+    fn parse(tag: u8, p: &[u8]) -> Self {
+        match tag {
+            TAG_AUDIO => Self::Audio(p.chunks_exact(3).map(ShortAudioDescriptor::parse).collect()),
+            TAG_VIDEO => Self::Video(p.to_vec()),
+            TAG_SPEAKER_ALLOCATION if p.len() >= 3 => Self::SpeakerAllocation([p[0], p[1], p[2]]),
+            TAG_VENDOR_SPECIFIC if p.len() >= 3 => Self::VendorSpecific {
+                oui: u32::from_le_bytes([p[0], p[1], p[2], 0]),
+                payload: p[3..].to_vec(),
+            },
+            TAG_EXTENDED if !p.is_empty() => match p[0] {
+                EXT_TAG_HDR_STATIC_METADATA => Self::HdrStaticMetadata(HdrStaticMetadata::parse(&p[1..])),
+                ext => Self::Extended { tag: ext, payload: p[1..].to_vec() },
+            },
+            _ => Self::Other { tag, payload: p.to_vec() },
+        }
+    }
+
+    /// Serialize including the tag/length header byte.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, EdidError> {
+        let (tag, payload) = match self {
+            Self::Video(svds) => (TAG_VIDEO, svds.clone()),
+            Self::Audio(sads) => (TAG_AUDIO, sads.iter().flat_map(|s| s.to_bytes()).collect()),
+            Self::SpeakerAllocation(s) => (TAG_SPEAKER_ALLOCATION, s.to_vec()),
+            Self::VendorSpecific { oui, payload } => {
+                let mut p = oui.to_le_bytes()[..3].to_vec();
+                p.extend_from_slice(payload);
+                (TAG_VENDOR_SPECIFIC, p)
+            }
+            Self::HdrStaticMetadata(hdr) => {
+                let mut p = vec![EXT_TAG_HDR_STATIC_METADATA];
+                p.extend(hdr.to_payload());
+                (TAG_EXTENDED, p)
+            }
+            Self::Extended { tag, payload } => {
+                let mut p = vec![*tag];
+                p.extend_from_slice(payload);
+                (TAG_EXTENDED, p)
+            }
+            Self::Other { tag, payload } => (*tag, payload.clone()),
+        };
+        if payload.len() > 31 {
+            return Err(EdidError::DataBlockTooLong { tag, len: payload.len() });
+        }
+        let mut out = vec![(tag << 5) | payload.len() as u8];
+        out.extend(payload);
+        Ok(out)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CTA extension block
+// ---------------------------------------------------------------------------
+
+/// A parsed CTA-861 extension block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CtaExtension {
+    /// CTA extension revision (3 for anything HDMI).
+    pub revision: u8,
+    /// Sink underscans IT formats by default.
+    pub underscan: bool,
+    /// Sink supports basic audio (2ch LPCM).
+    pub basic_audio: bool,
+    /// Sink supports YCbCr 4:4:4.
+    pub ycbcr444: bool,
+    /// Sink supports YCbCr 4:2:2.
+    pub ycbcr422: bool,
+    /// Number of native detailed timings.
+    pub native_dtds: u8,
+    /// Data block collection, in order.
+    pub data_blocks: Vec<DataBlock>,
+    /// Detailed timing descriptors following the data blocks.
+    pub detailed_timings: Vec<DetailedTiming>,
+}
+
+impl CtaExtension {
+    /// An empty revision 3 extension.
+    pub fn new() -> Self {
+        Self {
+            revision: 3,
+            underscan: false,
+            basic_audio: false,
+            ycbcr444: false,
+            ycbcr422: false,
+            native_dtds: 0,
+            data_blocks: Vec::new(),
+            detailed_timings: Vec::new(),
+        }
+    }
+
+    /// Parse a 128-byte CTA extension block.
+    pub fn parse(block: &[u8]) -> Result<Self, EdidError> {
+        if block.len() != EDID_BLOCK_SIZE || block[0] != CTA_EXTENSION_TAG {
+            return Err(EdidError::NotCtaExtension);
+        }
+        let dtd_offset = block[2] as usize;
+        let flags = block[3];
+
+        let mut data_blocks = Vec::new();
+        let mut detailed_timings = Vec::new();
+
+        if dtd_offset >= 4 {
+            let end = dtd_offset.min(EDID_BLOCK_SIZE - 1);
+            let mut i = 4;
+            while i < end {
+                let tag = block[i] >> 5;
+                let len = (block[i] & 0x1f) as usize;
+                if i + 1 + len > end {
+                    return Err(EdidError::MalformedCtaBlock { offset: i });
+                }
+                data_blocks.push(DataBlock::parse(tag, &block[i + 1..i + 1 + len]));
+                i += 1 + len;
+            }
+
+            let mut j = dtd_offset;
+            while j + 18 < EDID_BLOCK_SIZE {
+                let d: &[u8; 18] = block[j..j + 18].try_into().expect("slice is 18 bytes");
+                match DetailedTiming::from_dtd(d) {
+                    Some(t) => detailed_timings.push(t),
+                    None => break,
+                }
+                j += 18;
+            }
+        }
+
+        Ok(Self {
+            revision: block[1],
+            underscan: flags & 0x80 != 0,
+            basic_audio: flags & 0x40 != 0,
+            ycbcr444: flags & 0x20 != 0,
+            ycbcr422: flags & 0x10 != 0,
+            native_dtds: flags & 0x0f,
+            data_blocks,
+            detailed_timings,
+        })
+    }
+
+    /// Serialize to a 128-byte block with a valid checksum.
+    pub fn to_block(&self) -> Result<[u8; EDID_BLOCK_SIZE], EdidError> {
+        let mut collection = Vec::new();
+        for db in &self.data_blocks {
+            collection.extend(db.to_bytes()?);
+        }
+        let mut dtds = Vec::new();
+        for t in &self.detailed_timings {
+            dtds.extend_from_slice(&t.to_dtd()?);
+        }
+
+        let used = 4 + collection.len() + dtds.len();
+        if used > EDID_BLOCK_SIZE - 1 {
+            return Err(EdidError::CtaBlockOverflow { needed: used + 1 });
+        }
+
+        let mut block = [0u8; EDID_BLOCK_SIZE];
+        block[0] = CTA_EXTENSION_TAG;
+        block[1] = self.revision;
+        block[2] = (4 + collection.len()) as u8;
+        block[3] = (self.underscan as u8) << 7
+            | (self.basic_audio as u8) << 6
+            | (self.ycbcr444 as u8) << 5
+            | (self.ycbcr422 as u8) << 4
+            | (self.native_dtds & 0x0f);
+        block[4..4 + collection.len()].copy_from_slice(&collection);
+        block[4 + collection.len()..used].copy_from_slice(&dtds);
+        block[EDID_BLOCK_SIZE - 1] = block_checksum(&block[..EDID_BLOCK_SIZE - 1]);
+        Ok(block)
+    }
+
+    /// All VICs from every Video Data Block, native flags stripped.
+    pub fn vics(&self) -> Vec<u8> {
+        self.data_blocks
+            .iter()
+            .filter_map(|db| match db {
+                DataBlock::Video(svds) => Some(svds.iter().map(|&s| svd_vic(s))),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// All short audio descriptors from every Audio Data Block.
+    pub fn audio_descriptors(&self) -> Vec<ShortAudioDescriptor> {
+        self.data_blocks
+            .iter()
+            .filter_map(|db| match db {
+                DataBlock::Audio(sads) => Some(sads.iter().copied()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// The HDR Static Metadata data block, if present.
+    pub fn hdr_static_metadata(&self) -> Option<&HdrStaticMetadata> {
+        self.data_blocks.iter().find_map(|db| match db {
+            DataBlock::HdrStaticMetadata(hdr) => Some(hdr),
+            _ => None,
+        })
+    }
+
+    /// The HDMI 1.x vendor-specific block, decoded.
+    pub fn hdmi_vsdb(&self) -> Option<HdmiVsdb> {
+        self.data_blocks.iter().find_map(|db| match db {
+            DataBlock::VendorSpecific { oui: OUI_HDMI, payload } => HdmiVsdb::parse(payload),
+            _ => None,
+        })
+    }
+
+    /// The HDMI Forum capabilities, from the HF-VSDB or else the HF-SCDB.
+    pub fn hdmi_forum(&self) -> Option<HdmiForumVsdb> {
+        let vsdb = self.data_blocks.iter().find_map(|db| match db {
+            DataBlock::VendorSpecific { oui: OUI_HDMI_FORUM, payload } => HdmiForumVsdb::parse(payload),
+            _ => None,
+        });
+        vsdb.or_else(|| {
+            self.data_blocks.iter().find_map(|db| match db {
+                DataBlock::Extended { tag: EXT_TAG_HF_SCDB, payload } => HdmiForumVsdb::parse(payload.get(2..)?),
+                _ => None,
+            })
+        })
+    }
+}
+
+impl fmt::Display for CtaExtension {
+    /// Multi-line summary, one capability per line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "CTA-861 extension (revision {})", self.revision)?;
+        let mut encodings = vec!["RGB"];
+        if self.ycbcr444 {
+            encodings.push("YCbCr 4:4:4");
+        }
+        if self.ycbcr422 {
+            encodings.push("YCbCr 4:2:2");
+        }
+        writeln!(f, "  Colour: {}", encodings.join(", "))?;
+
+        let modes: Vec<String> = self
+            .vics()
+            .into_iter()
+            .map(|vic| match cta_vic(vic) {
+                Some(v) => format!("{}x{}@{}", v.width, v.height, v.refresh_hz),
+                None => format!("VIC {}", vic),
+            })
+            .collect();
+        if !modes.is_empty() {
+            writeln!(f, "  Video: {}", modes.join(", "))?;
+        }
+        for t in &self.detailed_timings {
+            writeln!(f, "  Timing: {}", t)?;
+        }
+
+        let audio: Vec<String> = self.audio_descriptors().iter().map(|s| s.to_string()).collect();
+        if !audio.is_empty() {
+            writeln!(f, "  Audio: {}", audio.join(", "))?;
+        }
+        if let Some(hdr) = self.hdr_static_metadata() {
+            let names: Vec<&str> = [
+                (eotf::SDR, "SDR"),
+                (eotf::HDR_TRADITIONAL, "HDR"),
+                (eotf::PQ, "PQ"),
+                (eotf::HLG, "HLG"),
+            ]
+            .iter()
+            .filter(|(bit, _)| hdr.eotfs & bit != 0)
+            .map(|&(_, name)| name)
+            .collect();
+            write!(f, "  HDR: {}", names.join(", "))?;
+            if let Some(nits) = hdr.max_luminance_nits() {
+                write!(f, " (max {:.0} cd/m²)", nits)?;
+            }
+            writeln!(f)?;
+        }
+        if let Some(vsdb) = self.hdmi_vsdb() {
+            writeln!(f, "  {}", vsdb)?;
+        }
+        if let Some(hf) = self.hdmi_forum() {
+            writeln!(f, "  {}", hf)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for CtaExtension {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edid::timing::VideoMode;
+
+    #[test]
+    fn sad_roundtrip() {
+        let sad = ShortAudioDescriptor::lpcm(2);
+        assert_eq!(sad.to_bytes(), [0x09, 0x07, 0x07]);
+        assert_eq!(ShortAudioDescriptor::parse(&sad.to_bytes()), sad);
+        assert!(!sad.is_bitstream());
+        assert!(ShortAudioDescriptor::ac3(6).is_bitstream());
+    }
+
+    #[test]
+    fn hdr_payload_positional_optionals() {
+        let mut hdr = HdrStaticMetadata::hdr10_hlg();
+        assert_eq!(hdr.to_payload(), vec![0x0d, 0x01]);
+        hdr.min_luminance = Some(0x10);
+        assert_eq!(hdr.to_payload(), vec![0x0d, 0x01, 0x00, 0x00, 0x10]);
+    }
+
+    #[test]
+    fn hdr_luminance_coding() {
+        let hdr = HdrStaticMetadata::hdr10_hlg().with_luminance(1000.0, 400.0, 0.05);
+        assert_eq!(hdr.max_luminance, Some(138));
+        assert!((hdr.max_luminance_nits().unwrap() - 1000.0).abs() < 25.0);
+        assert!((hdr.max_frame_avg_luminance_nits().unwrap() - 400.0).abs() < 10.0);
+        assert!((hdr.min_luminance_nits().unwrap() - 0.05).abs() < 0.005);
+        assert_eq!(HdrStaticMetadata::hdr10_hlg().min_luminance_nits(), None);
+    }
+
+    #[test]
+    fn extension_roundtrip() {
+        let mut ext = CtaExtension::new();
+        ext.basic_audio = true;
+        ext.ycbcr444 = true;
+        ext.native_dtds = 1;
+        ext.data_blocks = vec![
+            DataBlock::Video(vec![0x90, 0x04, 0x61]),
+            DataBlock::Audio(vec![ShortAudioDescriptor::lpcm(2)]),
+            DataBlock::SpeakerAllocation([0x01, 0x00, 0x00]),
+            DataBlock::VendorSpecific { oui: OUI_HDMI, payload: vec![0x10, 0x00] },
+            DataBlock::HdrStaticMetadata(HdrStaticMetadata::hdr10_hlg()),
+        ];
+        ext.detailed_timings = vec![DetailedTiming::cvt_rb(VideoMode::new(2560, 1440, 60))];
+
+        let block = ext.to_block().unwrap();
+        assert_eq!(block.iter().fold(0u8, |a, &b| a.wrapping_add(b)), 0);
+        assert_eq!(CtaExtension::parse(&block).unwrap(), ext);
+        assert_eq!(ext.vics(), vec![16, 4, 97]);
+    }
+
+    #[test]
+    fn hdmi_vsdb_decoding() {
+        // 1.0.0.0, DC_30bit + DC_Y444, 340 MHz, HDMI_Video_present, 1 HDMI VIC
+        let vsdb = HdmiVsdb::parse(&[0x10, 0x00, 0x18, 0x44, 0x20, 0x00, 0x20, 0x01]).unwrap();
+        assert_eq!(vsdb.physical_address, [1, 0, 0, 0]);
+        assert!(vsdb.dc_30bit && vsdb.dc_y444 && !vsdb.dc_36bit);
+        assert_eq!(vsdb.max_tmds_khz, Some(340_000));
+        assert_eq!(vsdb.hdmi_vics, vec![1]);
+        assert!(HdmiVsdb::parse(&[0x10]).is_none());
+    }
+
+    #[test]
+    fn hdmi_forum_decoding_from_vsdb_and_scdb() {
+        let fields = [0x01, 120, 0x88, 0x60, 0x02, 48, 120];
+        let hf = HdmiForumVsdb::parse(&fields).unwrap();
+        assert_eq!(hf.max_tmds_khz, Some(600_000));
+        assert!(hf.scdc_present && hf.allm);
+        assert_eq!(hf.max_frl_rate, 6);
+        assert_eq!(hf.vrr, VrrRange::new(48, 120));
+
+        let mut ext = CtaExtension::new();
+        let mut scdb = vec![0x00, 0x00];
+        scdb.extend_from_slice(&fields);
+        ext.data_blocks.push(DataBlock::Extended { tag: EXT_TAG_HF_SCDB, payload: scdb });
+        assert_eq!(ext.hdmi_forum(), Some(hf));
+    }
+
+    #[test]
+    fn extension_summary() {
+        let mut ext = CtaExtension::new();
+        ext.data_blocks = vec![
+            DataBlock::Video(vec![0x90]),
+            DataBlock::Audio(vec![ShortAudioDescriptor::lpcm(2)]),
+            DataBlock::HdrStaticMetadata(HdrStaticMetadata::hdr10_hlg()),
+        ];
+        let text = ext.to_string();
+        assert!(text.contains("Video: 1920x1080@60"));
+        assert!(text.contains("Audio: LPCM 2ch"));
+        assert!(text.contains("HDR: SDR, PQ, HLG"));
+    }
+
+    #[test]
+    fn oversized_data_block_rejected() {
+        let db = DataBlock::Video(vec![1; 32]);
+        assert!(matches!(db.to_bytes(), Err(EdidError::DataBlockTooLong { .. })));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn vrr_range_serde_checks_bounds() {
+        let vrr = VrrRange::new(48, 144).unwrap();
+        assert_eq!(serde_json::to_string(&vrr).unwrap(), r#"{"min_hz":48,"max_hz":144}"#);
+        assert_eq!(serde_json::from_str::<VrrRange>(r#"{"min_hz":48,"max_hz":144}"#).unwrap(), vrr);
+        assert!(serde_json::from_str::<VrrRange>(r#"{"min_hz":0,"max_hz":144}"#).is_err());
+        assert!(serde_json::from_str::<VrrRange>(r#"{"min_hz":60,"max_hz":48}"#).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+//! DisplayID extension blocks carrying Type I detailed timings.
+//!
+//! A base-EDID DTD can't describe a pixel clock above 655.35 MHz, which rules
+//! out modes like 3440x1440@144 or 2560x1440@240.  Monitors advertise those
+//! in a DisplayID 1.3 extension instead, whose Type I timing descriptor has
+//! a 24-bit pixel clock.  Linux, Windows, and macOS all read it.
+
+use super::timing::DetailedTiming;
+use super::{block_checksum, EdidError, EDID_BLOCK_SIZE};
+
+/// Extension tag of a DisplayID block inside an EDID.
+pub const DISPLAYID_EXTENSION_TAG: u8 = 0x70;
+
+/// DisplayID structure version 1.3 (the 1.x layout, which Type I belongs to).
+const DISPLAYID_VERSION: u8 = 0x13;
+/// Data block tag of Type I detailed timings.
+const TAG_TYPE_I_TIMING: u8 = 0x03;
+/// Size of one Type I timing descriptor.
+const TYPE_I_SIZE: usize = 20;
+/// Bytes available for data blocks: the block minus the extension tag,
+/// the 4-byte section header, the section checksum, and the EDID checksum.
+const MAX_SECTION_PAYLOAD: usize = EDID_BLOCK_SIZE - 1 - 4 - 1 - 1;
+
+/// Aspect ratio codes of a Type I descriptor, as (code, width, height).
+const ASPECT_RATIOS: [(u8, u32, u32); 8] = [
+    (0, 1, 1),
+    (1, 5, 4),
+    (2, 4, 3),
+    (3, 15, 9),
+    (4, 16, 9),
+    (5, 16, 10),
+    (6, 64, 27),
+    (7, 256, 135),
+];
+
+/// A DisplayID extension holding Type I detailed timings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayIdExtension {
+    /// Timings, with the preferred flag for each.
+    pub timings: Vec<(DetailedTiming, bool)>,
+}
+
+impl DisplayIdExtension {
+    /// Parse a 128-byte DisplayID extension block, keeping only Type I
+    /// timings.
+    pub fn parse(block: &[u8]) -> Result<Self, EdidError> {
+        if block.len() != EDID_BLOCK_SIZE || block[0] != DISPLAYID_EXTENSION_TAG {
+            return Err(EdidError::NotDisplayIdExtension);
+        }
+        let section_len = block[2] as usize;
+        let end = 5 + section_len;
+        if end > EDID_BLOCK_SIZE - 2 {
+            return Err(EdidError::MalformedDisplayIdBlock { offset: 2 });
+        }
+
+        let mut timings = Vec::new();
+        let mut i = 5;
+        while i + 3 <= end {
+            let (tag, len) = (block[i], block[i + 2] as usize);
+            if tag == 0 && len == 0 {
+                break; // padding
+            }
+            if i + 3 + len > end {
+                return Err(EdidError::MalformedDisplayIdBlock { offset: i });
+            }
+            if tag == TAG_TYPE_I_TIMING {
+                for (n, d) in block[i + 3..i + 3 + len].chunks_exact(TYPE_I_SIZE).enumerate() {
+                    let offset = i + 3 + n * TYPE_I_SIZE;
+                    timings.push(decode_type_i(d).ok_or(EdidError::MalformedDisplayIdBlock { offset })?);
+                }
+            }
+            i += 3 + len;
+        }
+        Ok(Self { timings })
+    }
+
+    /// Serialize to a 128-byte block with valid section and block checksums.
+    pub fn to_block(&self) -> Result<[u8; EDID_BLOCK_SIZE], EdidError> {
+        let mut payload = Vec::with_capacity(self.timings.len() * TYPE_I_SIZE);
+        for (t, preferred) in &self.timings {
+            payload.extend_from_slice(&encode_type_i(t, *preferred)?);
+        }
+        let section_len = if payload.is_empty() { 0 } else { 3 + payload.len() };
+        if section_len > MAX_SECTION_PAYLOAD {
+            return Err(EdidError::DisplayIdBlockOverflow { timings: self.timings.len() });
+        }
+
+        let mut block = [0u8; EDID_BLOCK_SIZE];
+        block[0] = DISPLAYID_EXTENSION_TAG;
+        block[1] = DISPLAYID_VERSION;
+        block[2] = section_len as u8;
+        // block[3]: product type 0 = extension section, block[4]: no extensions
+        if !payload.is_empty() {
+            block[5] = TAG_TYPE_I_TIMING;
+            block[6] = 0; // block revision
+            block[7] = payload.len() as u8;
+            block[8..8 + payload.len()].copy_from_slice(&payload);
+        }
+        let section_end = 5 + section_len;
+        block[section_end] = block_checksum(&block[1..section_end]);
+        block[EDID_BLOCK_SIZE - 1] = block_checksum(&block[..EDID_BLOCK_SIZE - 1]);
+        Ok(block)
+    }
+}
+
+/// Encode a Type I timing.  Fields are stored minus one, and the sync
+/// polarity bits ride on the top of the front porch fields.
+fn encode_type_i(t: &DetailedTiming, preferred: bool) -> Result<[u8; TYPE_I_SIZE], EdidError> {
+    let clock = (t.pixel_clock_khz / 10).checked_sub(1).ok_or(EdidError::TimingOutOfRange)?;
+    let fields = [t.h_active, t.h_blank, t.h_front_porch, t.h_sync_width, t.v_active, t.v_blank, t.v_front_porch, t.v_sync_width];
+    if clock >= 1 << 24 || fields.iter().any(|&f| f == 0 || f > 0x8000) {
+        return Err(EdidError::TimingOutOfRange);
+    }
+
+    let mut d = [0u8; TYPE_I_SIZE];
+    d[0..3].copy_from_slice(&clock.to_le_bytes()[..3]);
+    d[3] = (preferred as u8) << 7 | (t.interlaced as u8) << 4 | aspect_ratio_code(t.h_active, t.v_active);
+    for (i, &f) in fields.iter().enumerate() {
+        let mut v = f - 1;
+        if i == 2 && t.h_sync_positive {
+            v |= 0x8000;
+        }
+        if i == 6 && t.v_sync_positive {
+            v |= 0x8000;
+        }
+        d[4 + i * 2..6 + i * 2].copy_from_slice(&v.to_le_bytes());
+    }
+    Ok(d)
+}
+
+/// Decode a Type I timing, or `None` if a field is 0xffff and so can't be
+/// stored plus one.
+fn decode_type_i(d: &[u8]) -> Option<(DetailedTiming, bool)> {
+    let raw = |i: usize| u16::from_le_bytes([d[4 + i * 2], d[5 + i * 2]]);
+    let field = |i: usize| raw(i).checked_add(1);
+    let clock = u32::from_le_bytes([d[0], d[1], d[2], 0]) + 1;
+    let timing = DetailedTiming {
+        pixel_clock_khz: clock * 10,
+        h_active: field(0)?,
+        h_blank: field(1)?,
+        h_front_porch: (raw(2) & 0x7fff) + 1,
+        h_sync_width: field(3)?,
+        v_active: field(4)?,
+        v_blank: field(5)?,
+        v_front_porch: (raw(6) & 0x7fff) + 1,
+        v_sync_width: field(7)?,
+        h_sync_positive: raw(2) & 0x8000 != 0,
+        v_sync_positive: raw(6) & 0x8000 != 0,
+        interlaced: d[3] & 0x10 != 0,
+    };
+    Some((timing, d[3] & 0x80 != 0))
+}
+
+/// The aspect ratio code closest to `width:height`.
+fn aspect_ratio_code(width: u16, height: u16) -> u8 {
+    let (w, h) = (width as u32, height as u32);
+    ASPECT_RATIOS
+        .iter()
+        .min_by_key(|&&(_, aw, ah)| (w * ah).abs_diff(h * aw) * 1000 / (h * aw))
+        .map_or(0, |&(code, _, _)| code)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edid::VideoMode;
+
+    #[test]
+    fn type_i_roundtrip_above_dtd_limit() {
+        let t = DetailedTiming::cvt_rb(VideoMode::new(3440, 1440, 144));
+        assert!(t.to_dtd().is_err());
+
+        let ext = DisplayIdExtension { timings: vec![(t, true)] };
+        let block = ext.to_block().unwrap();
+        assert_eq!(block.iter().fold(0u8, |a, &b| a.wrapping_add(b)), 0);
+        // Section checksum covers version through the last data byte
+        let section_end = 5 + block[2] as usize;
+        assert_eq!(block[1..=section_end].iter().fold(0u8, |a, &b| a.wrapping_add(b)), 0);
+        assert_eq!(DisplayIdExtension::parse(&block).unwrap(), ext);
+    }
+
+    #[test]
+    fn field_of_0xffff_is_malformed() {
+        let t = DetailedTiming::cvt_rb(VideoMode::new(3440, 1440, 144));
+        let mut block = DisplayIdExtension { timings: vec![(t, true)] }.to_block().unwrap();
+        // h_active of the first timing: after the extension tag, the
+        // 4-byte section header and the 3-byte data block header.
+        let d = 5 + 3;
+        block[d + 4..d + 6].copy_from_slice(&[0xff, 0xff]);
+        assert_eq!(DisplayIdExtension::parse(&block), Err(EdidError::MalformedDisplayIdBlock { offset: d }));
+    }
+
+    #[test]
+    fn overflow_rejected() {
+        let t = DetailedTiming::cvt_rb(VideoMode::new(2560, 1440, 240));
+        let ext = DisplayIdExtension { timings: vec![(t, false); 6] };
+        assert_eq!(ext.to_block(), Err(EdidError::DisplayIdBlockOverflow { timings: 6 }));
+    }
+
+    #[test]
+    fn aspect_ratio_codes() {
+        assert_eq!(aspect_ratio_code(1920, 1080), 4);
+        assert_eq!(aspect_ratio_code(1920, 1200), 5);
+        assert_eq!(aspect_ratio_code(3440, 1440), 6);
+        assert_eq!(aspect_ratio_code(4096, 2160), 7);
+    }
+}
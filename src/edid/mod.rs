@@ -0,0 +1,353 @@
+//! EDID parsing and generation.
+//!
+//! Pure byte manipulation on EDID blobs — nothing in this module talks to a
+//! device.  An [`Edid`] is a 128-byte base block followed by zero or more
+//! 128-byte extension blocks; CTA-861 extensions are handled by
+//! [`CtaExtension`], modes too fast for a DTD by [`DisplayIdExtension`], and
+//! [`EdidBuilder`] assembles a complete EDID from a list of modes, audio
+//! formats, and HDR capabilities.
+
+mod builder;
+mod cta;
+mod displayid;
+mod patch;
+mod presets;
+mod timing;
+
+use thiserror::Error;
+
+pub use builder::EdidBuilder;
+pub use cta::{
+    audio_format, eotf, sample_rate, svd_vic, CtaExtension, DataBlock, HdmiForumVsdb, HdmiVsdb,
+    HdrStaticMetadata, ShortAudioDescriptor, VrrRange, CTA_EXTENSION_TAG, OUI_AMD, OUI_HDMI, OUI_HDMI_FORUM,
+};
+pub use displayid::{DisplayIdExtension, DISPLAYID_EXTENSION_TAG};
+pub use patch::EdidPatch;
+pub use presets::{edid_preset, EdidPreset, EDID_PRESETS};
+pub use timing::{cta_vic, CtaVic, DetailedTiming, VideoMode, CTA_VICS, DTD_MAX_PIXEL_CLOCK_KHZ};
+
+/// Size of every EDID block.
+pub const EDID_BLOCK_SIZE: usize = 128;
+
+/// Fixed 8-byte header at the start of every base EDID block.
+pub const EDID_HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+
+/// Offsets of the four 18-byte descriptors in the base block.
+pub(crate) const BASE_DESCRIPTOR_OFFSETS: [usize; 4] = [54, 72, 90, 108];
+
+/// Offset of the extension count byte in the base block.
+pub(crate) const EXTENSION_COUNT_OFFSET: usize = 126;
+
+/// Errors from parsing, building, or editing an EDID.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum EdidError {
+    /// The blob is empty or not a whole number of 128-byte blocks.
+    #[error("EDID must be a non-empty multiple of 128 bytes, got {0} bytes")]
+    InvalidLength(usize),
+
+    /// The base block does not start with `00 ff ff ff ff ff ff 00`.
+    #[error("EDID header magic is missing")]
+    BadHeader,
+
+    /// A block expected to be a CTA-861 extension has a different tag.
+    #[error("block is not a CTA-861 extension")]
+    NotCtaExtension,
+
+    /// A block expected to be a DisplayID extension has a different tag.
+    #[error("block is not a DisplayID extension")]
+    NotDisplayIdExtension,
+
+    /// A DisplayID data block runs past the end of its section.
+    #[error("malformed DisplayID data block at offset {offset}")]
+    MalformedDisplayIdBlock { offset: usize },
+
+    /// Too many timings for one DisplayID extension block.
+    #[error("{timings} DisplayID timings don't fit in one extension block (maximum 5)")]
+    DisplayIdBlockOverflow { timings: usize },
+
+    /// A data block in the CTA extension runs past the DTD offset.
+    #[error("malformed CTA data block at offset {offset}")]
+    MalformedCtaBlock { offset: usize },
+
+    /// A data block payload exceeds the 31-byte limit.
+    #[error("data block (tag {tag}) is {len} bytes, maximum is 31")]
+    DataBlockTooLong { tag: u8, len: usize },
+
+    /// Data blocks and timings don't fit in one 128-byte extension.
+    #[error("CTA extension needs {needed} bytes but only 128 are available")]
+    CtaBlockOverflow { needed: usize },
+
+    /// A timing's pixel clock cannot be encoded in an 18-byte DTD.
+    #[error("pixel clock {pixel_clock_khz} kHz exceeds the 655.35 MHz DTD limit")]
+    PixelClockTooHigh { pixel_clock_khz: u32 },
+
+    /// A timing field doesn't fit its DTD bit width.
+    #[error("timing value out of range for a detailed timing descriptor")]
+    TimingOutOfRange,
+
+    /// The builder was given no modes.
+    #[error("at least one video mode is required")]
+    NoModes,
+
+    /// Manufacturer IDs are three uppercase letters.
+    #[error("invalid manufacturer ID '{0}' (expected three letters A-Z)")]
+    InvalidManufacturer(String),
+
+    /// Monitor names are at most 13 printable ASCII characters.
+    #[error("invalid monitor name '{0}' (expected up to 13 ASCII characters)")]
+    InvalidMonitorName(String),
+
+    /// A block's bytes don't sum to zero.
+    #[error("block {block} checksum mismatch: stored 0x{stored:02x}, expected 0x{expected:02x}")]
+    BadChecksum { block: usize, stored: u8, expected: u8 },
+
+    /// The base block's extension count disagrees with the blob length.
+    #[error("base block declares {declared} extension block(s) but {actual} are present")]
+    ExtensionCountMismatch { declared: u8, actual: usize },
+}
+
+/// Value that makes a block's bytes sum to zero (mod 256).
+pub(crate) fn block_checksum(data: &[u8]) -> u8 {
+    0u8.wrapping_sub(data.iter().fold(0u8, |acc, &b| acc.wrapping_add(b)))
+}
+
+/// An EDID blob: base block plus extension blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edid {
+    bytes: Vec<u8>,
+}
+
+impl Edid {
+    /// Wrap raw EDID bytes, checking the block structure and header magic.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, EdidError> {
+        if bytes.is_empty() || bytes.len() % EDID_BLOCK_SIZE != 0 {
+            return Err(EdidError::InvalidLength(bytes.len()));
+        }
+        if bytes[..8] != EDID_HEADER {
+            return Err(EdidError::BadHeader);
+        }
+        Ok(Self { bytes })
+    }
+
+    /// Check the header magic, every block checksum, and that the extension
+    /// count in the base block matches the number of blocks present.
+    ///
+    /// Call this before uploading an EDID — a corrupt blob is much easier to
+    /// reject here than to recover from on the card.
+    pub fn validate(&self) -> Result<(), EdidError> {
+        if self.bytes[..8] != EDID_HEADER {
+            return Err(EdidError::BadHeader);
+        }
+        let declared = self.bytes[EXTENSION_COUNT_OFFSET];
+        let actual = self.block_count() - 1;
+        if declared as usize != actual {
+            return Err(EdidError::ExtensionCountMismatch { declared, actual });
+        }
+        for (block, data) in self.bytes.chunks_exact(EDID_BLOCK_SIZE).enumerate() {
+            let stored = data[EDID_BLOCK_SIZE - 1];
+            let expected = block_checksum(&data[..EDID_BLOCK_SIZE - 1]);
+            if stored != expected {
+                return Err(EdidError::BadChecksum { block, stored, expected });
+            }
+        }
+        Ok(())
+    }
+
+    /// Recompute every block checksum.  Returns the indices of the blocks
+    /// that were fixed.
+    pub fn repair_checksums(&mut self) -> Vec<usize> {
+        let mut fixed = Vec::new();
+        for (block, data) in self.bytes.chunks_exact_mut(EDID_BLOCK_SIZE).enumerate() {
+            let expected = block_checksum(&data[..EDID_BLOCK_SIZE - 1]);
+            if data[EDID_BLOCK_SIZE - 1] != expected {
+                data[EDID_BLOCK_SIZE - 1] = expected;
+                fixed.push(block);
+            }
+        }
+        fixed
+    }
+
+    /// The raw EDID bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consume the EDID and return its bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Number of 128-byte blocks, including the base block.
+    pub fn block_count(&self) -> usize {
+        self.bytes.len() / EDID_BLOCK_SIZE
+    }
+
+    /// Block `index` (0 = base block).
+    pub fn block(&self, index: usize) -> Option<&[u8]> {
+        self.bytes.chunks_exact(EDID_BLOCK_SIZE).nth(index)
+    }
+
+    /// Three-letter PNP manufacturer ID (e.g. `GSM` for LG).
+    pub fn manufacturer_id(&self) -> String {
+        let v = u16::from_be_bytes([self.bytes[8], self.bytes[9]]);
+        [(v >> 10) & 0x1f, (v >> 5) & 0x1f, v & 0x1f]
+            .iter()
+            .map(|&c| (b'A' - 1 + c as u8) as char)
+            .collect()
+    }
+
+    /// Manufacturer product code.
+    pub fn product_code(&self) -> u16 {
+        u16::from_le_bytes([self.bytes[10], self.bytes[11]])
+    }
+
+    /// Monitor name from the base block's `0xfc` display descriptor, e.g.
+    /// `LG ULTRAGEAR`.
+    pub fn monitor_name(&self) -> Option<String> {
+        BASE_DESCRIPTOR_OFFSETS.iter().find_map(|&o| {
+            let d = &self.bytes[o..o + 18];
+            if d[..3] != [0, 0, 0] || d[3] != 0xfc {
+                return None;
+            }
+            let text = &d[5..];
+            let end = text.iter().position(|&b| b == 0x0a).unwrap_or(text.len());
+            let name = String::from_utf8_lossy(&text[..end]).trim_end().to_string();
+            (!name.is_empty()).then_some(name)
+        })
+    }
+
+    /// Detailed timings from the base block descriptors.
+    pub fn detailed_timings(&self) -> Vec<DetailedTiming> {
+        BASE_DESCRIPTOR_OFFSETS
+            .iter()
+            .filter_map(|&o| {
+                let d: &[u8; 18] = self.bytes[o..o + 18].try_into().ok()?;
+                DetailedTiming::from_dtd(d)
+            })
+            .collect()
+    }
+
+    /// The first DisplayID extension block, parsed.
+    pub fn displayid_extension(&self) -> Option<Result<DisplayIdExtension, EdidError>> {
+        self.bytes
+            .chunks_exact(EDID_BLOCK_SIZE)
+            .skip(1)
+            .find(|b| b[0] == DISPLAYID_EXTENSION_TAG)
+            .map(DisplayIdExtension::parse)
+    }
+
+    /// Every CTA-861 extension block, parsed, in order.
+    pub fn cta_extensions(&self) -> Vec<Result<CtaExtension, EdidError>> {
+        self.bytes
+            .chunks_exact(EDID_BLOCK_SIZE)
+            .skip(1)
+            .filter(|b| b[0] == CTA_EXTENSION_TAG)
+            .map(CtaExtension::parse)
+            .collect()
+    }
+
+    /// The first CTA-861 extension block, parsed.
+    pub fn cta_extension(&self) -> Option<Result<CtaExtension, EdidError>> {
+        self.bytes
+            .chunks_exact(EDID_BLOCK_SIZE)
+            .skip(1)
+            .find(|b| b[0] == CTA_EXTENSION_TAG)
+            .map(CtaExtension::parse)
+    }
+}
+
+/// Serialized as the raw bytes; deserializing checks them like
+/// [`Edid::from_bytes`].
+#[cfg(feature = "serde")]
+impl serde::Serialize for Edid {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.bytes)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Edid {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        Self::from_bytes(bytes).map_err(serde::de::Error::custom)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_bytes_rejects_bad_length_and_header() {
+        assert_eq!(Edid::from_bytes(vec![]), Err(EdidError::InvalidLength(0)));
+        assert_eq!(Edid::from_bytes(vec![0; 100]), Err(EdidError::InvalidLength(100)));
+        assert_eq!(Edid::from_bytes(vec![0; 128]), Err(EdidError::BadHeader));
+    }
+
+    fn valid_base_block() -> Vec<u8> {
+        let mut bytes = vec![0u8; 128];
+        bytes[..8].copy_from_slice(&EDID_HEADER);
+        bytes[127] = block_checksum(&bytes[..127]);
+        bytes
+    }
+
+    #[test]
+    fn validate_accepts_consistent_edid() {
+        let edid = Edid::from_bytes(valid_base_block()).unwrap();
+        assert_eq!(edid.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_detects_checksum_and_extension_count() {
+        let mut bytes = valid_base_block();
+        bytes[20] = 0x80;
+        let mut edid = Edid::from_bytes(bytes.clone()).unwrap();
+        assert!(matches!(edid.validate(), Err(EdidError::BadChecksum { block: 0, .. })));
+        assert_eq!(edid.repair_checksums(), vec![0]);
+        assert_eq!(edid.validate(), Ok(()));
+        assert!(edid.repair_checksums().is_empty());
+
+        // Extension count says 1, but no extension block follows
+        bytes[126] = 1;
+        bytes[127] = block_checksum(&bytes[..127]);
+        let edid = Edid::from_bytes(bytes).unwrap();
+        assert_eq!(
+            edid.validate(),
+            Err(EdidError::ExtensionCountMismatch { declared: 1, actual: 0 })
+        );
+    }
+
+    #[test]
+    fn monitor_name_parsing() {
+        let mut bytes = valid_base_block();
+        assert_eq!(Edid::from_bytes(bytes.clone()).unwrap().monitor_name(), None);
+        bytes[72 + 3] = 0xfc;
+        bytes[72 + 5..72 + 18].copy_from_slice(b"LG ULTRAGEAR\n");
+        assert_eq!(Edid::from_bytes(bytes).unwrap().monitor_name().as_deref(), Some("LG ULTRAGEAR"));
+    }
+
+    #[test]
+    fn manufacturer_id_decoding() {
+        let mut bytes = vec![0u8; 128];
+        bytes[..8].copy_from_slice(&EDID_HEADER);
+        // "GSM" = (7 << 10) | (19 << 5) | 13 = 0x1e6d
+        bytes[8] = 0x1e;
+        bytes[9] = 0x6d;
+        let edid = Edid::from_bytes(bytes).unwrap();
+        assert_eq!(edid.manufacturer_id(), "GSM");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrips_bytes_and_checks_header() {
+        let edid = Edid::from_bytes(valid_base_block()).unwrap();
+        let json = serde_json::to_string(&edid).unwrap();
+        assert_eq!(serde_json::from_str::<Edid>(&json).unwrap(), edid);
+        let zeros = serde_json::to_string(&vec![0u8; 128]).unwrap();
+        assert!(serde_json::from_str::<Edid>(&zeros).is_err());
+    }
+}
@@ -0,0 +1,580 @@
+//! [`EdidPatch`]: derive a variant of an existing EDID.
+//!
+//! Patches edit an EDID in place — typically one read from the user's own
+//! display — rather than generating a new one, so everything the patch
+//! doesn't touch (manufacturer, name, colour data, vendor blocks) survives.
+//! Every CTA-861 extension is patched, and all checksums are recomputed.
+
+use super::builder::dummy_descriptor;
+use super::cta::{
+    hdmi_vic_span, svd_vic, CtaExtension, DataBlock, HdrStaticMetadata, ShortAudioDescriptor, VrrRange, CTA_EXTENSION_TAG,
+    EXT_TAG_HF_SCDB, OUI_AMD, OUI_HDMI, OUI_HDMI_FORUM,
+};
+use super::displayid::{DisplayIdExtension, DISPLAYID_EXTENSION_TAG};
+use super::timing::{cta_vic, DetailedTiming};
+use super::{block_checksum, Edid, EdidError, BASE_DESCRIPTOR_OFFSETS, EDID_BLOCK_SIZE};
+
+/// Extended tag of the HDR Dynamic Metadata data block.
+const EXT_TAG_HDR_DYNAMIC_METADATA: u8 = 7;
+/// Extended tag of the YCbCr 4:2:0 Video data block (VIC list).
+const EXT_TAG_YCBCR420_VIDEO: u8 = 14;
+/// Extended tag of the YCbCr 4:2:0 Capability Map data block (SVD bitmap).
+const EXT_TAG_YCBCR420_CAPABILITY_MAP: u8 = 15;
+/// HF-VSDB feature byte flags (payload offset 4).
+const HF_ALLM: u8 = 0x02;
+/// CNMVRR, CinemaVRR and M_delta: only meaningful alongside a VRR range.
+const HF_VRR_FLAGS: u8 = 0x38;
+/// Active lines of every HDMI 1.4 VIC (4K24/25/30 and 4096x2160@24).
+const HDMI_VIC_HEIGHT: u16 = 2160;
+
+/// A transformation applied to an existing EDID.
+///
+/// ```
+/// use elgato4k_linux::edid::{EdidBuilder, EdidPatch, VideoMode};
+///
+/// let mut edid = EdidBuilder::new()
+///     .mode(VideoMode::new(3840, 2160, 60))
+///     .mode(VideoMode::new(2560, 1440, 60))
+///     .build()?;
+/// EdidPatch::MaxHeight(1440).apply(&mut edid)?;
+/// assert!(edid.detailed_timings().iter().all(|t| t.v_active <= 1440));
+/// # Ok::<(), elgato4k_linux::edid::EdidError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdidPatch {
+    /// Remove the HDR static and dynamic metadata blocks, so sources send SDR.
+    StripHdr,
+    /// Replace the HDR static metadata block (supported EOTFs and luminance
+    /// hints), adding one if the EDID has none.
+    SetHdr(HdrStaticMetadata),
+    /// Remove all audio and speaker allocation blocks.
+    StripAudio,
+    /// Replace every audio descriptor with a single 2-channel LPCM one.
+    ForceStereo,
+    /// Remove every mode with more than this many active lines
+    /// (`MaxHeight(1440)` drops 4K but keeps 3440x1440).
+    MaxHeight(u16),
+    /// Advertise RGB only: clear the YCbCr 4:4:4 / 4:2:2 flags and drop the
+    /// 4:2:0 blocks, so PCs send full-chroma RGB instead of TV formats.
+    RgbOnly,
+    /// Set or clear the Auto Low Latency Mode flag.  Setting it adds a
+    /// minimal HDMI Forum block if the EDID has none.
+    Allm(bool),
+    /// Advertise HDMI VRR over a range, or (`None`) strip the VRR range and
+    /// the AMD FreeSync block so sources stay on a fixed refresh rate.
+    Vrr(Option<VrrRange>),
+    /// Remove the HDMI Forum blocks entirely (HF-VSDB and HF-SCDB), dropping
+    /// ALLM, VRR, and FRL.  Sources are then limited to 340 MHz TMDS, so
+    /// 4K60 falls back to 4:2:0 or is no longer offered.
+    StripHdmiForum,
+}
+
+impl EdidPatch {
+    /// Apply the patch to `edid`, recomputing checksums.
+    ///
+    /// Fails with [`EdidError::NoModes`] if `MaxHeight` would leave the
+    /// EDID without a preferred timing.
+    pub fn apply(self, edid: &mut Edid) -> Result<(), EdidError> {
+        // Work on a copy so a failed patch leaves `edid` untouched
+        let mut patched = edid.clone();
+        self.patch_blocks(&mut patched)?;
+        *edid = patched;
+        Ok(())
+    }
+
+    fn patch_blocks(self, edid: &mut Edid) -> Result<(), EdidError> {
+        for index in 1..edid.block_count() {
+            let range = index * EDID_BLOCK_SIZE..(index + 1) * EDID_BLOCK_SIZE;
+            match (edid.bytes[range.start], self) {
+                (CTA_EXTENSION_TAG, _) => {
+                    let mut cta = CtaExtension::parse(&edid.bytes[range.clone()])?;
+                    self.patch_cta(&mut cta);
+                    edid.bytes[range].copy_from_slice(&cta.to_block()?);
+                }
+                (DISPLAYID_EXTENSION_TAG, Self::MaxHeight(max)) => {
+                    let mut displayid = DisplayIdExtension::parse(&edid.bytes[range.clone()])?;
+                    displayid.timings.retain(|(t, _)| t.v_active <= max);
+                    edid.bytes[range].copy_from_slice(&displayid.to_block()?);
+                }
+                _ => {}
+            }
+        }
+
+        match self {
+            Self::MaxHeight(max) => cap_base_block(edid, max)?,
+            Self::RgbOnly => {
+                // Base block feature support: colour encoding bits 3-4
+                edid.bytes[24] &= !0x18;
+                edid.bytes[EDID_BLOCK_SIZE - 1] = block_checksum(&edid.bytes[..EDID_BLOCK_SIZE - 1]);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn patch_cta(self, cta: &mut CtaExtension) {
+        match self {
+            Self::StripHdr => cta.data_blocks.retain(|db| {
+                !matches!(
+                    db,
+                    DataBlock::HdrStaticMetadata(_)
+                        | DataBlock::Extended { tag: EXT_TAG_HDR_DYNAMIC_METADATA, .. }
+                )
+            }),
+            Self::SetHdr(hdr) => {
+                let existing = cta.data_blocks.iter_mut().find_map(|db| match db {
+                    DataBlock::HdrStaticMetadata(h) => Some(h),
+                    _ => None,
+                });
+                match existing {
+                    Some(h) => *h = hdr,
+                    None => cta.data_blocks.push(DataBlock::HdrStaticMetadata(hdr)),
+                }
+            }
+            Self::StripAudio => {
+                cta.basic_audio = false;
+                cta.data_blocks
+                    .retain(|db| !matches!(db, DataBlock::Audio(_) | DataBlock::SpeakerAllocation(_)));
+            }
+            Self::ForceStereo => force_stereo(cta),
+            Self::MaxHeight(max) => cap_cta(cta, max),
+            Self::RgbOnly => {
+                cta.ycbcr444 = false;
+                cta.ycbcr422 = false;
+                cta.data_blocks.retain(|db| {
+                    !matches!(
+                        db,
+                        DataBlock::Extended {
+                            tag: EXT_TAG_YCBCR420_VIDEO | EXT_TAG_YCBCR420_CAPABILITY_MAP,
+                            ..
+                        }
+                    )
+                });
+            }
+            Self::Allm(on) => {
+                for (payload, base) in hdmi_forum_blocks(cta, on) {
+                    set_flag(payload, base + 4, HF_ALLM, on);
+                }
+            }
+            Self::Vrr(range) => {
+                if range.is_none() {
+                    cta.data_blocks
+                        .retain(|db| !matches!(db, DataBlock::VendorSpecific { oui: OUI_AMD, .. }));
+                }
+                for (payload, base) in hdmi_forum_blocks(cta, range.is_some()) {
+                    set_vrr(payload, base, range);
+                }
+            }
+            Self::StripHdmiForum => cta.data_blocks.retain(|db| {
+                !matches!(
+                    db,
+                    DataBlock::VendorSpecific { oui: OUI_HDMI_FORUM, .. }
+                        | DataBlock::Extended { tag: EXT_TAG_HF_SCDB, .. }
+                )
+            }),
+        }
+    }
+}
+
+impl Edid {
+    /// Apply several patches in order.
+    pub fn apply_patches(&mut self, patches: &[EdidPatch]) -> Result<(), EdidError> {
+        patches.iter().try_for_each(|p| p.apply(self))
+    }
+}
+
+/// Collapse the audio blocks into one 2ch LPCM descriptor, keeping the
+/// sample rates and bit depths of the original LPCM entry where there is one.
+fn force_stereo(cta: &mut CtaExtension) {
+    let Some(first) = cta.data_blocks.iter().position(|db| matches!(db, DataBlock::Audio(_))) else {
+        return;
+    };
+    let mut stereo = ShortAudioDescriptor::lpcm(2);
+    if let Some(lpcm) = cta.audio_descriptors().into_iter().find(|s| !s.is_bitstream()) {
+        stereo.sample_rates = lpcm.sample_rates;
+        stereo.detail = lpcm.detail;
+    }
+
+    cta.data_blocks[first] = DataBlock::Audio(vec![stereo]);
+    let mut i = 0;
+    cta.data_blocks.retain(|db| {
+        let keep = i == first || !matches!(db, DataBlock::Audio(_));
+        i += 1;
+        keep
+    });
+    for db in &mut cta.data_blocks {
+        if let DataBlock::SpeakerAllocation(alloc) = db {
+            *alloc = [0x01, 0x00, 0x00]; // FL/FR
+        }
+    }
+    cta.basic_audio = true;
+}
+
+/// Payloads of the HF-VSDB / HF-SCDB blocks, each with the offset of the
+/// Version byte (the shared fields start there).  With `create`, a minimal
+/// HF-VSDB is appended when neither exists.
+fn hdmi_forum_blocks(cta: &mut CtaExtension, create: bool) -> Vec<(&mut Vec<u8>, usize)> {
+    let has_any = cta.data_blocks.iter().any(|db| {
+        matches!(
+            db,
+            DataBlock::VendorSpecific { oui: OUI_HDMI_FORUM, .. } | DataBlock::Extended { tag: EXT_TAG_HF_SCDB, .. }
+        )
+    });
+    if create && !has_any {
+        // Version 1, no TMDS above 340 MHz, no SCDC, no FRL
+        cta.data_blocks.push(DataBlock::VendorSpecific {
+            oui: OUI_HDMI_FORUM,
+            payload: vec![0x01, 0x00, 0x00, 0x00, 0x00],
+        });
+    }
+    cta.data_blocks
+        .iter_mut()
+        .filter_map(|db| match db {
+            DataBlock::VendorSpecific { oui: OUI_HDMI_FORUM, payload } => Some((payload, 0)),
+            DataBlock::Extended { tag: EXT_TAG_HF_SCDB, payload } => Some((payload, 2)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Set or clear `flag` at `index`, zero-extending the payload if needed.
+fn set_flag(payload: &mut Vec<u8>, index: usize, flag: u8, on: bool) {
+    if payload.len() <= index {
+        if !on {
+            return;
+        }
+        payload.resize(index + 1, 0);
+    }
+    if on {
+        payload[index] |= flag;
+    } else {
+        payload[index] &= !flag;
+    }
+}
+
+/// Write (or clear) VRRmin/VRRmax, which follow the feature byte.
+fn set_vrr(payload: &mut Vec<u8>, base: usize, range: Option<VrrRange>) {
+    match range {
+        Some(range) => {
+            if payload.len() < base + 7 {
+                payload.resize(base + 7, 0);
+            }
+            payload[base + 5..base + 7].copy_from_slice(&range.to_bytes());
+        }
+        None => {
+            set_flag(payload, base + 4, HF_VRR_FLAGS, false);
+            if payload.len() >= base + 7 {
+                payload[base + 5..base + 7].fill(0);
+            }
+        }
+    }
+}
+
+/// Whether a VIC is known to exceed `max` lines.  Unknown VICs are kept.
+fn vic_too_tall(vic: u8, max: u16) -> bool {
+    cta_vic(vic).is_some_and(|v| v.height > max)
+}
+
+/// Drop SVDs, 4:2:0 VICs, HDMI VICs, and DTDs taller than `max`.
+fn cap_cta(cta: &mut CtaExtension, max: u16) {
+    // The 4:2:0 capability map indexes SVDs in order across all video
+    // blocks, so record which positions survive before filtering.
+    let kept: Vec<bool> = cta
+        .data_blocks
+        .iter()
+        .filter_map(|db| match db {
+            DataBlock::Video(svds) => Some(svds.iter().map(|&s| !vic_too_tall(svd_vic(s), max))),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+
+    for db in &mut cta.data_blocks {
+        match db {
+            DataBlock::Video(svds) => svds.retain(|&s| !vic_too_tall(svd_vic(s), max)),
+            DataBlock::Extended { tag: EXT_TAG_YCBCR420_VIDEO, payload } => {
+                payload.retain(|&s| !vic_too_tall(svd_vic(s), max));
+            }
+            DataBlock::Extended { tag: EXT_TAG_YCBCR420_CAPABILITY_MAP, payload } if !payload.is_empty() => {
+                *payload = filter_bitmap(payload, &kept);
+            }
+            DataBlock::VendorSpecific { oui: OUI_HDMI, payload } if max < HDMI_VIC_HEIGHT => {
+                strip_hdmi_vics(payload);
+            }
+            _ => {}
+        }
+    }
+    cta.data_blocks.retain(|db| match db {
+        DataBlock::Video(svds) => !svds.is_empty(),
+        DataBlock::Extended { tag: EXT_TAG_YCBCR420_VIDEO, payload } => !payload.is_empty(),
+        _ => true,
+    });
+
+    let before = cta.detailed_timings.len();
+    cta.detailed_timings.retain(|t| t.v_active <= max);
+    let removed = (before - cta.detailed_timings.len()) as u8;
+    cta.native_dtds = cta.native_dtds.saturating_sub(removed);
+}
+
+/// Keep only the bits of an SVD bitmap whose position is marked in `kept`.
+fn filter_bitmap(bitmap: &[u8], kept: &[bool]) -> Vec<u8> {
+    let bits: Vec<bool> = kept
+        .iter()
+        .enumerate()
+        .filter(|&(_, &k)| k)
+        .map(|(i, _)| bitmap.get(i / 8).is_some_and(|b| b & (1 << (i % 8)) != 0))
+        .collect();
+    let mut out = vec![0u8; bits.len().div_ceil(8).max(1)];
+    for (i, _) in bits.iter().enumerate().filter(|&(_, &b)| b) {
+        out[i / 8] |= 1 << (i % 8);
+    }
+    out
+}
+
+/// Remove the HDMI_VIC list from an HDMI 1.x VSDB payload (after the OUI).
+fn strip_hdmi_vics(payload: &mut Vec<u8>) {
+    if let Some((len_index, count)) = hdmi_vic_span(payload) {
+        payload.drain(len_index + 1..len_index + 1 + count);
+        payload[len_index] &= 0x1f;
+    }
+}
+
+/// Replace base-block DTDs and standard timings taller than `max`.
+///
+/// If the preferred timing goes, the first surviving base DTD takes its
+/// place, falling back to the largest fitting timing from the CTA block.
+fn cap_base_block(edid: &mut Edid, max: u16) -> Result<(), EdidError> {
+    let dtd_at = |bytes: &[u8], o: usize| -> Option<DetailedTiming> {
+        DetailedTiming::from_dtd(bytes[o..o + 18].try_into().ok()?)
+    };
+    let [preferred_offset, ..] = BASE_DESCRIPTOR_OFFSETS;
+
+    let mut dropped_preferred = false;
+    for &o in &BASE_DESCRIPTOR_OFFSETS {
+        if dtd_at(&edid.bytes, o).is_some_and(|t| t.v_active > max) {
+            dropped_preferred |= o == preferred_offset;
+            edid.bytes[o..o + 18].copy_from_slice(&dummy_descriptor());
+        }
+    }
+
+    if dropped_preferred {
+        let promoted = BASE_DESCRIPTOR_OFFSETS[1..]
+            .iter()
+            .find_map(|&o| dtd_at(&edid.bytes, o).map(|t| (o, t)));
+        let replacement = match promoted {
+            Some((o, t)) => {
+                edid.bytes[o..o + 18].copy_from_slice(&dummy_descriptor());
+                t
+            }
+            None => largest_cta_timing(edid).ok_or(EdidError::NoModes)?,
+        };
+        edid.bytes[preferred_offset..preferred_offset + 18].copy_from_slice(&replacement.to_dtd()?);
+    }
+
+    // Standard timings: 8 two-byte entries at 38..54, `01 01` when unused
+    for entry in edid.bytes[38..54].chunks_exact_mut(2) {
+        if standard_timing_height(entry[0], entry[1]).is_some_and(|h| h > max) {
+            entry.copy_from_slice(&[0x01, 0x01]);
+        }
+    }
+
+    edid.bytes[EDID_BLOCK_SIZE - 1] = block_checksum(&edid.bytes[..EDID_BLOCK_SIZE - 1]);
+    Ok(())
+}
+
+/// The largest DTD-encodable timing left in any (already capped) CTA block.
+fn largest_cta_timing(edid: &Edid) -> Option<DetailedTiming> {
+    let cta = edid.cta_extension()?.ok()?;
+    let vic_timings = cta.vics().into_iter().filter_map(|v| cta_vic(v).map(|v| v.timing()));
+    cta.detailed_timings
+        .iter()
+        .copied()
+        .chain(vic_timings)
+        .filter(|t| t.to_dtd().is_ok())
+        .max_by_key(|t| (t.h_active as u32 * t.v_active as u32, t.pixel_clock_khz))
+}
+
+/// Active lines of a standard timing entry, or `None` if unused.
+fn standard_timing_height(b0: u8, b1: u8) -> Option<u16> {
+    if b0 == 0x00 || (b0 == 0x01 && b1 == 0x01) {
+        return None;
+    }
+    let width = (b0 as u32 + 31) * 8;
+    let height = match b1 >> 6 {
+        0 => width * 10 / 16,
+        1 => width * 3 / 4,
+        2 => width * 4 / 5,
+        _ => width * 9 / 16,
+    };
+    Some(height as u16)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edid::{EdidBuilder, HdrStaticMetadata, VideoMode};
+
+    fn sample() -> Edid {
+        EdidBuilder::new()
+            .mode(VideoMode::new(3840, 2160, 60))
+            .mode(VideoMode::new(1920, 1080, 60))
+            .mode(VideoMode::new(2560, 1440, 144))
+            .audio(ShortAudioDescriptor::lpcm(8))
+            .audio(ShortAudioDescriptor::ac3(6))
+            .hdr(HdrStaticMetadata::hdr10_hlg())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn strip_hdr_removes_metadata_block() {
+        let mut edid = sample();
+        EdidPatch::StripHdr.apply(&mut edid).unwrap();
+        assert_eq!(edid.validate(), Ok(()));
+        assert!(edid.cta_extension().unwrap().unwrap().hdr_static_metadata().is_none());
+    }
+
+    #[test]
+    fn set_hdr_replaces_or_adds_block() {
+        let hdr = HdrStaticMetadata {
+            eotfs: crate::edid::eotf::SDR | crate::edid::eotf::PQ,
+            ..HdrStaticMetadata::hdr10_hlg().with_luminance(600.0, 400.0, 0.1)
+        };
+        let mut edid = sample();
+        EdidPatch::SetHdr(hdr).apply(&mut edid).unwrap();
+        assert_eq!(edid.cta_extension().unwrap().unwrap().hdr_static_metadata(), Some(&hdr));
+
+        EdidPatch::StripHdr.apply(&mut edid).unwrap();
+        EdidPatch::SetHdr(hdr).apply(&mut edid).unwrap();
+        assert_eq!(edid.validate(), Ok(()));
+        assert_eq!(edid.cta_extension().unwrap().unwrap().hdr_static_metadata(), Some(&hdr));
+    }
+
+    #[test]
+    fn force_stereo_collapses_audio() {
+        let mut edid = sample();
+        EdidPatch::ForceStereo.apply(&mut edid).unwrap();
+        assert_eq!(edid.validate(), Ok(()));
+        let cta = edid.cta_extension().unwrap().unwrap();
+        assert_eq!(cta.audio_descriptors(), vec![ShortAudioDescriptor::lpcm(2)]);
+        assert!(cta.data_blocks.contains(&DataBlock::SpeakerAllocation([0x01, 0, 0])));
+    }
+
+    #[test]
+    fn rgb_only_clears_ycbcr() {
+        let mut edid = sample();
+        EdidPatch::RgbOnly.apply(&mut edid).unwrap();
+        assert_eq!(edid.validate(), Ok(()));
+        assert_eq!(edid.as_bytes()[24] & 0x18, 0);
+        let cta = edid.cta_extension().unwrap().unwrap();
+        assert!(!cta.ycbcr444 && !cta.ycbcr422);
+    }
+
+    #[test]
+    fn strip_audio_removes_blocks() {
+        let mut edid = sample();
+        EdidPatch::StripAudio.apply(&mut edid).unwrap();
+        let cta = edid.cta_extension().unwrap().unwrap();
+        assert!(cta.audio_descriptors().is_empty());
+        assert!(!cta.basic_audio);
+    }
+
+    #[test]
+    fn max_height_promotes_new_preferred_timing() {
+        let mut edid = sample();
+        EdidPatch::MaxHeight(1440).apply(&mut edid).unwrap();
+        assert_eq!(edid.validate(), Ok(()));
+        // 4K was preferred; 1440p144 was the only other base DTD
+        let dtds = edid.detailed_timings();
+        assert_eq!(dtds.len(), 1);
+        assert_eq!((dtds[0].h_active, dtds[0].v_active), (2560, 1440));
+        let cta = edid.cta_extension().unwrap().unwrap();
+        assert_eq!(cta.vics(), vec![16]);
+    }
+
+    #[test]
+    fn max_height_falls_back_to_cta_timing() {
+        let mut edid = EdidBuilder::new()
+            .mode(VideoMode::new(3840, 2160, 60))
+            .mode(VideoMode::new(1920, 1080, 60))
+            .build()
+            .unwrap();
+        EdidPatch::MaxHeight(1080).apply(&mut edid).unwrap();
+        assert_eq!(edid.detailed_timings()[0].pixel_clock_khz, 148_500);
+
+        let before = edid.clone();
+        assert_eq!(EdidPatch::MaxHeight(480).apply(&mut edid), Err(EdidError::NoModes));
+        assert_eq!(edid, before);
+    }
+
+    #[test]
+    fn allm_and_vrr_toggle() {
+        let mut edid = sample();
+        EdidPatch::Allm(true).apply(&mut edid).unwrap();
+        EdidPatch::Vrr(VrrRange::new(40, 144)).apply(&mut edid).unwrap();
+        assert_eq!(edid.validate(), Ok(()));
+        assert_eq!(hf_payload(&edid), Some(vec![0x01, 120, 0x88, 0x60, 0x02, 40, 144]));
+
+        EdidPatch::Vrr(None).apply(&mut edid).unwrap();
+        EdidPatch::Allm(false).apply(&mut edid).unwrap();
+        assert_eq!(hf_payload(&edid), Some(vec![0x01, 120, 0x88, 0x60, 0x00, 0, 0]));
+
+        EdidPatch::StripHdmiForum.apply(&mut edid).unwrap();
+        assert_eq!(hf_payload(&edid), None);
+    }
+
+    #[test]
+    fn allm_adds_hdmi_forum_block_when_missing() {
+        let mut edid = EdidBuilder::new().mode(VideoMode::new(1920, 1080, 60)).build().unwrap();
+        assert_eq!(hf_payload(&edid), None);
+        EdidPatch::Allm(false).apply(&mut edid).unwrap();
+        assert_eq!(hf_payload(&edid), None);
+        EdidPatch::Allm(true).apply(&mut edid).unwrap();
+        assert_eq!(hf_payload(&edid), Some(vec![0x01, 0x00, 0x00, 0x00, 0x02]));
+    }
+
+    fn hf_payload(edid: &Edid) -> Option<Vec<u8>> {
+        edid.cta_extension()?.ok()?.data_blocks.into_iter().find_map(|db| match db {
+            DataBlock::VendorSpecific { oui: OUI_HDMI_FORUM, payload } => Some(payload),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn max_height_filters_displayid_timings() {
+        let mut edid = EdidBuilder::new()
+            .mode(VideoMode::new(1920, 1080, 60))
+            .mode(VideoMode::new(3440, 1440, 144))
+            .build()
+            .unwrap();
+        EdidPatch::MaxHeight(1080).apply(&mut edid).unwrap();
+        assert_eq!(edid.validate(), Ok(()));
+        assert!(edid.displayid_extension().unwrap().unwrap().timings.is_empty());
+    }
+
+    #[test]
+    fn hdmi_vics_are_stripped() {
+        // phys addr, flags, TMDS, latency flags (video present), 3D flags, len (2 VICs), VICs
+        let mut payload = vec![0x10, 0x00, 0x00, 0x3c, 0x20, 0x00, 0x40, 0x01, 0x03];
+        strip_hdmi_vics(&mut payload);
+        assert_eq!(payload, vec![0x10, 0x00, 0x00, 0x3c, 0x20, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn capability_map_follows_kept_svds() {
+        // SVDs 0..3 all 4:2:0-capable; drop the second
+        assert_eq!(filter_bitmap(&[0b1111], &[true, false, true, true]), vec![0b111]);
+        assert_eq!(filter_bitmap(&[0b0010], &[true, true]), vec![0b10]);
+    }
+
+    #[test]
+    fn standard_timing_decoding() {
+        assert_eq!(standard_timing_height(0xd1, 0xc0), Some(1080)); // 1920x1080
+        assert_eq!(standard_timing_height(0x01, 0x01), None);
+    }
+}
@@ -0,0 +1,191 @@
+//! Built-in EDID presets for modes the card's stock EDIDs don't offer.
+//!
+//! Each preset is a mode list fed to [`EdidBuilder`].  The first mode is the
+//! one the preset is named after; the preferred (native) timing is the first
+//! one that fits a base-block DTD, so sources that ignore extension blocks
+//! still land on a sane mode.
+
+use super::builder::EdidBuilder;
+use super::cta::{HdrStaticMetadata, ShortAudioDescriptor};
+use super::timing::VideoMode;
+use super::{Edid, EdidError};
+
+/// A named, built-in EDID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EdidPreset {
+    /// CLI name, e.g. `ultrawide-144`.
+    pub name: &'static str,
+    /// One-line description for `--help` style listings.
+    pub description: &'static str,
+    /// Monitor name the source will display.
+    pub monitor_name: &'static str,
+    /// Modes, headline mode first.
+    pub modes: &'static [VideoMode],
+    /// Advertise HDR10 / HLG.
+    pub hdr: bool,
+    /// Advertise RGB only (no YCbCr), so PCs send full 4:4:4 chroma.
+    pub rgb_only: bool,
+}
+
+impl EdidPreset {
+    /// Names of all presets, for CLI error messages.
+    pub const VALID_VALUES: &'static str = "ultrawide-144, 1440p-240, 4k-120-hdr, 1080p-240, pc-444";
+
+    /// A builder preloaded with this preset, for further tweaks.
+    pub fn builder(&self) -> EdidBuilder {
+        let builder = self.modes.iter().fold(
+            EdidBuilder::new().monitor_name(self.monitor_name).audio(ShortAudioDescriptor::lpcm(2)),
+            |b, &m| b.mode(m),
+        );
+        let builder = if self.rgb_only { builder.ycbcr(false, false) } else { builder };
+        if self.hdr {
+            builder.hdr(HdrStaticMetadata::hdr10_hlg())
+        } else {
+            builder
+        }
+    }
+
+    /// Generate the EDID.
+    pub fn build(&self) -> Result<Edid, EdidError> {
+        self.builder().build()
+    }
+}
+
+/// All built-in presets.
+pub const EDID_PRESETS: &[EdidPreset] = &[
+    EdidPreset {
+        name: "ultrawide-144",
+        description: "3440x1440 up to 144 Hz",
+        monitor_name: "Elgato UW144",
+        modes: &[
+            VideoMode::new(3440, 1440, 144),
+            VideoMode::new(3440, 1440, 120),
+            VideoMode::new(3440, 1440, 60),
+            VideoMode::new(3440, 1440, 100),
+            VideoMode::new(2560, 1440, 60),
+            VideoMode::new(1920, 1080, 60),
+        ],
+        hdr: false,
+        rgb_only: false,
+    },
+    EdidPreset {
+        name: "1440p-240",
+        description: "2560x1440 up to 240 Hz",
+        monitor_name: "Elgato 1440p",
+        modes: &[
+            VideoMode::new(2560, 1440, 240),
+            VideoMode::new(2560, 1440, 165),
+            VideoMode::new(2560, 1440, 144),
+            VideoMode::new(2560, 1440, 120),
+            VideoMode::new(2560, 1440, 60),
+            VideoMode::new(1920, 1080, 60),
+        ],
+        hdr: false,
+        rgb_only: false,
+    },
+    EdidPreset {
+        name: "4k-120-hdr",
+        description: "3840x2160 up to 120 Hz with HDR10/HLG",
+        monitor_name: "Elgato 4K120",
+        modes: &[
+            VideoMode::new(3840, 2160, 120),
+            VideoMode::new(3840, 2160, 60),
+            VideoMode::new(2560, 1440, 120),
+            VideoMode::new(1920, 1080, 120),
+            VideoMode::new(1920, 1080, 60),
+        ],
+        hdr: true,
+        rgb_only: false,
+    },
+    EdidPreset {
+        name: "1080p-240",
+        description: "1920x1080 up to 240 Hz",
+        monitor_name: "Elgato 1080p",
+        modes: &[
+            VideoMode::new(1920, 1080, 240),
+            VideoMode::new(1920, 1080, 144),
+            VideoMode::new(1920, 1080, 120),
+            VideoMode::new(1920, 1080, 60),
+        ],
+        hdr: false,
+        rgb_only: false,
+    },
+    EdidPreset {
+        name: "pc-444",
+        description: "Desktop resolutions, RGB 4:4:4 only",
+        monitor_name: "Elgato PC",
+        modes: &[
+            VideoMode::new(3840, 2160, 60),
+            VideoMode::new(3840, 2160, 30),
+            VideoMode::new(2560, 1440, 60),
+            VideoMode::new(1920, 1200, 60),
+            VideoMode::new(1920, 1080, 60),
+            VideoMode::new(1680, 1050, 60),
+            VideoMode::new(1280, 720, 60),
+        ],
+        hdr: false,
+        rgb_only: true,
+    },
+];
+
+/// Look up a preset by name.
+pub fn edid_preset(name: &str) -> Option<&'static EdidPreset> {
+    EDID_PRESETS.iter().find(|p| p.name == name)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_presets_build_and_validate() {
+        for preset in EDID_PRESETS {
+            let edid = preset.build().unwrap_or_else(|e| panic!("{}: {}", preset.name, e));
+            assert_eq!(edid.validate(), Ok(()), "{}", preset.name);
+        }
+    }
+
+    #[test]
+    fn headline_mode_is_advertised() {
+        for preset in EDID_PRESETS {
+            let edid = preset.build().unwrap();
+            let headline = preset.modes[0];
+            let cta = edid.cta_extension().unwrap().unwrap();
+            let displayid = edid.displayid_extension().transpose().unwrap().unwrap_or_default();
+            let matches = |t: &crate::edid::DetailedTiming| {
+                t.h_active == headline.width
+                    && t.v_active == headline.height
+                    && t.refresh_hz().round() as u16 == headline.refresh_hz
+            };
+            let found = headline.vic().is_some_and(|v| cta.vics().contains(&v))
+                || edid.detailed_timings().iter().any(matches)
+                || cta.detailed_timings.iter().any(matches)
+                || displayid.timings.iter().any(|(t, _)| matches(t));
+            assert!(found, "{} does not advertise {}", preset.name, headline);
+        }
+    }
+
+    #[test]
+    fn pc_preset_is_rgb_only() {
+        let edid = edid_preset("pc-444").unwrap().build().unwrap();
+        assert_eq!(edid.as_bytes()[24] & 0x18, 0);
+        let cta = edid.cta_extension().unwrap().unwrap();
+        assert!(!cta.ycbcr444 && !cta.ycbcr422);
+    }
+
+    #[test]
+    fn valid_values_lists_every_preset() {
+        let names: Vec<&str> = EDID_PRESETS.iter().map(|p| p.name).collect();
+        assert_eq!(EdidPreset::VALID_VALUES, names.join(", "));
+    }
+
+    #[test]
+    fn lookup_by_name() {
+        assert_eq!(edid_preset("ultrawide-144").unwrap().modes[0], VideoMode::new(3440, 1440, 144));
+        assert!(edid_preset("nope").is_none());
+    }
+}
@@ -0,0 +1,370 @@
+//! Video timings: CVT reduced-blanking generation, 18-byte detailed timing
+//! descriptors, and the CTA-861 VIC table.
+
+use std::fmt;
+
+use super::EdidError;
+
+/// Highest pixel clock a base-EDID detailed timing descriptor can carry
+/// (16-bit field in 10 kHz units).
+pub const DTD_MAX_PIXEL_CLOCK_KHZ: u32 = 655_350;
+
+/// A requested video mode: active resolution and refresh rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VideoMode {
+    /// Horizontal active pixels.
+    pub width: u16,
+    /// Vertical active lines.
+    pub height: u16,
+    /// Vertical refresh rate in Hz.
+    pub refresh_hz: u16,
+}
+
+impl VideoMode {
+    pub const fn new(width: u16, height: u16, refresh_hz: u16) -> Self {
+        Self { width, height, refresh_hz }
+    }
+
+    /// The CTA-861 Video Identification Code for this mode, if one exists.
+    ///
+    /// Only progressive 16:9 (or native-aspect) VICs are considered.
+    pub fn vic(&self) -> Option<u8> {
+        CTA_VICS
+            .iter()
+            .find(|v| v.width == self.width && v.height == self.height && v.refresh_hz == self.refresh_hz)
+            .map(|v| v.vic)
+    }
+}
+
+impl fmt::Display for VideoMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}@{}", self.width, self.height, self.refresh_hz)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CTA-861 VIC table
+// ---------------------------------------------------------------------------
+
+/// A CTA-861 Video Identification Code and the timing it defines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CtaVic {
+    pub vic: u8,
+    pub width: u16,
+    pub height: u16,
+    pub refresh_hz: u16,
+    /// Pixel clock of the CTA timing in kHz.
+    pub pixel_clock_khz: u32,
+    pub h_total: u16,
+    pub h_front_porch: u16,
+    pub h_sync_width: u16,
+    pub v_total: u16,
+    pub v_front_porch: u16,
+    pub v_sync_width: u16,
+    /// Both syncs positive (all HD formats) or both negative (SD formats).
+    pub sync_positive: bool,
+}
+
+impl CtaVic {
+    /// The full timing this VIC stands for.
+    pub fn timing(&self) -> DetailedTiming {
+        DetailedTiming {
+            pixel_clock_khz: self.pixel_clock_khz,
+            h_active: self.width,
+            h_blank: self.h_total - self.width,
+            h_front_porch: self.h_front_porch,
+            h_sync_width: self.h_sync_width,
+            v_active: self.height,
+            v_blank: self.v_total - self.height,
+            v_front_porch: self.v_front_porch,
+            v_sync_width: self.v_sync_width,
+            h_sync_positive: self.sync_positive,
+            v_sync_positive: self.sync_positive,
+            interlaced: false,
+        }
+    }
+}
+
+macro_rules! vic {
+    ($vic:expr, $w:expr, $h:expr, $hz:expr, $khz:expr,
+     $ht:expr, $hfp:expr, $hs:expr, $vt:expr, $vfp:expr, $vs:expr, $pos:expr) => {
+        CtaVic {
+            vic: $vic, width: $w, height: $h, refresh_hz: $hz, pixel_clock_khz: $khz,
+            h_total: $ht, h_front_porch: $hfp, h_sync_width: $hs,
+            v_total: $vt, v_front_porch: $vfp, v_sync_width: $vs, sync_positive: $pos,
+        }
+    };
+}
+
+/// Progressive CTA-861 VICs relevant to capture (16:9 and 4096x2160 only).
+pub const CTA_VICS: &[CtaVic] = &[
+    //   VIC  width height Hz  clock(kHz)  htot  hfp   hs   vtot vfp vs  +sync
+    vic!(1,   640,  480,  60,  25_175,     800,  16,   96,  525, 10, 2,  false),
+    vic!(3,   720,  480,  60,  27_000,     858,  16,   62,  525, 9,  6,  false),
+    vic!(4,   1280, 720,  60,  74_250,     1650, 110,  40,  750, 5,  5,  true),
+    vic!(16,  1920, 1080, 60,  148_500,    2200, 88,   44,  1125, 4, 5,  true),
+    vic!(18,  720,  576,  50,  27_000,     864,  12,   64,  625, 5,  5,  false),
+    vic!(19,  1280, 720,  50,  74_250,     1980, 440,  40,  750, 5,  5,  true),
+    vic!(31,  1920, 1080, 50,  148_500,    2640, 528,  44,  1125, 4, 5,  true),
+    vic!(32,  1920, 1080, 24,  74_250,     2750, 638,  44,  1125, 4, 5,  true),
+    vic!(33,  1920, 1080, 25,  74_250,     2640, 528,  44,  1125, 4, 5,  true),
+    vic!(34,  1920, 1080, 30,  74_250,     2200, 88,   44,  1125, 4, 5,  true),
+    vic!(47,  1280, 720,  120, 148_500,    1650, 110,  40,  750, 5,  5,  true),
+    vic!(63,  1920, 1080, 120, 297_000,    2200, 88,   44,  1125, 4, 5,  true),
+    vic!(64,  1920, 1080, 100, 297_000,    2640, 528,  44,  1125, 4, 5,  true),
+    vic!(93,  3840, 2160, 24,  297_000,    5500, 1276, 88,  2250, 8, 10, true),
+    vic!(94,  3840, 2160, 25,  297_000,    5280, 1056, 88,  2250, 8, 10, true),
+    vic!(95,  3840, 2160, 30,  297_000,    4400, 176,  88,  2250, 8, 10, true),
+    vic!(96,  3840, 2160, 50,  594_000,    5280, 1056, 88,  2250, 8, 10, true),
+    vic!(97,  3840, 2160, 60,  594_000,    4400, 176,  88,  2250, 8, 10, true),
+    vic!(98,  4096, 2160, 24,  297_000,    5500, 1020, 88,  2250, 8, 10, true),
+    vic!(99,  4096, 2160, 25,  297_000,    5280, 968,  88,  2250, 8, 10, true),
+    vic!(100, 4096, 2160, 30,  297_000,    4400, 88,   88,  2250, 8, 10, true),
+    vic!(101, 4096, 2160, 50,  594_000,    5280, 968,  88,  2250, 8, 10, true),
+    vic!(102, 4096, 2160, 60,  594_000,    4400, 88,   88,  2250, 8, 10, true),
+    vic!(117, 3840, 2160, 100, 1_188_000,  5280, 1056, 88,  2250, 8, 10, true),
+    vic!(118, 3840, 2160, 120, 1_188_000,  4400, 176,  88,  2250, 8, 10, true),
+];
+
+/// Look up a VIC in [`CTA_VICS`].
+pub fn cta_vic(vic: u8) -> Option<&'static CtaVic> {
+    CTA_VICS.iter().find(|v| v.vic == vic)
+}
+
+// ---------------------------------------------------------------------------
+// Detailed timings
+// ---------------------------------------------------------------------------
+
+/// A fully specified video timing, as carried in an 18-byte DTD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DetailedTiming {
+    pub pixel_clock_khz: u32,
+    pub h_active: u16,
+    pub h_blank: u16,
+    pub h_front_porch: u16,
+    pub h_sync_width: u16,
+    pub v_active: u16,
+    pub v_blank: u16,
+    pub v_front_porch: u16,
+    pub v_sync_width: u16,
+    pub h_sync_positive: bool,
+    pub v_sync_positive: bool,
+    pub interlaced: bool,
+}
+
+impl DetailedTiming {
+    /// Generate a VESA CVT 1.1 reduced-blanking (v1) timing.
+    ///
+    /// This is what monitors use for non-CTA desktop modes, and it keeps the
+    /// pixel clock low enough for high refresh rates over capture links.
+    pub fn cvt_rb(mode: VideoMode) -> Self {
+        const CLOCK_STEP_KHZ: u32 = 250;
+        const MIN_V_BLANK_US: f64 = 460.0;
+        const V_FRONT_PORCH: u16 = 3;
+        const MIN_V_BACK_PORCH: u16 = 6;
+        const H_BLANK: u16 = 160;
+        const H_SYNC: u16 = 32;
+
+        let h_active = mode.width / 8 * 8;
+        let v_active = mode.height;
+        let v_sync = cvt_v_sync_width(mode.width, mode.height);
+
+        let h_period_est = (1_000_000.0 / mode.refresh_hz as f64 - MIN_V_BLANK_US) / v_active as f64;
+        let vbi_lines = (MIN_V_BLANK_US / h_period_est).floor() as u16 + 1;
+        let v_blank = vbi_lines.max(V_FRONT_PORCH + v_sync + MIN_V_BACK_PORCH);
+
+        let total_pixels = (h_active + H_BLANK) as u64;
+        let total_lines = (v_active + v_blank) as u64;
+        let raw_khz = mode.refresh_hz as u64 * total_lines * total_pixels / 1000;
+        let pixel_clock_khz = (raw_khz as u32) / CLOCK_STEP_KHZ * CLOCK_STEP_KHZ;
+
+        Self {
+            pixel_clock_khz,
+            h_active,
+            h_blank: H_BLANK,
+            h_front_porch: H_BLANK / 2 - H_SYNC,
+            h_sync_width: H_SYNC,
+            v_active,
+            v_blank,
+            v_front_porch: V_FRONT_PORCH,
+            v_sync_width: v_sync,
+            h_sync_positive: true,
+            v_sync_positive: false,
+            interlaced: false,
+        }
+    }
+
+    /// Total pixels per line including blanking.
+    pub fn h_total(&self) -> u32 {
+        self.h_active as u32 + self.h_blank as u32
+    }
+
+    /// Total lines per frame including blanking.
+    pub fn v_total(&self) -> u32 {
+        self.v_active as u32 + self.v_blank as u32
+    }
+
+    /// Vertical refresh rate in Hz.
+    pub fn refresh_hz(&self) -> f64 {
+        self.pixel_clock_khz as f64 * 1000.0 / (self.h_total() as f64 * self.v_total() as f64)
+    }
+
+    /// Horizontal line rate in kHz.
+    pub fn h_freq_khz(&self) -> f64 {
+        self.pixel_clock_khz as f64 / self.h_total() as f64
+    }
+
+    /// Encode as an 18-byte detailed timing descriptor.
+    pub fn to_dtd(&self) -> Result<[u8; 18], EdidError> {
+        if self.pixel_clock_khz > DTD_MAX_PIXEL_CLOCK_KHZ {
+            return Err(EdidError::PixelClockTooHigh {
+                pixel_clock_khz: self.pixel_clock_khz,
+            });
+        }
+        let fits = |v: u16, bits: u32| (v as u32) < (1 << bits);
+        if !(fits(self.h_active, 12)
+            && fits(self.h_blank, 12)
+            && fits(self.v_active, 12)
+            && fits(self.v_blank, 12)
+            && fits(self.h_front_porch, 10)
+            && fits(self.h_sync_width, 10)
+            && fits(self.v_front_porch, 6)
+            && fits(self.v_sync_width, 6))
+        {
+            return Err(EdidError::TimingOutOfRange);
+        }
+
+        let clock = (self.pixel_clock_khz / 10) as u16;
+        let mut d = [0u8; 18];
+        d[0..2].copy_from_slice(&clock.to_le_bytes());
+        d[2] = self.h_active as u8;
+        d[3] = self.h_blank as u8;
+        d[4] = (((self.h_active >> 8) as u8) << 4) | (self.h_blank >> 8) as u8;
+        d[5] = self.v_active as u8;
+        d[6] = self.v_blank as u8;
+        d[7] = (((self.v_active >> 8) as u8) << 4) | (self.v_blank >> 8) as u8;
+        d[8] = self.h_front_porch as u8;
+        d[9] = self.h_sync_width as u8;
+        d[10] = (((self.v_front_porch & 0x0f) as u8) << 4) | (self.v_sync_width & 0x0f) as u8;
+        d[11] = (((self.h_front_porch >> 8) as u8) << 6)
+            | (((self.h_sync_width >> 8) as u8) << 4)
+            | (((self.v_front_porch >> 4) as u8) << 2)
+            | (self.v_sync_width >> 4) as u8;
+        // Bytes 12-14: image size in mm (unknown), 15-16: borders
+        d[17] = (self.interlaced as u8) << 7
+            | 0x18 // digital separate sync
+            | (self.v_sync_positive as u8) << 2
+            | (self.h_sync_positive as u8) << 1;
+        Ok(d)
+    }
+
+    /// Decode an 18-byte descriptor, returning `None` for display descriptors
+    /// (pixel clock of zero).
+    pub fn from_dtd(d: &[u8; 18]) -> Option<Self> {
+        let clock = u16::from_le_bytes([d[0], d[1]]);
+        if clock == 0 {
+            return None;
+        }
+        Some(Self {
+            pixel_clock_khz: clock as u32 * 10,
+            h_active: d[2] as u16 | ((d[4] as u16 >> 4) << 8),
+            h_blank: d[3] as u16 | ((d[4] as u16 & 0x0f) << 8),
+            v_active: d[5] as u16 | ((d[7] as u16 >> 4) << 8),
+            v_blank: d[6] as u16 | ((d[7] as u16 & 0x0f) << 8),
+            h_front_porch: d[8] as u16 | (((d[11] as u16 >> 6) & 0x03) << 8),
+            h_sync_width: d[9] as u16 | (((d[11] as u16 >> 4) & 0x03) << 8),
+            v_front_porch: (d[10] as u16 >> 4) | (((d[11] as u16 >> 2) & 0x03) << 4),
+            v_sync_width: (d[10] as u16 & 0x0f) | ((d[11] as u16 & 0x03) << 4),
+            interlaced: d[17] & 0x80 != 0,
+            v_sync_positive: d[17] & 0x04 != 0,
+            h_sync_positive: d[17] & 0x02 != 0,
+        })
+    }
+}
+
+impl fmt::Display for DetailedTiming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}x{}{}@{:.2} ({:.2} MHz)",
+            self.h_active,
+            self.v_active,
+            if self.interlaced { "i" } else { "" },
+            self.refresh_hz(),
+            self.pixel_clock_khz as f64 / 1000.0
+        )
+    }
+}
+
+/// CVT vertical sync width, which encodes the aspect ratio.
+fn cvt_v_sync_width(width: u16, height: u16) -> u16 {
+    let (w, h) = (width as u32, height as u32);
+    if w * 3 == h * 4 {
+        4
+    } else if w * 9 == h * 16 {
+        5
+    } else if w * 10 == h * 16 {
+        6
+    } else if w * 4 == h * 5 || w * 9 == h * 15 {
+        7
+    } else {
+        10
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cvt_rb_1080p60_matches_vesa() {
+        // VESA DMT/CVT-RB 1920x1080@60: 138.5 MHz, 2080x1111
+        let t = DetailedTiming::cvt_rb(VideoMode::new(1920, 1080, 60));
+        assert_eq!(t.pixel_clock_khz, 138_500);
+        assert_eq!(t.h_total(), 2080);
+        assert_eq!(t.v_total(), 1111);
+        assert_eq!(t.v_sync_width, 5);
+    }
+
+    #[test]
+    fn cvt_rb_1080p240_fits_in_dtd() {
+        let t = DetailedTiming::cvt_rb(VideoMode::new(1920, 1080, 240));
+        assert!(t.pixel_clock_khz <= DTD_MAX_PIXEL_CLOCK_KHZ);
+        assert!((t.refresh_hz() - 240.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn dtd_roundtrip() {
+        let t = DetailedTiming::cvt_rb(VideoMode::new(2560, 1440, 60));
+        let d = t.to_dtd().unwrap();
+        assert_eq!(DetailedTiming::from_dtd(&d), Some(t));
+    }
+
+    #[test]
+    fn dtd_rejects_high_pixel_clock() {
+        let t = DetailedTiming::cvt_rb(VideoMode::new(3440, 1440, 144));
+        assert!(matches!(t.to_dtd(), Err(EdidError::PixelClockTooHigh { .. })));
+    }
+
+    #[test]
+    fn vic_timings_are_consistent() {
+        for v in CTA_VICS {
+            let hz = v.timing().refresh_hz();
+            assert!((hz - v.refresh_hz as f64).abs() < 0.1, "VIC {} refreshes at {}", v.vic, hz);
+        }
+    }
+
+    #[test]
+    fn vic_lookup() {
+        assert_eq!(VideoMode::new(1920, 1080, 60).vic(), Some(16));
+        assert_eq!(VideoMode::new(3840, 2160, 120).vic(), Some(118));
+        assert_eq!(VideoMode::new(2560, 1440, 60).vic(), None);
+    }
+}
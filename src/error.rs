@@ -7,13 +7,24 @@
 use thiserror::Error;
 
 /// Top-level error type for all elgato4k operations.
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum ElgatoError {
     /// No supported Elgato device was found on the USB bus.
     #[error("Elgato 4K X or 4K S not found. Make sure it's connected.\n\
              Known PIDs: 4K X (009b, 009c, 009d), 4K S (00ae, 00af)")]
     DeviceNotFound,
 
+    /// A device path could not be resolved to a supported Elgato device.
+    #[error("Cannot open '{path}': {reason}")]
+    InvalidDevicePath { path: String, reason: String },
+
+    /// A kernel driver is bound to the interface the tool needs, and
+    /// detaching it was turned off with
+    /// [`detach_kernel_driver`](crate::ElgatoDeviceBuilder::detach_kernel_driver).
+    #[error("Interface {interface} is in use by its kernel driver and detaching it is turned off.\n\
+             Stop the program capturing from the card, or allow detaching, and try again.")]
+    KernelDriverActive { interface: u16 },
+
     /// A USB/libusb transport error occurred.
     #[error("USB error: {0}")]
     Usb(#[from] rusb::Error),
@@ -37,4 +48,22 @@ pub enum ElgatoError {
         model: &'static str,
     },
 
+    /// A picture control value lies outside the range the device reports.
+    #[error("{control} value {value} is out of range ({min} to {max})")]
+    ValueOutOfRange {
+        control: &'static str,
+        value: i32,
+        min: i32,
+        max: i32,
+    },
+
+    /// A raw command was refused before being sent.
+    #[error("Raw command rejected: {0}")]
+    RawCommandRejected(String),
+
+    /// The operation cannot work while the device is in USB 2.0 fallback mode.
+    #[error("{feature} is unavailable while the device is in USB 2.0 fallback mode.\n\
+             The card only negotiated a USB 2.0 link. Connect it directly to a USB 3 \
+             port (not through a hub) using the supplied USB-C cable, then replug it.")]
+    Usb2Fallback { feature: &'static str },
 }
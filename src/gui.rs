@@ -0,0 +1,161 @@
+//! The `gui` subcommand: a small window with the everyday settings and the
+//! live status, for users who'd rather click than type.
+//!
+//! Part of the binary, not the library.  The window holds the card open the
+//! way `watch` does, so on the 4K X the capture is interrupted until it closes.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+use elgato4k_linux::*;
+
+const RANGES: [EdidRangePolicy; 3] = [EdidRangePolicy::Auto, EdidRangePolicy::Expand, EdidRangePolicy::Shrink];
+const SOURCES: [EdidSource; 3] = [EdidSource::Display, EdidSource::Merged, EdidSource::Internal];
+
+/// Show the window until it's closed, reading the status every `interval`.
+pub fn run(device: ElgatoDevice, interval: Duration) -> Result<(), Box<dyn std::error::Error>> {
+    let title = format!("Elgato {} (PID 0x{:04x})", device.model(), device.pid());
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default().with_inner_size([380.0, 460.0]),
+        ..Default::default()
+    };
+    let panel = ControlPanel {
+        device,
+        interval,
+        status: None,
+        edid_source: None,
+        error: None,
+        next_read: Instant::now(),
+    };
+    eframe::run_native(&title, options, Box::new(|_| Ok(Box::new(panel))))?;
+    Ok(())
+}
+
+struct ControlPanel {
+    device: ElgatoDevice,
+    interval: Duration,
+    /// Last status read, kept while later reads fail.
+    status: Option<DeviceStatus>,
+    /// EDID source last picked here, since the 4K X can't report it.
+    edid_source: Option<EdidSource>,
+    /// Last read or write failure, cleared by the next success.
+    error: Option<String>,
+    next_read: Instant,
+}
+
+impl ControlPanel {
+    fn refresh(&mut self) {
+        match self.device.read_status() {
+            Ok(status) => {
+                self.status = Some(status);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("Reading the status failed: {}", e)),
+        }
+        self.next_read = Instant::now() + self.interval;
+    }
+
+    fn apply(&mut self, change: SettingChange) {
+        match self.device.apply_change(&change) {
+            Ok(()) => {
+                log::info!("Set {}", change);
+                if let SettingChange::EdidSource(source) = change {
+                    self.edid_source = Some(source);
+                }
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("Setting {} failed: {}", change, e)),
+        }
+        // Give the card time to take the change before reading it back.
+        self.next_read = Instant::now() + SETTING_APPLY_DELAY;
+    }
+
+    /// The value read for `field`, if it was recognized.
+    fn known<T: Copy>(&self, field: impl Fn(&DeviceStatus) -> &StatusField<ReadValue<T>>) -> Option<T> {
+        match self.status.as_ref().map(field)?.value()? {
+            ReadValue::Known(value) => Some(*value),
+            ReadValue::Unknown(_) => None,
+        }
+    }
+}
+
+impl eframe::App for ControlPanel {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if Instant::now() >= self.next_read {
+            self.refresh();
+        }
+
+        let hdr = self.known(|s| &s.hdr_tone_mapping);
+        let range = self.known(|s| &s.hdmi_color_range);
+        let source = self.known(|s| &s.edid_source).or(self.edid_source);
+        let mut change = None;
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Settings");
+            egui::Grid::new("settings").num_columns(2).spacing([24.0, 8.0]).show(ui, |ui| {
+                ui.label("HDR tone mapping");
+                let text = hdr.map_or_else(|| "Unknown".to_string(), |mode| mode.to_string());
+                let mut on = hdr == Some(HdrToneMapping::On);
+                if ui.checkbox(&mut on, text).changed() {
+                    let mode = if on { HdrToneMapping::On } else { HdrToneMapping::Off };
+                    change = Some(SettingChange::HdrMapping(mode));
+                }
+                ui.end_row();
+
+                ui.label("HDMI color range");
+                if let Some(range) = choice(ui, "range", range, &RANGES) {
+                    change = Some(SettingChange::HdmiRange(range));
+                }
+                ui.end_row();
+
+                ui.label("EDID source");
+                if let Some(source) = choice(ui, "edid-source", source, &SOURCES) {
+                    change = Some(SettingChange::EdidSource(source));
+                }
+                ui.end_row();
+            });
+
+            ui.separator();
+            ui.heading("Status");
+            if let Some(status) = &self.status {
+                egui::Grid::new("status").num_columns(2).spacing([24.0, 4.0]).show(ui, |ui| {
+                    for (label, value) in status.rows() {
+                        if let Some(value) = value {
+                            ui.label(label);
+                            ui.label(value);
+                            ui.end_row();
+                        }
+                    }
+                });
+            }
+            if let Some(error) = &self.error {
+                ui.separator();
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+        });
+
+        if let Some(change) = change {
+            self.apply(change);
+        }
+        ctx.request_repaint_after(self.next_read.saturating_duration_since(Instant::now()));
+    }
+}
+
+/// A drop-down of `options` showing `current`.  Returns the option picked,
+/// if it differs from `current`.
+fn choice<T>(ui: &mut egui::Ui, id: &str, current: Option<T>, options: &[T]) -> Option<T>
+where
+    T: Copy + PartialEq + fmt::Display,
+{
+    let mut picked = None;
+    let text = current.map_or_else(|| "Unknown".to_string(), |v| v.to_string());
+    egui::ComboBox::from_id_salt(id).selected_text(text).show_ui(ui, |ui| {
+        for &option in options {
+            if ui.selectable_label(current == Some(option), option.to_string()).clicked() && current != Some(option) {
+                picked = Some(option);
+            }
+        }
+    });
+    picked
+}
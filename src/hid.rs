@@ -8,6 +8,46 @@
 use crate::device::ElgatoDevice;
 use crate::error::ElgatoError;
 use crate::protocol::*;
+#[cfg(feature = "unsafe-raw")]
+use crate::settings::hid_write_packet;
+
+/// Build a raw settings write, refusing sub-commands in [`HID_SUBCMDS_DENIED`].
+#[cfg(feature = "unsafe-raw")]
+pub(crate) fn raw_hid_packet(sub_cmd: u8, value: u8) -> Result<[u8; HID_PACKET_SIZE], ElgatoError> {
+    if HID_SUBCMDS_DENIED.contains(&sub_cmd) {
+        return Err(ElgatoError::RawCommandRejected(format!(
+            "sub-command 0x{:02x} resets or hangs the device",
+            sub_cmd
+        )));
+    }
+    Ok(hid_write_packet(sub_cmd, value))
+}
+
+/// Zero-pad a raw output report to [`HID_PACKET_SIZE`].  Settings writes
+/// (`06 06 06 55 02 sub ...`) are held to the same deny list as
+/// [`raw_hid_packet`].
+#[cfg(feature = "unsafe-raw")]
+pub(crate) fn raw_hid_report(report: &[u8]) -> Result<[u8; HID_PACKET_SIZE], ElgatoError> {
+    if report.is_empty() || report.len() > HID_PACKET_SIZE {
+        return Err(ElgatoError::RawCommandRejected(format!(
+            "report must be 1 to {} bytes, got {}",
+            HID_PACKET_SIZE,
+            report.len()
+        )));
+    }
+    if let Some(&sub_cmd) = report.strip_prefix(&HID_WRITE_HEADER[..]).and_then(|rest| rest.first()) {
+        raw_hid_packet(sub_cmd, 0)?;
+    }
+    let mut packet = [0u8; HID_PACKET_SIZE];
+    packet[..report.len()].copy_from_slice(report);
+    Ok(packet)
+}
+
+/// `packet` without its trailing zero padding, for trace logging.
+fn trim_padding(packet: &[u8]) -> &[u8] {
+    let end = packet.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+    &packet[..end]
+}
 
 /// HID Output/Input Report protocol methods for the 4K S.
 ///
@@ -23,14 +63,15 @@ impl ElgatoDevice {
                 got: packet.len(),
             });
         }
+        self.status_cache().invalidate();
+        log::trace!("HID write {:02x?}", trim_padding(packet));
 
-        self.handle.write_control(
+        self.write_control(
             HID_REQUEST_TYPE_OUT,
             HID_SET_REPORT,
             HID_REPORT_VALUE_OUTPUT,
             HID_INTERFACE,
             packet,
-            USB_TIMEOUT,
         ).map_err(|e| ElgatoError::HidTransfer(format!("SET_REPORT failed: {}", e)))?;
 
         Ok(())
@@ -52,13 +93,12 @@ impl ElgatoDevice {
         request[3] = data_len;
 
         // Send the request via SET_REPORT (Output)
-        self.handle.write_control(
+        self.write_control(
             HID_REQUEST_TYPE_OUT,
             HID_SET_REPORT,
             HID_REPORT_VALUE_OUTPUT,
             HID_INTERFACE,
             &request,
-            USB_TIMEOUT,
         ).map_err(|e| ElgatoError::HidTransfer(format!("read request SET_REPORT failed: {}", e)))?;
 
         // Small delay for device to prepare response
@@ -68,15 +108,16 @@ impl ElgatoDevice {
         let mut buf = [0u8; HID_PACKET_SIZE];
         buf[0] = HID_REPORT_ID; // Report ID must be set in buffer for GET_REPORT
 
-        let len = self.handle.read_control(
+        let len = self.read_control(
             HID_REQUEST_TYPE_IN,
             HID_GET_REPORT,
             HID_REPORT_VALUE_INPUT,
             HID_INTERFACE,
             &mut buf,
-            USB_TIMEOUT,
         ).map_err(|e| ElgatoError::HidTransfer(format!("GET_REPORT failed: {}", e)))?;
 
+        log::trace!("HID read {:02x} {:02x}: {:02x?}", cmd, sub_cmd, trim_padding(&buf[..len]));
+
         // Return data after report ID byte
         if len > 1 {
             Ok(buf[1..len].to_vec())
@@ -85,3 +126,44 @@ impl ElgatoDevice {
         }
     }
 }
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trim_padding_keeps_inner_zeros() {
+        assert_eq!(trim_padding(&[0x06, 0x00, 0x55, 0x00, 0x00]), &[0x06, 0x00, 0x55]);
+        assert_eq!(trim_padding(&[0x00; 4]), &[] as &[u8]);
+    }
+
+    #[cfg(feature = "unsafe-raw")]
+    #[test]
+    fn raw_packet_matches_typed_setting() {
+        let typed = crate::settings::VideoScaler::On.payload_4ks();
+        assert_eq!(raw_hid_packet(SUBCMD_VIDEO_SCALER, 0x01).unwrap(), typed);
+    }
+
+    #[cfg(feature = "unsafe-raw")]
+    #[test]
+    fn raw_packet_refuses_hang_command() {
+        assert!(matches!(raw_hid_packet(0x13, 0x00), Err(ElgatoError::RawCommandRejected(_))));
+    }
+
+    #[cfg(feature = "unsafe-raw")]
+    #[test]
+    fn raw_report_is_padded_and_checked() {
+        let typed = crate::settings::VideoScaler::On.payload_4ks();
+        assert_eq!(raw_hid_report(trim_padding(&typed)).unwrap(), typed);
+        assert!(matches!(
+            raw_hid_report(&[0x06, 0x06, 0x06, 0x55, 0x02, 0x13, 0x00]),
+            Err(ElgatoError::RawCommandRejected(_))
+        ));
+        assert!(raw_hid_report(&[]).is_err());
+        assert!(raw_hid_report(&[0; HID_PACKET_SIZE + 1]).is_err());
+    }
+}
@@ -6,6 +6,7 @@
 //! # Quick Start
 //!
 //! ```no_run
+//! # #[cfg(feature = "usb")] {
 //! use elgato4k_linux::{ElgatoDevice, HdrToneMapping, EdidSource};
 //!
 //! let device = ElgatoDevice::open()?;
@@ -13,22 +14,110 @@
 //! device.set_edid_source(EdidSource::Display)?;
 //!
 //! let status = device.read_status()?;
-//! println!("Firmware: {}", status.firmware_version);
+//! if let Some(version) = status.firmware_version.value() {
+//!     println!("Firmware: {}", version);
+//! }
+//! # }
 //! # Ok::<(), Box<dyn std::error::Error>>(())
 //! ```
+//!
+//! With several cards connected, or to change the USB timeout and kernel
+//! driver handling, open through `ElgatoDevice::builder()` instead.
+//!
+//! # Features
+//!
+//! - `usb` (default): device control via rusb/libusb.  Without it, only the
+//!   [`edid`] module is built, so tools that just need EDID parsing,
+//!   generation, and patching don't pull in libusb.
+//! - `update-check` (default): the CLI checks GitHub for a newer release.
+//! - `unsafe-raw`: `ElgatoDevice::set_raw_hid_setting` and
+//!   `ElgatoDevice::set_raw_uvc_payload`, for driving sub-commands this
+//!   crate doesn't wrap yet, plus `send_raw_hid`/`send_raw_uvc` for byte
+//!   sequences sent as given and the CLI's `raw` subcommand.  Known
+//!   reset/hang commands are still refused.
+//! - `serde`: `Serialize`/`Deserialize` for [`DeviceStatus`], [`ReadValue`],
+//!   the settings types, profiles, and the [`edid`] types, so other tools
+//!   can store or exchange them.
+//! - `json` (default): `serde` plus the CLI's `--json` output and
+//!   `--status --save`/`--compare` snapshot files, with status in the
+//!   versioned `StatusSnapshot` format.
+//! - `gui`: the CLI's `gui` subcommand, a small egui window.  Adds nothing
+//!   to the library.
+//!
+//! # Logging
+//!
+//! The library never prints.  Interface claims and kernel-driver detaches
+//! are logged at `debug` level and every USB transfer at `trace` level
+//! through the [`log`](https://docs.rs/log) crate, so they only show up if
+//! the application installs a logger.
 
+#[cfg(feature = "usb")]
 mod device;
+#[cfg(feature = "usb")]
+mod device_builder;
+#[cfg(feature = "usb")]
+mod device_set;
+#[cfg(feature = "usb")]
+mod doctor;
+#[cfg(feature = "usb")]
+mod dry_run;
+pub mod edid;
+#[cfg(feature = "usb")]
 mod error;
+#[cfg(feature = "usb")]
 mod hid;
+#[cfg(feature = "usb")]
+mod profile;
+#[cfg(feature = "usb")]
 mod protocol;
+#[cfg(feature = "usb")]
 mod settings;
+#[cfg(feature = "usb")]
+mod selftest;
+#[cfg(feature = "usb")]
 mod status;
+#[cfg(feature = "usb")]
+mod transport;
+#[cfg(feature = "usb")]
+mod udev;
+#[cfg(feature = "usb")]
 mod uvc;
 
-pub use device::ElgatoDevice;
+#[cfg(feature = "usb")]
+pub use device::{DetectedDevice, ElgatoDevice, HardwareRevision, TransferPolicy};
+#[cfg(feature = "usb")]
+pub use device_builder::ElgatoDeviceBuilder;
+#[cfg(feature = "usb")]
+pub use device_set::{DeviceResult, ElgatoDeviceSet};
+#[cfg(feature = "usb")]
+pub use doctor::{diagnose, Check, CheckStatus};
+#[cfg(feature = "usb")]
+pub use dry_run::PlannedTransfer;
+#[cfg(feature = "usb")]
 pub use error::ElgatoError;
+#[cfg(feature = "usb")]
+pub use profile::{Profile, ProfileError, ProfileFile, SettingChange};
+#[cfg(feature = "usb")]
+pub use protocol::SETTING_APPLY_DELAY;
+#[cfg(feature = "usb")]
+pub use selftest::TestStep;
+#[cfg(feature = "usb")]
 pub use settings::{
-    AudioInput, CustomEdidMode, DeviceModel, EdidRangePolicy,
-    EdidSource, HdrToneMapping, UsbSpeed, VideoScaler,
+    AudioInput, Bundle, ControlRange, CustomEdidMode, CustomEdidPreset, DeviceModel, EdidRangePolicy,
+    EdidSource, HdrToneMapping, InputLimit, PictureControl, UsbSpeed, VideoScaler,
+};
+#[cfg(feature = "usb")]
+pub use transport::{Transport, UsbTransport};
+#[cfg(feature = "usb")]
+pub use udev::{udev_rules, UDEV_RULES_PATH};
+#[cfg(feature = "unsafe-raw")]
+pub use uvc::{lrc, raw_uvc_payload};
+#[cfg(feature = "usb")]
+pub use status::{
+    CustomEdidStatus, DeviceStatus, FirmwareVersion, ReadValue, Setting, SettingValue, StatusChange,
+    StatusField, UsbSpeedStatus,
 };
-pub use status::{CustomEdidStatus, DeviceStatus, ReadValue, UsbSpeedStatus};
+#[cfg(all(feature = "usb", feature = "serde"))]
+pub use status::StatusSnapshot;
+#[cfg(feature = "usb")]
+pub use uvc::XuControlInfo;
@@ -4,11 +4,14 @@
 //! 4K S (HID) capture cards.  Run `elgato4k --help` for usage information.
 
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use elgato4k_linux::*;
 
-/// Delay between consecutive setting changes to give the device time to process.
-const SETTING_APPLY_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
+#[cfg(feature = "gui")]
+mod gui;
 
 /// CLI-specific errors for argument parsing.
 #[derive(Debug)]
@@ -21,6 +24,20 @@ enum CliError {
     },
     /// A required CLI argument value is missing.
     MissingArgumentValue(String),
+    /// An option the CLI doesn't know.
+    UnknownOption(String),
+    /// Options that don't make sense together.
+    InvalidUsage(&'static str),
+    /// Settings read from stdin that couldn't be understood.
+    #[cfg(feature = "json")]
+    InvalidInput(String),
+    /// Something the build or the connected device can't do.
+    Unsupported(String),
+    /// Some cards failed under `--all`; `first` is the first card's error.
+    DevicesFailed { failed: usize, total: usize, first: ElgatoError },
+    /// The user declined a confirmation prompt.
+    #[cfg(feature = "unsafe-raw")]
+    Aborted,
 }
 
 impl fmt::Display for CliError {
@@ -32,19 +49,129 @@ impl fmt::Display for CliError {
             Self::MissingArgumentValue(arg) => {
                 write!(f, "{} requires a value", arg)
             }
+            Self::UnknownOption(arg) => write!(f, "Unknown option '{}'", arg),
+            Self::InvalidUsage(message) => write!(f, "{}", message),
+            #[cfg(feature = "json")]
+            Self::InvalidInput(message) => write!(f, "{}", message),
+            Self::Unsupported(message) => write!(f, "{}", message),
+            Self::DevicesFailed { failed, total, .. } => write!(f, "{} of {} devices failed", failed, total),
+            #[cfg(feature = "unsafe-raw")]
+            Self::Aborted => write!(f, "Aborted, nothing was sent"),
         }
     }
 }
 
 impl std::error::Error for CliError {}
 
+/// Process exit codes, so scripts can branch on why a run failed.  Listed
+/// in the README; don't renumber them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExitStatus {
+    /// Anything not covered below, e.g. a file that couldn't be read.
+    Failure = 1,
+    /// A bad option, value, or profile.
+    InvalidArgument = 2,
+    /// No supported device was found.
+    DeviceNotFound = 3,
+    /// The device was found but couldn't be opened (udev rule missing?).
+    PermissionDenied = 4,
+    /// The connected model or this build doesn't support the operation.
+    Unsupported = 5,
+    /// A USB transfer failed.
+    Usb = 6,
+}
+
+impl ExitStatus {
+    /// Pick the exit code for an error `run` returned.
+    fn of(error: &(dyn std::error::Error + 'static)) -> Self {
+        if let Some(error) = error.downcast_ref::<ElgatoError>() {
+            return match error {
+                ElgatoError::DeviceNotFound
+                | ElgatoError::InvalidDevicePath { .. }
+                | ElgatoError::Usb(rusb::Error::NoDevice | rusb::Error::NotFound) => Self::DeviceNotFound,
+                ElgatoError::Usb(rusb::Error::Access) => Self::PermissionDenied,
+                ElgatoError::KernelDriverActive { .. } => Self::Failure,
+                ElgatoError::UnsupportedFeature { .. } | ElgatoError::Usb2Fallback { .. } => Self::Unsupported,
+                ElgatoError::ValueOutOfRange { .. } | ElgatoError::RawCommandRejected(_) => Self::InvalidArgument,
+                ElgatoError::Usb(_)
+                | ElgatoError::HidPacketSize { .. }
+                | ElgatoError::HidTransfer(_)
+                | ElgatoError::UvcTransfer(_) => Self::Usb,
+            };
+        }
+        if let Some(error) = error.downcast_ref::<CliError>() {
+            return match error {
+                CliError::Unsupported(_) => Self::Unsupported,
+                CliError::DevicesFailed { first, .. } => Self::of(first),
+                #[cfg(feature = "unsafe-raw")]
+                CliError::Aborted => Self::Failure,
+                _ => Self::InvalidArgument,
+            };
+        }
+        if error.downcast_ref::<ProfileError>().is_some() {
+            return Self::InvalidArgument;
+        }
+        Self::Failure
+    }
+}
+
+/// How `--format` lays out results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    /// Aligned columns, one per card.
+    Table,
+    /// One `label: value` line each, as `--status` always printed.
+    Plain,
+    /// Same as `--json`.
+    Json,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Self::Table),
+            "plain" => Ok(Self::Plain),
+            "json" => Ok(Self::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<ExitStatus> for ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
 fn print_usage() {
     println!("Elgato 4K X/S Controller - USB Control Tool\n");
     println!("USAGE:");
     println!("    sudo elgato4k-linux [OPTIONS]\n");
     println!("OPTIONS:");
     println!("    --status                    Read current device settings");
-    println!("    --firmware-version          Read firmware version\n");
+    println!("    --status --json             Print the settings as versioned JSON");
+    println!("    --status --save <FILE>      Also save the settings to a JSON snapshot");
+    println!("    --status --compare <FILE>   Show what changed since a saved snapshot");
+    println!("    --firmware-version          Read firmware version");
+    println!("    --get <SETTING>             Read a single setting and print its value");
+    println!("                                Values: firmware-version, usb-speed, hdmi-range,");
+    println!("                                hdr-map, edid-source, audio-input, video-scaler");
+    println!("    get <SETTING>               Print only the value as setters take it (e.g. on), for scripts");
+    println!("    --xu-info                   Show what the UVC Extension Unit reports (4K X only)");
+    println!("    --json                      Print results as JSON (status, get, listings, setters)");
+    println!("    --format <FORMAT>           table, plain or json, for list and --status");
+    println!("                                (table: a column per card, handy with --all)");
+    println!("    --quiet, -q                 Only print results and errors");
+    println!("    --verbose, -v               Also log USB details (-vv: every transfer)");
+    println!("    --all                       Apply settings (or --status) to every connected device");
+    println!("    --dry-run                   Print the USB transfers a write would send, without sending them");
+    println!("    --usb-timeout <TIME>        Time each USB transfer may take (default 1s), e.g. 3s");
+    println!("    --retry <N>                 Retry a transfer that timed out up to N times (0-10, default 0)");
+    println!("    --no-detach                 Fail instead of detaching the kernel driver (keeps a capture running)");
+    println!("    --no-reattach               Leave the interface without its kernel driver on exit");
+    println!("    --model <MODEL>             Model to plan a --dry-run for when no device is connected");
+    println!("                                Values: 4kx, 4ks\n");
     println!("    --hdmi-range <VALUE>        Set HDMI color range");
     println!("                                Values: auto, expand, shrink");
     println!("                                  auto   = match input source (recommended)");
@@ -60,6 +187,15 @@ fn print_usage() {
     println!("    --custom-edid <VALUE>       Set custom EDID preset (4K X only)");
     println!("                                Values: on, off");
     println!("                                Note: selects preset index, not file upload\n");
+    println!("    --custom-edid-preset <N>    Select a custom EDID preset slot (4K X only)");
+    println!("                                Values: 0-13 (0 = default, 12 = user-uploaded)\n");
+    println!("    --input-limit <VALUE>       Offer the source a built-in EDID preset slot (4K X only)");
+    println!("                                Values: off, 1440p, 1080p-mobile, 1080p");
+    println!("                                (selects a built-in EDID preset; handy on 5Gbps)\n");
+    println!("    --brightness <N>            Set a UVC picture control (4K X only)");
+    println!("    --contrast <N>              The valid range is read from the device");
+    println!("    --hue <N>");
+    println!("    --saturation <N>\n");
     println!("    --audio-input <VALUE>        Set audio input source (4K S only)");
     println!("                                Values: embedded, analog");
     println!("                                (embedded = HDMI audio, analog = line-in)\n");
@@ -70,16 +206,79 @@ fn print_usage() {
     println!("                                WARNING: Device will disconnect and");
     println!("                                re-enumerate with a different PID\n");
     println!("    --help, -h                  Show this help message\n");
+    println!("    set <KEY>=<VALUE>...        Apply settings by key instead of flag, e.g. set hdr-map=on");
+    println!("                                Keys are the setting flags above without the dashes\n");
+    println!("SETTING BUNDLES:");
+    println!("    preset                      List setting bundles (no device needed)");
+    println!("    preset <NAME>               Apply a bundle of settings");
+    println!("                                Values: console-hdr, pc-sdr, retro\n");
+    println!("GUI:");
+    println!("    gui                         Open a window with HDR, range and EDID source toggles and");
+    println!("                                the live status (needs the `gui` feature)\n");
+    println!("DIAGNOSTICS:");
+    println!("    list                        List connected cards without opening them");
+    println!("    doctor                      Check the cable, permissions, drivers and firmware, with fixes");
+    println!("    version                     Print this version, the kernel and each card's firmware");
+    println!("    selftest                    Read every setting and write one back unchanged, timing each");
+    println!("    udev --print                Print udev rules for using the cards without sudo");
+    println!("    udev --install              Install them to /etc/udev/rules.d and reload udev");
+    println!("    watch [--interval <TIME>]   Re-read the status and print each change with the time");
+    println!("                                (default every 1s; e.g. 500ms, 2s, 1m)");
+    println!("    diff <BEFORE> <AFTER>       Compare two --status --save snapshots (no device needed)");
+    println!("    reset-usb                   Reset the card's USB port, as if replugged (--all: every card)\n");
+    println!("PROFILES:");
+    println!("    apply                       List the profiles in the config file (no device needed)");
+    println!("    apply --profile <NAME>      Apply a profile's settings, EDID first and USB speed last");
+    println!("    apply --stdin               Apply settings given as JSON on stdin, e.g. {{\"hdr-map\": \"on\"}}");
+    println!("    save-profile <NAME>         Save the device's current settings as a profile");
+    println!("    --config <FILE>             Profile file to use instead of");
+    println!("                                ~/.config/elgato4k-linux/profiles.conf\n");
+    println!("EDID TOOLS (no device needed):");
+    println!("    edid preset                 List built-in EDID presets");
+    println!("    edid preset <NAME> --out <FILE> [EDID OPTIONS]");
+    println!("                                Write a built-in EDID preset to a file");
+    println!("    edid slots                  List the 4K X's EDID preset slots for --custom-edid-preset");
+    println!("    edid patch <IN> --out <FILE> [EDID OPTIONS]");
+    println!("                                Rewrite an existing EDID file\n");
+    println!("EDID OPTIONS:");
+    println!("    --audio <VALUE>             Rewrite the advertised audio formats");
+    println!("                                Values: stereo, none");
+    println!("                                  stereo = 2ch LPCM only (no 5.1 / bitstream)");
+    println!("                                  none   = remove audio entirely\n");
+    println!("RAW COMMANDS (`unsafe-raw` feature):");
+    println!("    raw uvc <BYTES>...          Send a payload to the 4K X as given (XU 4 trigger + payload)");
+    println!("    raw hid <BYTES>...          Send a HID report to the 4K S, zero-padded to 255 bytes");
+    println!("    --frame                     uvc: treat the bytes as a command body and add the a1 header + LRC");
+    println!("    --lrc                       uvc: append the LRC");
+    println!("    --yes, -y                   Don't ask for confirmation\n");
     println!("EXAMPLES:");
     println!("    sudo elgato4k-linux --status");
+    println!("    sudo elgato4k-linux --status --save before.json");
+    println!("    sudo elgato4k-linux --status --compare before.json");
     println!("    sudo elgato4k-linux --firmware-version");
+    println!("    sudo elgato4k-linux --hdr-map on --json");
     println!("    sudo elgato4k-linux --hdr-map on");
     println!("    sudo elgato4k-linux --hdmi-range expand --hdr-map on");
     println!("    sudo elgato4k-linux --edid-source display --hdmi-range auto");
     println!("    sudo elgato4k-linux --custom-edid on");
+    println!("    sudo elgato4k-linux --custom-edid-preset 12");
+    println!("    sudo elgato4k-linux --input-limit 1440p");
+    println!("    sudo elgato4k-linux --brightness 10 --saturation 140");
     println!("    sudo elgato4k-linux --audio-input analog  # 4K S only");
     println!("    sudo elgato4k-linux --video-scaler on     # 4K S only");
     println!("    sudo elgato4k-linux --usb-speed 10g");
+    println!("    sudo elgato4k-linux set hdr-map=on hdmi-range=auto edid-source=display");
+    println!("    sudo elgato4k-linux set hdr-map=off --all");
+    println!("    elgato4k-linux --hdr-map on --model 4ks --dry-run");
+    println!("    sudo elgato4k-linux raw uvc 1f 00 00 00 01 --frame");
+    println!("    sudo elgato4k-linux raw hid 06 06 06 55 02 0a 01");
+    println!("    elgato4k-linux doctor");
+    println!("    sudo elgato4k-linux udev --install");
+    println!("    sudo elgato4k-linux preset console-hdr");
+    println!("    sudo elgato4k-linux save-profile gaming --config ~/.config/elgato4k-linux/profiles.conf");
+    println!("    sudo elgato4k-linux apply --profile gaming --config ~/.config/elgato4k-linux/profiles.conf");
+    println!("    elgato4k-linux edid preset ultrawide-144 --out ultrawide.bin");
+    println!("    elgato4k-linux edid patch monitor.bin --out stereo.bin --audio stereo");
     println!("\nSUPPORTED DEVICES:");
     println!("    Elgato 4K X:");
     println!("      0fd9:009b  (10Gbps / SuperSpeed+)");
@@ -114,13 +313,14 @@ fn check_for_update() {
             .and_then(|body| extract_tag_name(&body))
             .filter(|v| is_newer(v, current))
         {
-            println!("\nUpdate available: v{} -> v{}", current, latest);
-            println!("   https://github.com/13bm/elgato4k-linux/releases/latest");
+            log::info!("Update available: v{} -> v{}", current, latest);
+            log::info!("   https://github.com/13bm/elgato4k-linux/releases/latest");
         }
     }
 }
 
 /// Extract version from `"tag_name":"vX.Y.Z"` in a JSON response body.
+#[cfg_attr(not(feature = "update-check"), allow(dead_code))]
 fn extract_tag_name(json: &str) -> Option<String> {
     let marker = "\"tag_name\":\"";
     let start = json.find(marker)? + marker.len();
@@ -130,6 +330,7 @@ fn extract_tag_name(json: &str) -> Option<String> {
 }
 
 /// Compare semver strings: is `latest` newer than `current`?
+#[cfg_attr(not(feature = "update-check"), allow(dead_code))]
 fn is_newer(latest: &str, current: &str) -> bool {
     let parse = |v: &str| -> Vec<u32> {
         v.split('.').filter_map(|s| s.parse().ok()).collect()
@@ -139,145 +340,1739 @@ fn is_newer(latest: &str, current: &str) -> bool {
     l > c
 }
 
-fn run() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = std::env::args().collect();
+fn run(
+    args: &[String],
+    format: Option<OutputFormat>,
+    open: &ElgatoDeviceBuilder,
+    dry_run: bool,
+    all: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let json = format == Some(OutputFormat::Json);
+    if json && !cfg!(feature = "json") {
+        return Err(CliError::Unsupported("--json needs the `json` feature".into()).into());
+    }
 
     if args.len() < 2 || args.iter().any(|a| a == "--help" || a == "-h") {
         print_usage();
         return Ok(());
     }
 
-    let device = ElgatoDevice::open()?;
+    let single = ["edid", "raw", "doctor", "udev", "watch", "diff", "version", "selftest", "gui"];
+    if all && single.contains(&args[1].as_str()) {
+        return Err(CliError::InvalidUsage("--all only applies to settings and --status").into());
+    }
+
+    if args[1] == "edid" {
+        return run_edid(&args[2..], json);
+    }
+
+    if args[1] == "doctor" {
+        return run_doctor(json);
+    }
+
+    if args[1] == "list" {
+        return run_list(format);
+    }
+
+    if args[1] == "version" {
+        return run_version(json, open);
+    }
+
+    if args[1] == "udev" {
+        return run_udev(&args[2..]);
+    }
+
+    if dry_run && ["watch", "diff", "reset-usb", "selftest", "gui"].contains(&args[1].as_str()) {
+        return Err(CliError::InvalidUsage("--dry-run only applies to settings that are written").into());
+    }
+
+    if args[1] == "watch" {
+        return run_watch(&args[2..], json, open);
+    }
+
+    if args[1] == "selftest" {
+        return run_selftest(json, open);
+    }
+
+    if args[1] == "gui" {
+        if json {
+            return Err(CliError::InvalidUsage("gui doesn't support --json").into());
+        }
+        if let Some(arg) = args.get(2) {
+            return Err(CliError::UnknownOption(arg.clone()).into());
+        }
+        return run_gui(open);
+    }
+
+    if args[1] == "reset-usb" {
+        if let Some(arg) = args.get(2) {
+            return Err(CliError::UnknownOption(arg.clone()).into());
+        }
+        let reset = |device: ElgatoDevice| {
+            let label = format!("{} (PID 0x{:04x})", device.model(), device.pid());
+            device.reset_usb()?;
+            log::info!("Reset {}; it will reappear on the bus in a few seconds", label);
+            Ok(())
+        };
+        if !all {
+            return Ok(reset(open.open()?)?);
+        }
+        let results: Vec<_> = open.open_all()?.into_iter().map(|card| card.and_then(reset)).collect();
+        for card in &results {
+            if let Err(e) = &card.result {
+                log::error!("{}: {}", device_label(card), e);
+            }
+        }
+        return devices_failed(results);
+    }
+
+    if args[1] == "diff" {
+        let [before, after] = &args[2..] else {
+            return Err(CliError::InvalidUsage("diff takes two saved status files: diff <BEFORE> <AFTER>").into());
+        };
+        let changes = load_status(before)?.diff(&load_status(after)?);
+        if json {
+            return print_json(&changes);
+        }
+        if changes.is_empty() {
+            println!("No changes between {} and {}", before, after);
+        } else {
+            println!("Changes from {} to {}:", before, after);
+            print_changes(&changes);
+        }
+        return Ok(());
+    }
+
+    if args[1] == "raw" {
+        if json {
+            return Err(CliError::InvalidUsage("raw doesn't support --json").into());
+        }
+        return run_raw(&args[2..], dry_run, open);
+    }
+
+    let bundle = if args[1] == "preset" {
+        let Some(name) = args.get(2) else {
+            if json {
+                let entries: Vec<ListEntry> = Bundle::ALL.iter().map(|b| ListEntry::new(b.name(), b.description())).collect();
+                return print_json(&entries);
+            }
+            println!("Setting bundles:");
+            for bundle in Bundle::ALL {
+                println!("    {:<16}{}", bundle.name(), bundle.description());
+            }
+            return Ok(());
+        };
+        let bundle: Bundle = name.parse().map_err(|_| CliError::InvalidArgument {
+            arg: "preset",
+            value: name.clone(),
+            valid: Bundle::VALID_VALUES,
+        })?;
+        Some(bundle)
+    } else {
+        None
+    };
+
+    let profile = if args[1] == "apply" && args.iter().any(|a| a == "--stdin") {
+        if flag_value(args, "--profile")?.is_some() {
+            return Err(CliError::InvalidUsage("apply takes --profile or --stdin, not both").into());
+        }
+        Some(stdin_profile()?)
+    } else if args[1] == "apply" {
+        let path = profile_path(args)?;
+        let Some(name) = flag_value(args, "--profile")? else {
+            return list_profiles(&path, json);
+        };
+        Some(load_profiles(&path)?.find(name)?.clone())
+    } else {
+        None
+    };
+
+    // Check the name and the existing file before touching the device.
+    let save_profile = if args[1] == "save-profile" {
+        let name = args
+            .get(2)
+            .filter(|name| !name.starts_with("--"))
+            .ok_or_else(|| CliError::MissingArgumentValue("save-profile".into()))?;
+        Profile::new(name)?;
+        let path = profile_path(args)?;
+        let text = if path.exists() { std::fs::read_to_string(&path)? } else { String::new() };
+        ProfileFile::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Some((name, path, text))
+    } else {
+        None
+    };
+
+    let query = args[1] == "get"
+        || ["--status", "--get", "--xu-info", "--firmware-version"]
+            .iter()
+            .any(|flag| args.iter().any(|a| a == flag));
+    let model = flag_value(args, "--model")?;
+    if model.is_some() && !dry_run {
+        return Err(CliError::InvalidUsage("--model only applies to --dry-run").into());
+    }
+
+    // Setter flags are checked up front too, so a typo doesn't leave the
+    // earlier settings half applied.
+    let changes = if bundle.is_none() && profile.is_none() && save_profile.is_none() && !query {
+        if args[1] == "set" { set_changes(&args[2..])? } else { setter_changes(args)? }
+    } else {
+        Vec::new()
+    };
+
+    if dry_run {
+        if query || save_profile.is_some() {
+            return Err(CliError::InvalidUsage("--dry-run only applies to settings that are written").into());
+        }
+        let model = match model {
+            Some(name) => name.parse().map_err(|_| CliError::InvalidArgument {
+                arg: "--model",
+                value: name.to_string(),
+                valid: DeviceModel::VALID_VALUES,
+            })?,
+            None => ElgatoDevice::detect_model()?,
+        };
+        let changes = match (bundle, profile) {
+            (Some(bundle), _) => bundle_changes(bundle, model),
+            (None, Some(profile)) => profile.apply_order(),
+            (None, None) => changes,
+        };
+        return print_dry_run(model, &changes, json);
+    }
+
+    if all {
+        let status = args.iter().any(|a| a == "--status");
+        let snapshot = flag_value(args, "--save")?.is_some() || flag_value(args, "--compare")?.is_some();
+        if save_profile.is_some() || (query && !status) || snapshot {
+            return Err(CliError::InvalidUsage("--all only applies to settings and --status").into());
+        }
+        let set = ElgatoDeviceSet::from_results(open.open_all()?);
+        if status {
+            return report_all_status(set.read_status(), format);
+        }
+        let results = match (bundle, profile) {
+            (Some(bundle), _) => {
+                log::info!("Applying {} to {} devices", bundle, set.len());
+                set.apply(|device| device.apply_bundle(bundle).map(|()| bundle_changes(bundle, device.model())))
+            }
+            (None, Some(profile)) => {
+                log::info!("Applying profile {} to {} devices", profile.name(), set.len());
+                set.apply(|device| device.apply_profile(&profile).map(|()| profile.apply_order()))
+            }
+            (None, None) => {
+                log::info!("Applying {} settings to {} devices", changes.len(), set.len());
+                set.apply(|device| apply_changes(device, &changes).map(|()| changes.clone()))
+            }
+        };
+        return report_all(results, json);
+    }
+
+    let device = open.open()?;
+    let mut report = Report::new(json);
+
+    if let Some(bundle) = bundle {
+        report.say(format_args!("Applying {} ({})", bundle, bundle.description()));
+        device.apply_bundle(bundle)?;
+        for change in bundle_changes(bundle, device.model()) {
+            report.applied(&change);
+        }
+        return report.finish();
+    }
+
+    if let Some((name, path, text)) = save_profile {
+        let profile = Profile::with_settings(name, device.current_settings()?)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, ProfileFile::upsert(&text, &profile)?)?;
+        log::info!("Saved profile {} to {}", name, path.display());
+        if json {
+            return print_json(&ProfileEntry::from(&profile));
+        }
+        print!("{}", profile);
+        return Ok(());
+    }
+
+    if let Some(profile) = profile {
+        report.say(format_args!("Applying profile {}", profile.name()));
+        for (i, change) in profile.apply_order().iter().enumerate() {
+            if i > 0 {
+                std::thread::sleep(SETTING_APPLY_DELAY);
+            }
+            apply_change(&device, change, &mut report)?;
+        }
+        return report.finish();
+    }
+
+    if device.is_degraded() {
+        log::warn!(
+            "{} is in USB 2.0 fallback mode (PID: 0x{:04x}).\n         Check the cable and connect it directly to a USB 3 port.",
+            device.model(),
+            device.pid()
+        );
+    }
 
     // Handle flags that don't require a value
     if args.iter().any(|a| a == "--status") {
-        println!("Reading current settings from {} (PID: 0x{:04x})...\n", device.model(), device.pid());
-        print!("{}", device.read_status()?);
+        let save = flag_value(args, "--save")?;
+        let compare = flag_value(args, "--compare")?;
+        if json {
+            let status = device.read_status()?;
+            match compare {
+                Some(path) => print_json(&load_status(path)?.diff(&status))?,
+                None => print_status_json(&status)?,
+            }
+            if let Some(path) = save {
+                save_status(&status, path)?;
+            }
+            return Ok(());
+        }
+        log::info!("Reading current settings from {} (PID: 0x{:04x})...", device.model(), device.pid());
+        let status = device.read_status()?;
+        if let Some(path) = compare {
+            let changes = load_status(path)?.diff(&status);
+            if changes.is_empty() {
+                println!("No changes since {}", path);
+            } else {
+                println!("Changes since {}:", path);
+                print_changes(&changes);
+            }
+        } else if format == Some(OutputFormat::Table) {
+            print!("{}", status_table(&[(device.model().to_string(), &status)]));
+        } else {
+            print!("{}", status);
+        }
+        if let Some(path) = save {
+            save_status(&status, path)?;
+            log::info!("Saved status to {}", path);
+        }
+        return Ok(());
+    }
+
+    // `get` prints the value the way the setter takes it (`on`), for
+    // scripts; `--get` prints it the way `--status` shows it (`On`).
+    let get = if args[1] == "get" {
+        Some(("get", 2))
+    } else {
+        args.iter().position(|a| a == "--get").map(|pos| ("--get", pos + 1))
+    };
+    if let Some((arg, pos)) = get {
+        let name = args.get(pos).ok_or_else(|| CliError::MissingArgumentValue(arg.into()))?;
+        let setting: Setting = name.parse().map_err(|_| CliError::InvalidArgument {
+            arg,
+            value: name.clone(),
+            valid: Setting::VALID_VALUES,
+        })?;
+        let Some(value) = device.read_setting(setting)? else {
+            let message = format!("{} is not readable on the {}", setting, device.model());
+            return Err(CliError::Unsupported(message).into());
+        };
+        if json {
+            return print_json(&value);
+        }
+        if arg == "--get" {
+            println!("{}", value);
+            return Ok(());
+        }
+        let token = value.token().ok_or_else(|| format!("{} read an unrecognized value: {}", setting, value))?;
+        println!("{}", token);
+        return Ok(());
+    }
+
+    if args.iter().any(|a| a == "--xu-info") {
+        if json {
+            return print_json(&device.xu_controls()?);
+        }
+        for control in device.xu_controls()? {
+            print!("{}", control);
+        }
         return Ok(());
     }
 
     if args.iter().any(|a| a == "--firmware-version") {
-        println!("Firmware version: {}", device.read_firmware_version()?);
+        match device.read_firmware_version()? {
+            version if json => print_json(&version)?,
+            Some(version) => println!("Firmware version: {}", version),
+            None => println!("Firmware version: Unknown"),
+        }
         return Ok(());
     }
 
-    let mut i = 1;
-    let mut settings_applied = false;
+    for (i, change) in changes.iter().enumerate() {
+        // Delay between consecutive settings, but not after the last one
+        if i > 0 {
+            std::thread::sleep(SETTING_APPLY_DELAY);
+        }
+        apply_change(&device, change, &mut report)?;
+    }
+
+    report.finish()
+}
+
+/// Parse the `--flag value` setter pairs, in command-line order.
+///
+/// The flags are the original spelling of `set`: `--hdr-map on` becomes
+/// `hdr-map=on` and goes through [`set_changes`], so the two can't drift
+/// apart.  Existing scripts rely on the flags; keep them working.
+fn setter_changes(args: &[String]) -> Result<Vec<SettingChange>, Box<dyn std::error::Error>> {
+    let mut pairs = Vec::new();
+    let mut flags = args[1..].iter();
+    while let Some(flag) = flags.next() {
+        let value = flags.next().ok_or_else(|| CliError::MissingArgumentValue(flag.clone()))?;
+        if flag == "--model" {
+            continue;
+        }
+        let key = flag.strip_prefix("--").filter(|key| SettingChange::KEYS.contains(key) || *key == "edid-range");
+        let Some(key) = key else {
+            print_usage();
+            return Err(CliError::UnknownOption(flag.clone()).into());
+        };
+        pairs.push(format!("{}={}", key, value));
+    }
+    if pairs.is_empty() {
+        return Ok(Vec::new());
+    }
+    set_changes(&pairs)
+}
+
+/// Parse `set key=value...`, in command-line order.  Keys are the setter
+/// flags without their dashes, so every [`SettingChange`] is reachable.
+fn set_changes(args: &[String]) -> Result<Vec<SettingChange>, Box<dyn std::error::Error>> {
+    let mut changes = Vec::new();
+    let mut pairs = args.iter();
+    while let Some(pair) = pairs.next() {
+        if pair == "--model" {
+            pairs.next();
+            continue;
+        }
+        let (key, value) = pair.split_once('=').ok_or_else(|| CliError::InvalidArgument {
+            arg: "set",
+            value: pair.clone(),
+            valid: "KEY=VALUE pairs, e.g. hdr-map=on",
+        })?;
+        changes.push(SettingChange::parse(key, value)?);
+    }
+    if changes.is_empty() {
+        return Err(CliError::MissingArgumentValue("set".into()).into());
+    }
+    Ok(changes)
+}
+
+/// The settings [`ElgatoDevice::apply_bundle`] writes on `model`.
+fn bundle_changes(bundle: Bundle, model: DeviceModel) -> Vec<SettingChange> {
+    let mut changes = vec![
+        SettingChange::HdmiRange(bundle.hdmi_range()),
+        SettingChange::EdidSource(bundle.edid_source()),
+        SettingChange::HdrMapping(bundle.hdr_mapping()),
+    ];
+    if let Some(scaler) = bundle.video_scaler().filter(|_| model == DeviceModel::Elgato4KS) {
+        changes.push(SettingChange::VideoScaler(scaler));
+    }
+    changes
+}
+
+/// One setting of `--dry-run --json` output.
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+#[cfg_attr(not(feature = "json"), allow(dead_code))]
+struct PlannedSetting {
+    #[cfg_attr(feature = "json", serde(flatten))]
+    setting: AppliedSetting,
+    transfers: Vec<TransferEntry>,
+}
+
+/// A [`PlannedTransfer`] with its data as the hex the text output shows.
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+#[cfg_attr(not(feature = "json"), allow(dead_code))]
+struct TransferEntry {
+    request: String,
+    data: String,
+    meaning: String,
+}
+
+impl From<PlannedTransfer> for TransferEntry {
+    fn from(transfer: PlannedTransfer) -> Self {
+        Self { data: transfer.hex(), request: transfer.request, meaning: transfer.meaning }
+    }
+}
+
+/// Print the transfers `changes` would send, without opening the device.
+fn print_dry_run(model: DeviceModel, changes: &[SettingChange], json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    // Plan everything first so an unsupported setting fails before any output.
+    let planned = changes
+        .iter()
+        .map(|change| change.planned_transfers(model))
+        .collect::<Result<Vec<_>, ElgatoError>>()?;
+
+    if json {
+        let entries: Vec<PlannedSetting> = changes
+            .iter()
+            .zip(planned)
+            .map(|(change, transfers)| PlannedSetting {
+                setting: change.into(),
+                transfers: transfers.into_iter().map(TransferEntry::from).collect(),
+            })
+            .collect();
+        return print_json(&entries);
+    }
+    log::info!("Dry run for the {}: nothing will be sent", model);
+    for (change, transfers) in changes.iter().zip(&planned) {
+        println!("Setting {} to {}", change.label(), change.display_value());
+        for transfer in transfers {
+            for line in transfer.to_string().lines() {
+                println!("    {}", line);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write `changes` in order, spaced by [`SETTING_APPLY_DELAY`].
+fn apply_changes(device: &ElgatoDevice, changes: &[SettingChange]) -> Result<(), ElgatoError> {
+    for (i, change) in changes.iter().enumerate() {
+        if i > 0 {
+            std::thread::sleep(SETTING_APPLY_DELAY);
+        }
+        device.apply_change(change)?;
+    }
+    Ok(())
+}
+
+/// `4K X (PID 0x009c, bus 2 address 5)`, to tell cards apart under `--all`.
+fn device_label<T>(result: &DeviceResult<T>) -> String {
+    format!(
+        "{} (PID 0x{:04x}, bus {} address {})",
+        result.model, result.pid, result.bus_number, result.address
+    )
+}
 
-    while i < args.len() {
-        let arg = &args[i];
+/// One card's outcome under `--all --json`: its `error`, or whatever the
+/// command reports for a single card.
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+#[cfg_attr(not(feature = "json"), allow(dead_code))]
+struct DeviceEntry<T> {
+    model: String,
+    pid: u16,
+    bus_number: u8,
+    address: u8,
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+    error: Option<String>,
+    #[cfg_attr(feature = "json", serde(flatten))]
+    result: Option<T>,
+}
 
-        if i + 1 >= args.len() {
-            return Err(CliError::MissingArgumentValue(arg.clone()).into());
+impl<T> DeviceEntry<T> {
+    fn new<R>(device: &DeviceResult<R>, result: Result<T, &ElgatoError>) -> Self {
+        let (result, error) = match result {
+            Ok(value) => (Some(value), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        Self {
+            model: device.model.to_string(),
+            pid: device.pid,
+            bus_number: device.bus_number,
+            address: device.address,
+            error,
+            result,
         }
+    }
+}
 
-        let value = &args[i + 1];
+/// `--json` output for `--all`.
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+#[cfg_attr(not(feature = "json"), allow(dead_code))]
+struct DevicesReport<T> {
+    devices: Vec<DeviceEntry<T>>,
+}
 
-        match arg.as_str() {
-            "--hdmi-range" => {
-                let range: EdidRangePolicy = value.parse().map_err(|_| CliError::InvalidArgument {
-                    arg: "--hdmi-range",
-                    value: value.clone(),
-                    valid: EdidRangePolicy::VALID_VALUES,
-                })?;
-                println!("Setting HDMI color range to {}", range);
-                device.set_hdmi_range(range)?;
-                settings_applied = true;
-            }
-            "--edid-source" => {
-                let source: EdidSource = value.parse().map_err(|_| CliError::InvalidArgument {
-                    arg: "--edid-source",
-                    value: value.clone(),
-                    valid: EdidSource::VALID_VALUES,
-                })?;
-                println!("Setting EDID source to {}", source);
-                device.set_edid_source(source)?;
-                settings_applied = true;
-            }
-            "--hdr-map" => {
-                let mode: HdrToneMapping = value.parse().map_err(|_| CliError::InvalidArgument {
-                    arg: "--hdr-map",
-                    value: value.clone(),
-                    valid: HdrToneMapping::VALID_VALUES,
-                })?;
-                println!("Setting HDR tone mapping to {}", mode);
-                device.set_hdr_mapping(mode)?;
-                settings_applied = true;
-            }
-            "--custom-edid" => {
-                let mode: CustomEdidMode = value.parse().map_err(|_| CliError::InvalidArgument {
-                    arg: "--custom-edid",
-                    value: value.clone(),
-                    valid: CustomEdidMode::VALID_VALUES,
-                })?;
-                println!("Setting custom EDID to {}", mode);
-                device.set_custom_edid(mode)?;
-                settings_applied = true;
-            }
-            "--audio-input" => {
-                let input: AudioInput = value.parse().map_err(|_| CliError::InvalidArgument {
-                    arg: "--audio-input",
-                    value: value.clone(),
-                    valid: AudioInput::VALID_VALUES,
-                })?;
-                println!("Setting audio input to {}", input);
-                device.set_audio_input(input)?;
-                settings_applied = true;
-            }
-            "--video-scaler" => {
-                let scaler: VideoScaler = value.parse().map_err(|_| CliError::InvalidArgument {
-                    arg: "--video-scaler",
-                    value: value.clone(),
-                    valid: VideoScaler::VALID_VALUES,
-                })?;
-                println!("Setting video scaler to {}", scaler);
-                device.set_video_scaler(scaler)?;
-                settings_applied = true;
-            }
-            "--usb-speed" => {
-                let speed: UsbSpeed = value.parse().map_err(|_| CliError::InvalidArgument {
-                    arg: "--usb-speed",
-                    value: value.clone(),
-                    valid: UsbSpeed::VALID_VALUES,
-                })?;
-                println!("Setting USB speed to {}", speed);
-                println!("WARNING: Device will disconnect and re-enumerate with a different PID!");
-                device.set_usb_speed(speed)?;
-                settings_applied = true;
+/// `applied` list of a card that took every setting.
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+#[cfg_attr(not(feature = "json"), allow(dead_code))]
+struct AppliedList {
+    applied: Vec<AppliedSetting>,
+}
+
+/// Print each card's outcome of an `--all` write.  Fails if any card did,
+/// with the first card's error deciding the exit code.
+fn report_all(results: Vec<DeviceResult<Vec<SettingChange>>>, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let mut entries = Vec::new();
+    for device in &results {
+        match &device.result {
+            Ok(changes) if json => {
+                let applied = changes.iter().map(AppliedSetting::from).collect();
+                entries.push(DeviceEntry::new(device, Ok(AppliedList { applied })));
+            }
+            Ok(changes) => {
+                let changes: Vec<String> = changes.iter().map(SettingChange::to_string).collect();
+                println!("{}: {}", device_label(device), changes.join(", "));
+            }
+            Err(e) => {
+                log::error!("{}: {}", device_label(device), e);
+                entries.push(DeviceEntry::new(device, Err(e)));
+            }
+        }
+    }
+    if json {
+        print_json(&DevicesReport { devices: entries })?;
+    }
+    devices_failed(results)
+}
+
+/// Print `--status --all`: every card's settings, or why they couldn't be read.
+fn report_all_status(
+    results: Vec<DeviceResult<DeviceStatus>>,
+    format: Option<OutputFormat>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if format == Some(OutputFormat::Json) {
+        print_all_status_json(&results)?;
+    } else if format == Some(OutputFormat::Table) {
+        let mut columns = Vec::new();
+        for device in &results {
+            match &device.result {
+                Ok(status) => columns.push((format!("{} {}:{}", device.model, device.bus_number, device.address), status)),
+                Err(e) => log::error!("{}: {}", device_label(device), e),
+            }
+        }
+        if !columns.is_empty() {
+            print!("{}", status_table(&columns));
+        }
+    } else {
+        for (i, device) in results.iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            match &device.result {
+                Ok(status) => {
+                    println!("{}:", device_label(device));
+                    print!("{}", status);
+                }
+                Err(e) => log::error!("{}: {}", device_label(device), e),
+            }
+        }
+    }
+    devices_failed(results)
+}
+
+/// Text cells printed as aligned columns; the first row is the header.
+struct Table(Vec<Vec<String>>);
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let columns = self.0.iter().map(Vec::len).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..columns)
+            .map(|c| self.0.iter().filter_map(|row| row.get(c)).map(|cell| cell.chars().count()).max().unwrap_or(0))
+            .collect();
+        for row in &self.0 {
+            let cells: Vec<String> = row.iter().zip(&widths).map(|(cell, &width)| format!("{:<width$}", cell)).collect();
+            writeln!(f, "{}", cells.join("  ").trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// `--status --format table`: a row per field and a column per card.
+/// Fields none of the cards report are left out; `-` marks the others.
+fn status_table(columns: &[(String, &DeviceStatus)]) -> Table {
+    let mut rows = vec![std::iter::once(String::new()).chain(columns.iter().map(|(name, _)| name.clone())).collect()];
+    let cards: Vec<_> = columns.iter().map(|(_, status)| status.rows()).collect();
+    for (i, (label, _)) in DeviceStatus::default().rows().iter().enumerate() {
+        let values: Vec<Option<String>> = cards.iter().map(|rows| rows[i].1.clone()).collect();
+        if values.iter().any(Option::is_some) {
+            let cells = values.into_iter().map(|v| v.unwrap_or_else(|| "-".to_string()));
+            rows.push(std::iter::once(label.to_string()).chain(cells).collect());
+        }
+    }
+    Table(rows)
+}
+
+/// `list`: the connected cards, found without opening them.
+fn run_list(format: Option<OutputFormat>) -> Result<(), Box<dyn std::error::Error>> {
+    let devices = ElgatoDevice::detect_all()?;
+    if devices.is_empty() {
+        return Err(ElgatoError::DeviceNotFound.into());
+    }
+    let link = |device: &DetectedDevice| device.link_speed.map_or("unknown".to_string(), |speed| speed.to_string());
+    match format.unwrap_or(OutputFormat::Table) {
+        OutputFormat::Json => print_json(&devices)?,
+        OutputFormat::Plain => {
+            for device in &devices {
+                println!(
+                    "{} (PID 0x{:04x}, bus {} address {}), revision {}, link {}",
+                    device.model,
+                    device.pid,
+                    device.bus_number,
+                    device.address,
+                    device.revision,
+                    link(device)
+                );
+            }
+        }
+        OutputFormat::Table => {
+            let header = ["MODEL", "PID", "BUS", "ADDRESS", "REVISION", "LINK"];
+            let mut rows = vec![header.iter().map(ToString::to_string).collect()];
+            for device in &devices {
+                rows.push(vec![
+                    device.model.to_string(),
+                    format!("{:04x}", device.pid),
+                    device.bus_number.to_string(),
+                    device.address.to_string(),
+                    device.revision.to_string(),
+                    link(device),
+                ]);
+            }
+            print!("{}", Table(rows));
+        }
+    }
+    Ok(())
+}
+
+/// `Err` if any card in `results` failed.
+fn devices_failed<T>(results: Vec<DeviceResult<T>>) -> Result<(), Box<dyn std::error::Error>> {
+    let total = results.len();
+    let mut errors = results.into_iter().filter_map(|r| r.result.err());
+    match errors.next() {
+        None => Ok(()),
+        Some(first) => Err(CliError::DevicesFailed { failed: 1 + errors.count(), total, first }.into()),
+    }
+}
+
+/// Write one setting, logging it and recording it in `report`.
+fn apply_change(device: &ElgatoDevice, change: &SettingChange, report: &mut Report) -> Result<(), ElgatoError> {
+    report.say(format_args!("Setting {} to {}", change.label(), change.display_value()));
+    if let SettingChange::UsbSpeed(_) = change {
+        report.say(format_args!("WARNING: Device will disconnect and re-enumerate with a different PID!"));
+    }
+    device.apply_change(change)?;
+    report.applied(change);
+    Ok(())
+}
+
+/// What a run changed.  Progress lines are logged at info level; with
+/// `--json` the changes are printed as one JSON document by
+/// [`finish`](Self::finish) instead of the closing summary.
+struct Report {
+    json: bool,
+    applied: Vec<AppliedSetting>,
+}
+
+/// One setting as `--json` shows it, e.g. `{"setting": "hdr-map", "value": "on"}`.
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+#[cfg_attr(not(feature = "json"), allow(dead_code))]
+struct AppliedSetting {
+    /// CLI flag without the dashes.
+    setting: &'static str,
+    /// The value as the flag takes it.
+    value: String,
+}
+
+impl From<&SettingChange> for AppliedSetting {
+    fn from(change: &SettingChange) -> Self {
+        Self { setting: change.key(), value: change.value() }
+    }
+}
+
+/// `--json` output for `run` when settings were written.
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+#[cfg_attr(not(feature = "json"), allow(dead_code))]
+struct AppliedReport<'a> {
+    applied: &'a [AppliedSetting],
+}
+
+impl Report {
+    fn new(json: bool) -> Self {
+        Self { json, applied: Vec::new() }
+    }
+
+    /// Log a progress line.
+    fn say(&self, line: fmt::Arguments<'_>) {
+        log::info!("{}", line);
+    }
+
+    /// Record a setting that was written.
+    fn applied(&mut self, change: &SettingChange) {
+        self.applied.push(change.into());
+    }
+
+    /// Print the closing summary.
+    fn finish(self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.json {
+            print_json(&AppliedReport { applied: &self.applied })
+        } else {
+            if self.applied.is_empty() {
+                log::info!("No settings were changed.");
+            } else {
+                log::info!("All settings applied successfully!");
+            }
+            Ok(())
+        }
+    }
+}
+
+/// An `edid slots` row, for `--json`.
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+#[cfg_attr(not(feature = "json"), allow(dead_code))]
+struct SlotEntry {
+    index: u8,
+    name: &'static str,
+}
+
+impl From<CustomEdidPreset> for SlotEntry {
+    fn from(slot: CustomEdidPreset) -> Self {
+        Self { index: slot.index(), name: slot.name() }
+    }
+}
+
+/// A `name  description` row of `preset` / `edid preset`, for `--json`.
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+#[cfg_attr(not(feature = "json"), allow(dead_code))]
+struct ListEntry {
+    name: &'static str,
+    description: &'static str,
+}
+
+impl ListEntry {
+    fn new(name: &'static str, description: &'static str) -> Self {
+        Self { name, description }
+    }
+}
+
+/// Anything `--json` can print.  Without the `json` feature, `--json` is
+/// rejected before anything runs, so there is no bound to satisfy.
+#[cfg(feature = "json")]
+trait Json: serde::Serialize {}
+#[cfg(feature = "json")]
+impl<T: serde::Serialize> Json for T {}
+#[cfg(not(feature = "json"))]
+trait Json {}
+#[cfg(not(feature = "json"))]
+impl<T> Json for T {}
+
+/// Print `value` as pretty JSON on stdout.
+#[cfg(feature = "json")]
+fn print_json(value: &impl Json) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+#[cfg(not(feature = "json"))]
+fn print_json(_: &impl Json) -> Result<(), Box<dyn std::error::Error>> {
+    Err(CliError::Unsupported("JSON output needs the `json` feature".into()).into())
+}
+
+/// Print `value` as one line of JSON, for output that streams.
+#[cfg(feature = "json")]
+fn print_json_line(value: &impl Json) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", serde_json::to_string(value)?);
+    Ok(())
+}
+
+#[cfg(not(feature = "json"))]
+fn print_json_line(_: &impl Json) -> Result<(), Box<dyn std::error::Error>> {
+    Err(CliError::Unsupported("JSON output needs the `json` feature".into()).into())
+}
+
+/// `$XDG_CONFIG_HOME/elgato4k-linux/profiles.conf`, falling back to
+/// `~/.config` when `XDG_CONFIG_HOME` isn't set.
+fn default_profile_path() -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config.join("elgato4k-linux").join("profiles.conf"))
+}
+
+/// The profile file from `--config`, or [`default_profile_path`].
+fn profile_path(args: &[String]) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    match flag_value(args, "--config")? {
+        Some(path) => Ok(PathBuf::from(path)),
+        None => Ok(default_profile_path().ok_or("Cannot find the config directory; pass --config <FILE>")?),
+    }
+}
+
+/// Read and parse a profile file.
+fn load_profiles(path: &Path) -> Result<ProfileFile, Box<dyn std::error::Error>> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    Ok(ProfileFile::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))?)
+}
+
+/// A profile and its settings, for `apply --json` and `save-profile --json`.
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+#[cfg_attr(not(feature = "json"), allow(dead_code))]
+struct ProfileEntry<'a> {
+    name: &'a str,
+    settings: Vec<AppliedSetting>,
+}
+
+impl<'a> From<&'a Profile> for ProfileEntry<'a> {
+    fn from(profile: &'a Profile) -> Self {
+        Self { name: profile.name(), settings: profile.settings().iter().map(Into::into).collect() }
+    }
+}
+
+/// `apply` without `--profile`: list the profiles in `path`.
+fn list_profiles(path: &Path, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let file = if path.exists() { load_profiles(path)? } else { ProfileFile::default() };
+    if json {
+        let entries: Vec<ProfileEntry> = file.profiles().iter().map(Into::into).collect();
+        return print_json(&entries);
+    }
+    if file.profiles().is_empty() {
+        println!("No profiles in {}", path.display());
+        return Ok(());
+    }
+    println!("Profiles in {}:", path.display());
+    for profile in file.profiles() {
+        let settings: Vec<String> = profile.settings().iter().map(|s| format!("{} {}", s.key(), s.value())).collect();
+        println!("    {:<16}{}", profile.name(), settings.join(", "));
+    }
+    Ok(())
+}
+
+/// The value following `flag`, if the flag is present.
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Result<Option<&'a str>, CliError> {
+    match args.iter().position(|a| a == flag) {
+        Some(pos) => args
+            .get(pos + 1)
+            .map(|v| Some(v.as_str()))
+            .ok_or_else(|| CliError::MissingArgumentValue(flag.to_string())),
+        None => Ok(None),
+    }
+}
+
+/// Print `--status --json` output.
+#[cfg(feature = "json")]
+fn print_status_json(status: &DeviceStatus) -> Result<(), Box<dyn std::error::Error>> {
+    print_json(&StatusSnapshot::new(status.clone()))
+}
+
+/// Print `--status --all --json`, each card's status as a snapshot.
+#[cfg(feature = "json")]
+fn print_all_status_json(results: &[DeviceResult<DeviceStatus>]) -> Result<(), Box<dyn std::error::Error>> {
+    /// Keeps the snapshot under a `status` key next to the card's details.
+    #[derive(serde::Serialize)]
+    struct StatusEntry {
+        status: StatusSnapshot,
+    }
+    let devices = results
+        .iter()
+        .map(|device| {
+            let status = device.result.as_ref().map(|status| StatusSnapshot::new(status.clone()));
+            DeviceEntry::new(device, status.map(|status| StatusEntry { status }))
+        })
+        .collect();
+    print_json(&DevicesReport { devices })
+}
+
+/// The `--status --compare` / `diff` list, one indented line per field.
+fn print_changes(changes: &[StatusChange]) {
+    for change in changes {
+        println!("    {}", change);
+    }
+}
+
+/// Write a `--status --save` snapshot.
+#[cfg(feature = "json")]
+fn save_status(status: &DeviceStatus, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::write(path, serde_json::to_string_pretty(&StatusSnapshot::new(status.clone()))? + "\n")?;
+    Ok(())
+}
+
+/// Read a `--status --save` snapshot back for `--compare`.
+#[cfg(feature = "json")]
+fn load_status(path: &str) -> Result<DeviceStatus, Box<dyn std::error::Error>> {
+    let text = std::fs::read_to_string(path)?;
+    let snapshot = serde_json::from_str(&text).map_err(|e| format!("{} is not a saved status: {}", path, e))?;
+    snapshot_status(snapshot, path)
+}
+
+/// The status in `snapshot`, if this version reads its schema.
+#[cfg(feature = "json")]
+fn snapshot_status(snapshot: StatusSnapshot, source: &str) -> Result<DeviceStatus, Box<dyn std::error::Error>> {
+    if !snapshot.is_supported() {
+        return Err(format!(
+            "{} uses status schema {}, but this version reads schema {}",
+            source,
+            snapshot.schema_version,
+            DeviceStatus::SCHEMA_VERSION
+        )
+        .into());
+    }
+    Ok(snapshot.status)
+}
+
+/// `apply --stdin`: settings as a JSON object of `set` keys and values, or
+/// a `--status --json` snapshot, whose writable settings are applied.
+#[cfg(feature = "json")]
+fn stdin_profile() -> Result<Profile, Box<dyn std::error::Error>> {
+    use std::io::Read;
+
+    let mut text = String::new();
+    std::io::stdin().read_to_string(&mut text)?;
+    let value: serde_json::Value =
+        serde_json::from_str(&text).map_err(|e| CliError::InvalidInput(format!("stdin is not JSON: {}", e)))?;
+    let Some(object) = value.as_object() else {
+        return Err(CliError::InvalidInput("stdin must be a JSON object, e.g. {\"hdr-map\": \"on\"}".into()).into());
+    };
+    let settings = if object.contains_key("schema_version") {
+        let snapshot = serde_json::from_value(value)
+            .map_err(|e| CliError::InvalidInput(format!("stdin is not a saved status: {}", e)))?;
+        SettingChange::from_status(&snapshot_status(snapshot, "stdin")?)
+    } else {
+        object
+            .iter()
+            .map(|(key, value)| match value {
+                serde_json::Value::String(value) => SettingChange::parse(key, value),
+                other => SettingChange::parse(key, &other.to_string()),
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    Ok(Profile::with_settings("stdin", settings)?)
+}
+
+#[cfg(not(feature = "json"))]
+fn print_status_json(_: &DeviceStatus) -> Result<(), Box<dyn std::error::Error>> {
+    Err(CliError::Unsupported("JSON output needs the `json` feature".into()).into())
+}
+
+#[cfg(not(feature = "json"))]
+fn print_all_status_json(_: &[DeviceResult<DeviceStatus>]) -> Result<(), Box<dyn std::error::Error>> {
+    Err(CliError::Unsupported("JSON output needs the `json` feature".into()).into())
+}
+
+#[cfg(not(feature = "json"))]
+fn save_status(_: &DeviceStatus, _: &str) -> Result<(), Box<dyn std::error::Error>> {
+    Err(CliError::Unsupported("status snapshots need the `json` feature".into()).into())
+}
+
+#[cfg(not(feature = "json"))]
+fn load_status(_: &str) -> Result<DeviceStatus, Box<dyn std::error::Error>> {
+    Err(CliError::Unsupported("status snapshots need the `json` feature".into()).into())
+}
+
+#[cfg(not(feature = "json"))]
+fn stdin_profile() -> Result<Profile, Box<dyn std::error::Error>> {
+    Err(CliError::Unsupported("apply --stdin needs the `json` feature".into()).into())
+}
+
+/// `edid` subcommands.  These only generate files; uploading to the card is
+/// not supported yet (see docs/BLOCKED_FEATURES.md).
+fn run_edid(args: &[String], json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let (label, mut edid, options) = match args.first().map(String::as_str) {
+        Some("preset") => {
+            let Some(name) = args.get(1) else {
+                if json {
+                    let entries: Vec<ListEntry> =
+                        edid::EDID_PRESETS.iter().map(|p| ListEntry::new(p.name, p.description)).collect();
+                    return print_json(&entries);
+                }
+                println!("Built-in EDID presets:");
+                for preset in edid::EDID_PRESETS {
+                    println!("    {:<16}{}", preset.name, preset.description);
+                }
+                return Ok(());
+            };
+            let preset = edid::edid_preset(name).ok_or_else(|| CliError::InvalidArgument {
+                arg: "edid preset",
+                value: name.clone(),
+                valid: edid::EdidPreset::VALID_VALUES,
+            })?;
+            (preset.name.to_string(), preset.build()?, &args[2..])
+        }
+        Some("slots") => {
+            if json {
+                let entries: Vec<SlotEntry> = CustomEdidPreset::all().map(SlotEntry::from).collect();
+                return print_json(&entries);
+            }
+            println!("EDID preset slots on the 4K X (names from the Elgato software):");
+            for slot in CustomEdidPreset::all() {
+                println!("    {:>2}  {}", slot.index(), slot.name());
+            }
+            return Ok(());
+        }
+        Some("patch") => {
+            let input = args.get(1).ok_or_else(|| CliError::MissingArgumentValue("edid patch".into()))?;
+            let edid = edid::Edid::from_bytes(std::fs::read(input)?)?;
+            edid.validate()?;
+            (input.clone(), edid, &args[2..])
+        }
+        other => {
+            return Err(CliError::InvalidArgument {
+                arg: "edid",
+                value: other.unwrap_or("").to_string(),
+                valid: "preset, patch, slots",
+            }
+            .into());
+        }
+    };
+
+    let mut out = None;
+    for pair in options.chunks(2) {
+        let [flag, value] = pair else {
+            return Err(CliError::MissingArgumentValue(pair[0].clone()).into());
+        };
+        match flag.as_str() {
+            "--out" => out = Some(value),
+            "--audio" => {
+                let patch = match value.as_str() {
+                    "stereo" => edid::EdidPatch::ForceStereo,
+                    "none" => edid::EdidPatch::StripAudio,
+                    _ => {
+                        return Err(CliError::InvalidArgument {
+                            arg: "--audio",
+                            value: value.clone(),
+                            valid: "stereo, none",
+                        }
+                        .into());
+                    }
+                };
+                patch.apply(&mut edid)?;
             }
             _ => {
-                eprintln!("Error: Unknown option '{}'", arg);
                 print_usage();
-                return Err("Unknown option".into());
+                return Err(CliError::UnknownOption(flag.clone()).into());
             }
         }
+    }
+    let out = out.ok_or_else(|| CliError::MissingArgumentValue("--out".into()))?;
 
-        i += 2;
-        // Delay between consecutive settings, but not after the last one
-        if i < args.len() {
-            std::thread::sleep(SETTING_APPLY_DELAY);
+    std::fs::write(out, edid.as_bytes())?;
+    log::info!("Wrote {} ({} bytes) to {}", label, edid.as_bytes().len(), out);
+    Ok(())
+}
+
+/// `doctor`: check the setup and print a fix for anything that's wrong.
+fn run_doctor(json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let checks = diagnose();
+    if json {
+        print_json(&checks)?;
+    } else {
+        let mut device = None;
+        for check in &checks {
+            if check.device != device {
+                device = check.device.clone();
+                if let Some(label) = &device {
+                    println!("{}:", label);
+                }
+            }
+            println!("    [{:<4}] {:<14}{}", check.status, check.name, check.detail);
+            if let Some(fix) = &check.fix {
+                println!("           {:<14}fix: {}", "", fix);
+            }
         }
     }
+    let failed = checks.iter().filter(|c| c.status == CheckStatus::Fail).count();
+    if failed > 0 {
+        return Err(format!("{} of {} checks failed", failed, checks.len()).into());
+    }
+    Ok(())
+}
 
-    if settings_applied {
-        println!("\nAll settings applied successfully!");
+/// `selftest`: read every setting and write one back unchanged, timing each
+/// step.
+fn run_selftest(json: bool, open: &ElgatoDeviceBuilder) -> Result<(), Box<dyn std::error::Error>> {
+    let device = open.open()?;
+    let steps = device.self_test();
+    if json {
+        print_json(&steps)?;
     } else {
-        println!("No settings were changed.");
+        println!("{} (PID 0x{:04x}):", device.model(), device.pid());
+        for step in &steps {
+            let ms = step.elapsed_us as f64 / 1000.0;
+            println!("    [{:<4}] {:<24}{:>8.1} ms  {}", step.status, step.name, ms, step.detail);
+        }
+    }
+    let failed = steps.iter().filter(|s| s.status == CheckStatus::Fail).count();
+    if failed > 0 {
+        return Err(format!("{} of {} steps failed", failed, steps.len()).into());
+    }
+    Ok(())
+}
+
+/// `version`: this build, the kernel, and each connected card's firmware,
+/// in one block for bug reports.
+fn run_version(json: bool, open: &ElgatoDeviceBuilder) -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg_attr(feature = "json", derive(serde::Serialize))]
+    struct VersionReport {
+        version: &'static str,
+        features: Vec<&'static str>,
+        kernel: Option<String>,
+        devices: Vec<CardVersion>,
+    }
+    #[cfg_attr(feature = "json", derive(serde::Serialize))]
+    #[cfg_attr(not(feature = "json"), allow(dead_code))]
+    struct CardVersion {
+        model: String,
+        pid: u16,
+        bus_number: u8,
+        address: u8,
+        revision: Option<String>,
+        firmware_version: Option<String>,
+        #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+        error: Option<String>,
     }
 
+    let features = [
+        ("update-check", cfg!(feature = "update-check")),
+        ("json", cfg!(feature = "json")),
+        ("unsafe-raw", cfg!(feature = "unsafe-raw")),
+    ];
+    let mut report = VersionReport {
+        version: env!("CARGO_PKG_VERSION"),
+        features: features.iter().filter(|(_, on)| *on).map(|(name, _)| *name).collect(),
+        kernel: std::fs::read_to_string("/proc/sys/kernel/osrelease").ok().map(|s| s.trim().to_string()),
+        devices: Vec::new(),
+    };
+    if !json {
+        let features = if report.features.is_empty() { "none".to_string() } else { report.features.join(", ") };
+        println!("elgato4k-linux {} (features: {})", report.version, features);
+        println!("Kernel: {}", report.kernel.as_deref().unwrap_or("unknown"));
+    }
+
+    // No card, or a bus that can't be scanned, still gets a report: that is
+    // when someone is most likely to be filing a bug.
+    let cards = match open.open_all() {
+        Ok(cards) => cards,
+        Err(ElgatoError::DeviceNotFound) => Vec::new(),
+        Err(e) => {
+            log::warn!("Could not scan for devices: {}", e);
+            Vec::new()
+        }
+    };
+    if !json && cards.is_empty() {
+        println!("No devices found");
+    }
+
+    let mut first_error = None;
+    for card in cards {
+        let label = device_label(&card);
+        let mut entry = CardVersion {
+            model: card.model.to_string(),
+            pid: card.pid,
+            bus_number: card.bus_number,
+            address: card.address,
+            revision: None,
+            firmware_version: None,
+            error: None,
+        };
+        let result = card.result.and_then(|device| {
+            entry.revision = Some(device.revision().to_string());
+            device.read_firmware_version()
+        });
+        match result {
+            Ok(version) => entry.firmware_version = version.map(|v| v.to_string()),
+            Err(e) => {
+                log::error!("{}: {}", label, e);
+                entry.error = Some(e.to_string());
+                first_error.get_or_insert(e);
+            }
+        }
+        if !json {
+            match &entry.error {
+                Some(error) => println!("{}: {}", label, error),
+                None => println!(
+                    "{}: revision {}, firmware {}",
+                    label,
+                    entry.revision.as_deref().unwrap_or("unknown"),
+                    entry.firmware_version.as_deref().unwrap_or("unknown")
+                ),
+            }
+        }
+        report.devices.push(entry);
+    }
+    if json {
+        print_json(&report)?;
+    }
+    first_error.map_or(Ok(()), |e| Err(e.into()))
+}
+
+/// `udev --print|--install`: the rules that let non-root users open the cards.
+fn run_udev(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let rules = udev_rules();
+    match args.first().map(String::as_str) {
+        Some("--print") => {
+            print!("{}", rules);
+            Ok(())
+        }
+        Some("--install") => {
+            std::fs::write(UDEV_RULES_PATH, &rules).map_err(|e| {
+                if e.kind() == std::io::ErrorKind::PermissionDenied {
+                    format!("Cannot write {}: run `sudo elgato4k-linux udev --install`", UDEV_RULES_PATH)
+                } else {
+                    format!("Cannot write {}: {}", UDEV_RULES_PATH, e)
+                }
+            })?;
+            log::info!("Wrote {}", UDEV_RULES_PATH);
+
+            // Apply the rules to cards that are already plugged in.
+            for udevadm in [&["control", "--reload-rules"][..], &["trigger", "--attr-match=idVendor=0fd9"]] {
+                let ok = std::process::Command::new("udevadm").args(udevadm).status().is_ok_and(|s| s.success());
+                if !ok {
+                    log::warn!("`udevadm {}` failed; replug the card to apply the rules", udevadm.join(" "));
+                }
+            }
+            log::info!("Only the plugdev group can open the cards. If you aren't in it yet:");
+            log::info!("   sudo groupadd -f plugdev && sudo usermod -aG plugdev $USER, then log in again");
+            Ok(())
+        }
+        other => Err(CliError::InvalidArgument {
+            arg: "udev",
+            value: other.unwrap_or("").to_string(),
+            valid: "--print, --install",
+        }
+        .into()),
+    }
+}
+
+/// How often `watch` reads the status without `--interval`.
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// `watch [--interval DURATION]`: read the status over and over and print
+/// the fields that changed, until interrupted.
+fn run_watch(args: &[String], json: bool, open: &ElgatoDeviceBuilder) -> Result<(), Box<dyn std::error::Error>> {
+    /// One changed field, for `watch --json` (one object per line).
+    #[cfg_attr(feature = "json", derive(serde::Serialize))]
+    #[cfg_attr(not(feature = "json"), allow(dead_code))]
+    struct WatchEvent {
+        unix_time: u64,
+        #[cfg_attr(feature = "json", serde(flatten))]
+        change: StatusChange,
+    }
+
+    let interval = match args {
+        [] => WATCH_INTERVAL,
+        [flag, value] if flag == "--interval" => parse_duration("--interval", value)?,
+        [flag] if flag == "--interval" => return Err(CliError::MissingArgumentValue(flag.clone()).into()),
+        [other, ..] => return Err(CliError::UnknownOption(other.clone()).into()),
+    };
+
+    let device = open.open()?;
+    log::info!("Watching {} every {:?}; press Ctrl-C to stop", device.model(), interval);
+    let mut last = device.read_status()?;
+    if !json {
+        print!("{}", last);
+    }
+    let mut failing = false;
+    loop {
+        std::thread::sleep(interval);
+        let status = match device.read_status() {
+            Ok(status) => status,
+            Err(e) => {
+                if !failing {
+                    log::warn!("[{}] Reading the status failed: {}", clock(unix_time()), e);
+                }
+                failing = true;
+                continue;
+            }
+        };
+        failing = false;
+        let now = unix_time();
+        for change in last.diff(&status) {
+            if json {
+                print_json_line(&WatchEvent { unix_time: now, change })?;
+            } else {
+                println!("[{}] {}", clock(now), change);
+            }
+        }
+        last = status;
+    }
+}
+
+/// `gui`: a window with the everyday settings and the live status.
+#[cfg(feature = "gui")]
+fn run_gui(open: &ElgatoDeviceBuilder) -> Result<(), Box<dyn std::error::Error>> {
+    gui::run(open.open()?, WATCH_INTERVAL)
+}
+
+#[cfg(not(feature = "gui"))]
+fn run_gui(_: &ElgatoDeviceBuilder) -> Result<(), Box<dyn std::error::Error>> {
+    Err(CliError::Unsupported("gui needs the `gui` feature".into()).into())
+}
+
+/// Seconds since the Unix epoch.
+fn unix_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// `unix_time` as a UTC time of day, e.g. `21:04:37 UTC`.
+fn clock(unix_time: u64) -> String {
+    let secs = unix_time % 86_400;
+    format!("{:02}:{:02}:{:02} UTC", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Parse a duration such as `500ms`, `1s`, `1.5s` or `2m`; a bare number
+/// is seconds.
+fn parse_duration(arg: &'static str, value: &str) -> Result<Duration, CliError> {
+    let invalid = || CliError::InvalidArgument {
+        arg,
+        value: value.to_string(),
+        valid: "a duration such as 500ms, 1s or 2m",
+    };
+    let (number, unit) = if let Some(number) = value.strip_suffix("ms") {
+        (number, 0.001)
+    } else if let Some(number) = value.strip_suffix('s') {
+        (number, 1.0)
+    } else if let Some(number) = value.strip_suffix('m') {
+        (number, 60.0)
+    } else {
+        (value, 1.0)
+    };
+    let number: f64 = number.parse().map_err(|_| invalid())?;
+    Duration::try_from_secs_f64(number * unit)
+        .ok()
+        .filter(|d| !d.is_zero())
+        .ok_or_else(invalid)
+}
+
+/// `raw uvc|hid <BYTES>...`: send a byte sequence as given, after showing it
+/// and asking for confirmation.
+#[cfg(feature = "unsafe-raw")]
+fn run_raw(args: &[String], dry_run: bool, open: &ElgatoDeviceBuilder) -> Result<(), Box<dyn std::error::Error>> {
+    let transport = args.first().map(String::as_str);
+    let model = match transport {
+        Some("uvc") => DeviceModel::Elgato4KX,
+        Some("hid") => DeviceModel::Elgato4KS,
+        other => {
+            return Err(CliError::InvalidArgument {
+                arg: "raw",
+                value: other.unwrap_or("").to_string(),
+                valid: "uvc, hid",
+            }
+            .into());
+        }
+    };
+
+    let (mut frame, mut add_lrc, mut yes) = (false, false, false);
+    let mut bytes = Vec::new();
+    for arg in &args[1..] {
+        match arg.as_str() {
+            "--frame" => frame = true,
+            "--lrc" => add_lrc = true,
+            "--yes" | "-y" => yes = true,
+            hex => bytes.extend(parse_hex(hex)?),
+        }
+    }
+    if model == DeviceModel::Elgato4KS && (frame || add_lrc) {
+        return Err(CliError::InvalidUsage("--frame and --lrc only apply to raw uvc").into());
+    }
+    if frame && add_lrc {
+        return Err(CliError::InvalidUsage("--frame already appends the LRC").into());
+    }
+
+    let transfers = if model == DeviceModel::Elgato4KX {
+        if frame {
+            bytes = raw_uvc_payload(&bytes)?;
+        } else if add_lrc {
+            bytes.push(lrc(&bytes));
+        }
+        PlannedTransfer::raw_uvc(&bytes)?
+    } else {
+        vec![PlannedTransfer::raw_hid(&bytes)?]
+    };
+    for transfer in &transfers {
+        println!("{}", transfer);
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    let device = open.open()?;
+    if device.model() != model {
+        let message = format!("raw {} needs a {}, found a {}", transport.unwrap_or(""), model, device.model());
+        return Err(CliError::Unsupported(message).into());
+    }
+    if !yes && !confirm(&format!("Send this to the {} (PID: 0x{:04x})?", device.model(), device.pid()))? {
+        return Err(CliError::Aborted.into());
+    }
+    match model {
+        DeviceModel::Elgato4KX => device.send_raw_uvc(&bytes)?,
+        DeviceModel::Elgato4KS => device.send_raw_hid(&bytes)?,
+    }
+    log::info!("Sent {} bytes", bytes.len());
     Ok(())
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let result = run();
-    check_for_update();
-    result
+#[cfg(not(feature = "unsafe-raw"))]
+fn run_raw(_: &[String], _: bool, _: &ElgatoDeviceBuilder) -> Result<(), Box<dyn std::error::Error>> {
+    Err(CliError::Unsupported("raw needs the `unsafe-raw` feature".into()).into())
+}
+
+/// Parse hex bytes such as `a1`, `0xa1` or `a10700`.
+#[cfg(feature = "unsafe-raw")]
+fn parse_hex(text: &str) -> Result<Vec<u8>, CliError> {
+    let digits = text.strip_prefix("0x").unwrap_or(text);
+    let invalid = || CliError::InvalidArgument {
+        arg: "raw",
+        value: text.to_string(),
+        valid: "hex bytes, e.g. a1 07 00 or a10700",
+    };
+    if digits.is_empty() || digits.len() % 2 != 0 {
+        return Err(invalid());
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| digits.get(i..i + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok()).ok_or_else(invalid))
+        .collect()
+}
+
+/// Ask a yes/no question on stderr.  Anything but `y`/`yes` (including end
+/// of input) is a no.
+#[cfg(feature = "unsafe-raw")]
+fn confirm(question: &str) -> std::io::Result<bool> {
+    use std::io::Write;
+    eprint!("{} [y/N] ", question);
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Writes log records to stderr: progress lines as-is, warnings and errors
+/// with the prefixes the CLI has always used, and debug output tagged with
+/// its level and module.
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        match record.level() {
+            log::Level::Error => eprintln!("Error: {}", record.args()),
+            log::Level::Warn => eprintln!("WARNING: {}", record.args()),
+            log::Level::Info => eprintln!("{}", record.args()),
+            level => eprintln!("[{}] {}: {}", level, record.target(), record.args()),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+/// Take `flag` and the value after it out of `args`.
+fn take_flag(args: &mut Vec<String>, flag: &str) -> Result<Option<String>, CliError> {
+    let Some(pos) = args.iter().position(|a| a == flag) else {
+        return Ok(None);
+    };
+    let value = args.get(pos + 1).cloned().ok_or_else(|| CliError::MissingArgumentValue(flag.into()))?;
+    args.drain(pos..=pos + 1);
+    Ok(Some(value))
+}
+
+/// Take `--format <FORMAT>` out of `args`.
+fn take_format(args: &mut Vec<String>) -> Result<Option<OutputFormat>, CliError> {
+    let Some(value) = take_flag(args, "--format")? else {
+        return Ok(None);
+    };
+    value.parse().map(Some).map_err(|_| CliError::InvalidArgument {
+        arg: "--format",
+        value,
+        valid: "table, plain, json",
+    })
+}
+
+/// Most `--retry` accepts; past this a card isn't going to answer.
+const MAX_RETRIES: u32 = 10;
+
+/// Take the flags that say how cards are opened (`--usb-timeout`,
+/// `--retry`, `--no-detach`, `--no-reattach`) out of `args`.
+fn take_open_options(args: &mut Vec<String>) -> Result<ElgatoDeviceBuilder, CliError> {
+    let mut open = ElgatoDevice::builder();
+    if let Some(value) = take_flag(args, "--usb-timeout")? {
+        open = open.timeout(parse_duration("--usb-timeout", &value)?);
+    }
+    if let Some(value) = take_flag(args, "--retry")? {
+        let retries = value.parse().ok().filter(|&n| n <= MAX_RETRIES).ok_or(CliError::InvalidArgument {
+            arg: "--retry",
+            value,
+            valid: "0 to 10",
+        })?;
+        open = open.retries(retries);
+    }
+    let mut take_switch = |flag: &str| {
+        let before = args.len();
+        args.retain(|a| a != flag);
+        args.len() != before
+    };
+    let detach = !take_switch("--no-detach");
+    let reattach = !take_switch("--no-reattach");
+    Ok(open.detach_kernel_driver(detach).reattach_kernel_driver(reattach))
+}
+
+/// Log level for the verbosity flags.  `--quiet` wins over `--verbose`;
+/// `--json` drops progress lines unless verbosity is asked for.
+fn log_level(args: &[String], json: bool) -> log::LevelFilter {
+    let has = |flags: &[&str]| args.iter().any(|a| flags.contains(&a.as_str()));
+    if has(&["--quiet", "-q"]) {
+        log::LevelFilter::Error
+    } else if has(&["-vv"]) {
+        log::LevelFilter::Trace
+    } else if has(&["--verbose", "-v"]) {
+        log::LevelFilter::Debug
+    } else if json {
+        log::LevelFilter::Warn
+    } else {
+        log::LevelFilter::Info
+    }
+}
+
+fn main() -> ExitCode {
+    // Global flags may appear anywhere, so take them out before the setter
+    // loop walks the remaining arguments in flag/value pairs.
+    let mut args: Vec<String> = std::env::args().collect();
+    let format = take_format(&mut args);
+    let open = take_open_options(&mut args);
+    let json = args.iter().any(|a| a == "--json") || matches!(format, Ok(Some(OutputFormat::Json)));
+    log::set_max_level(log_level(&args, json));
+    let dry_run = args.iter().any(|a| a == "--dry-run");
+    let all = args.iter().any(|a| a == "--all");
+    args.retain(|a| {
+        !matches!(a.as_str(), "--json" | "--dry-run" | "--all" | "--quiet" | "-q" | "--verbose" | "-v" | "-vv")
+    });
+    // Only fails if a logger is already set, which nothing else does.
+    let _ = log::set_logger(&LOGGER);
+
+    let result = match (format, open) {
+        (Ok(Some(format)), _) if json && format != OutputFormat::Json => {
+            Err(CliError::InvalidUsage("--json is --format json; give one or the other").into())
+        }
+        (Ok(format), Ok(open)) => {
+            run(&args, if json { Some(OutputFormat::Json) } else { format }, &open, dry_run, all)
+        }
+        (Err(e), _) | (_, Err(e)) => Err(e.into()),
+    };
+    // Skip the network round trip when nobody would see the notice.
+    if log::log_enabled!(log::Level::Info) {
+        check_for_update();
+    }
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            log::error!("{}", e);
+            ExitStatus::of(e.as_ref()).into()
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn default_log_level() {
+        assert_eq!(log_level(&args(&["elgato4k-linux", "--status"]), false), log::LevelFilter::Info);
+        assert_eq!(log_level(&args(&["elgato4k-linux", "--status"]), true), log::LevelFilter::Warn);
+    }
+
+    #[test]
+    fn verbosity_flags() {
+        assert_eq!(log_level(&args(&["x", "-v"]), false), log::LevelFilter::Debug);
+        assert_eq!(log_level(&args(&["x", "--verbose"]), true), log::LevelFilter::Debug);
+        assert_eq!(log_level(&args(&["x", "-vv"]), false), log::LevelFilter::Trace);
+        assert_eq!(log_level(&args(&["x", "-q"]), false), log::LevelFilter::Error);
+    }
+
+    #[test]
+    fn quiet_wins_over_verbose() {
+        assert_eq!(log_level(&args(&["x", "--verbose", "--quiet"]), false), log::LevelFilter::Error);
+    }
+
+    #[test]
+    fn format_flag_is_taken_out() {
+        let mut list = args(&["x", "--status", "--format", "table", "--all"]);
+        assert_eq!(take_format(&mut list).unwrap(), Some(OutputFormat::Table));
+        assert_eq!(list, args(&["x", "--status", "--all"]));
+        assert_eq!(take_format(&mut list).unwrap(), None);
+        assert!(take_format(&mut args(&["x", "--format", "yaml"])).is_err());
+        assert!(take_format(&mut args(&["x", "--format"])).is_err());
+    }
+
+    #[test]
+    fn transfer_flags_are_taken_out() {
+        let mut list = args(&["x", "--retry", "2", "--status", "--usb-timeout", "2500ms"]);
+        let open = take_open_options(&mut list).unwrap();
+        assert_eq!(open, ElgatoDevice::builder().timeout(Duration::from_millis(2500)).retries(2));
+        assert_eq!(list, args(&["x", "--status"]));
+        assert_eq!(take_open_options(&mut list).unwrap(), ElgatoDevice::builder());
+        for bad in [&["x", "--retry", "11"][..], &["x", "--retry", "-1"], &["x", "--usb-timeout", "0s"], &["x", "--retry"]] {
+            assert!(take_open_options(&mut args(bad)).is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn driver_flags_are_taken_out() {
+        let mut list = args(&["x", "--no-reattach", "--status"]);
+        let open = take_open_options(&mut list).unwrap();
+        assert_eq!(open, ElgatoDevice::builder().reattach_kernel_driver(false));
+        assert_eq!(list, args(&["x", "--status"]));
+        let open = take_open_options(&mut args(&["x", "--no-detach"])).unwrap();
+        assert_eq!(open, ElgatoDevice::builder().detach_kernel_driver(false));
+    }
+
+    #[test]
+    fn table_aligns_columns() {
+        let table = Table(vec![args(&["", "4K X 2:5", "4K S 3:7"]), args(&["HDR tone mapping", "On", "-"])]);
+        assert_eq!(table.to_string(), "                  4K X 2:5  4K S 3:7\nHDR tone mapping  On        -\n");
+    }
+
+    #[test]
+    fn status_table_skips_fields_no_card_reports() {
+        let x = DeviceStatus { hdr_tone_mapping: StatusField::Value(ReadValue::Known(HdrToneMapping::On)), ..Default::default() };
+        let s = DeviceStatus { audio_input: StatusField::ReadFailed("timeout".into()), ..Default::default() };
+        let table = status_table(&[("4K X".into(), &x), ("4K S".into(), &s)]).to_string();
+        assert!(table.contains("HDR tone mapping   On    -\n"), "{}", table);
+        assert!(table.contains("Audio input        -     Read failed (timeout)\n"), "{}", table);
+        assert!(!table.contains("Video scaler"), "{}", table);
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("--interval", "500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_duration("--interval", "1.5s").unwrap(), Duration::from_millis(1500));
+        assert_eq!(parse_duration("--interval", "2m").unwrap(), Duration::from_secs(120));
+        assert_eq!(parse_duration("--interval", "3").unwrap(), Duration::from_secs(3));
+        for bad in ["0s", "-1s", "fast", "1h", ""] {
+            assert!(parse_duration("--interval", bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn clock_is_utc_time_of_day() {
+        assert_eq!(clock(0), "00:00:00 UTC");
+        assert_eq!(clock(86_400 + 3600 * 21 + 64), "21:01:04 UTC");
+    }
+
+    #[test]
+    fn exit_status_follows_error_kind() {
+        let status = |e: Box<dyn std::error::Error>| ExitStatus::of(e.as_ref());
+        assert_eq!(status(ElgatoError::DeviceNotFound.into()), ExitStatus::DeviceNotFound);
+        assert_eq!(status(ElgatoError::Usb(rusb::Error::Access).into()), ExitStatus::PermissionDenied);
+        assert_eq!(status(ElgatoError::Usb(rusb::Error::Pipe).into()), ExitStatus::Usb);
+        assert_eq!(status(ElgatoError::KernelDriverActive { interface: 0 }.into()), ExitStatus::Failure);
+        assert_eq!(
+            status(ElgatoError::UnsupportedFeature { feature: "Video scaler", model: "4K X" }.into()),
+            ExitStatus::Unsupported
+        );
+        assert_eq!(status(CliError::UnknownOption("--bogus".into()).into()), ExitStatus::InvalidArgument);
+        assert_eq!(status(ProfileError::OutsideProfile.into()), ExitStatus::InvalidArgument);
+        assert_eq!(status("something else".into()), ExitStatus::Failure);
+    }
+
+    #[test]
+    fn all_fails_with_first_device_error() {
+        let device = |result| DeviceResult { model: DeviceModel::Elgato4KX, pid: 0x009c, bus_number: 2, address: 5, result };
+        let error = devices_failed(vec![device(Ok(())), device(Err(ElgatoError::Usb(rusb::Error::Access)))]).unwrap_err();
+        assert_eq!(error.to_string(), "1 of 2 devices failed");
+        assert_eq!(ExitStatus::of(error.as_ref()), ExitStatus::PermissionDenied);
+        assert!(devices_failed(vec![device(Ok(()))]).is_ok());
+    }
+
     #[test]
     fn extract_tag_with_v_prefix() {
         let json = r#"{"tag_name":"v0.3.0","name":"v0.3.0"}"#;
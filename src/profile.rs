@@ -0,0 +1,670 @@
+//! Named setting profiles, stored in a small INI-style file.
+//!
+//! ```text
+//! # ~/.config/elgato4k-linux/profiles.conf
+//! [gaming]
+//! hdr-map = on
+//! hdmi-range = auto
+//!
+//! [desktop]
+//! hdmi-range = expand
+//! hdr-map = off
+//! ```
+//!
+//! Keys are the CLI setting flags without the dashes and take the same
+//! values.  Blank lines and lines starting with `#` or `;` are ignored.
+//! Values may be wrapped in double quotes.
+
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::status::{CustomEdidStatus, DeviceStatus, ReadValue, StatusField};
+use crate::settings::{
+    AudioInput, CustomEdidMode, CustomEdidPreset, EdidRangePolicy, EdidSource, HdrToneMapping,
+    InputLimit, PictureControl, UsbSpeed, VideoScaler,
+};
+
+/// Errors from parsing a profile file or a single setting.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ProfileError {
+    /// The key isn't a writable setting.
+    #[error("Unknown setting '{0}'.\nValid settings: {valid}", valid = SettingChange::VALID_KEYS)]
+    UnknownSetting(String),
+
+    /// The value doesn't parse for its setting.
+    #[error("Invalid value '{value}' for {key}.\nValid values: {valid}")]
+    InvalidValue {
+        key: &'static str,
+        value: String,
+        valid: &'static str,
+    },
+
+    /// A line is neither a `[name]` header nor a `key = value` pair.
+    #[error("expected `[name]` or `key = value`, got '{0}'")]
+    Syntax(String),
+
+    /// A `key = value` pair appears before the first `[name]` header.
+    #[error("setting outside a [profile] section")]
+    OutsideProfile,
+
+    /// A profile name is empty or contains characters other than letters,
+    /// digits, `-` and `_`.
+    #[error("invalid profile name '{0}' (use letters, digits, '-' and '_')")]
+    InvalidName(String),
+
+    /// Two sections share a name.
+    #[error("profile '{0}' is defined twice")]
+    DuplicateProfile(String),
+
+    /// A profile sets the same setting twice.
+    #[error("{key} is set twice in profile '{profile}'")]
+    DuplicateSetting { profile: String, key: &'static str },
+
+    /// No profile with the requested name.
+    #[error("No profile named '{name}'. Available: {available}")]
+    NotFound { name: String, available: String },
+
+    /// Wraps another error with the line it came from.
+    #[error("line {line}: {error}")]
+    AtLine { line: usize, error: Box<ProfileError> },
+}
+
+// ---------------------------------------------------------------------------
+// Setting changes
+// ---------------------------------------------------------------------------
+
+/// One writable setting and the value to write.
+///
+/// The common currency of profiles and the CLI setters; apply it with
+/// [`ElgatoDevice::apply_change`](crate::ElgatoDevice::apply_change).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SettingChange {
+    HdmiRange(EdidRangePolicy),
+    EdidSource(EdidSource),
+    HdrMapping(HdrToneMapping),
+    CustomEdid(CustomEdidMode),
+    CustomEdidPreset(CustomEdidPreset),
+    InputLimit(InputLimit),
+    Picture(PictureControl, i32),
+    AudioInput(AudioInput),
+    VideoScaler(VideoScaler),
+    UsbSpeed(UsbSpeed),
+}
+
+impl SettingChange {
+    /// Every key [`parse`](Self::parse) accepts.
+    pub const KEYS: [&str; 13] = [
+        "hdmi-range",
+        "edid-source",
+        "hdr-map",
+        "custom-edid",
+        "custom-edid-preset",
+        "input-limit",
+        "brightness",
+        "contrast",
+        "hue",
+        "saturation",
+        "audio-input",
+        "video-scaler",
+        "usb-speed",
+    ];
+
+    /// [`KEYS`](Self::KEYS) as one string, for error messages.
+    pub const VALID_KEYS: &str = "hdmi-range, edid-source, hdr-map, custom-edid, custom-edid-preset, \
+                                  input-limit, brightness, contrast, hue, saturation, audio-input, \
+                                  video-scaler, usb-speed";
+
+    /// Parse a `key`/`value` pair, where `key` is a CLI setting flag without
+    /// the dashes (`hdr-map`) and `value` is anything that flag accepts.
+    pub fn parse(key: &str, value: &str) -> Result<Self, ProfileError> {
+        let name = if key == "edid-range" { "hdmi-range" } else { key };
+        let key = *Self::KEYS
+            .iter()
+            .find(|k| **k == name)
+            .ok_or_else(|| ProfileError::UnknownSetting(key.to_string()))?;
+        fn parsed<T: std::str::FromStr>(key: &'static str, value: &str, valid: &'static str) -> Result<T, ProfileError> {
+            value.parse().map_err(|_| ProfileError::InvalidValue {
+                key,
+                value: value.to_string(),
+                valid,
+            })
+        }
+
+        Ok(match key {
+            "hdmi-range" => Self::HdmiRange(parsed(key, value, EdidRangePolicy::VALID_VALUES)?),
+            "edid-source" => Self::EdidSource(parsed(key, value, EdidSource::VALID_VALUES)?),
+            "hdr-map" => Self::HdrMapping(parsed(key, value, HdrToneMapping::VALID_VALUES)?),
+            "custom-edid" => Self::CustomEdid(parsed(key, value, CustomEdidMode::VALID_VALUES)?),
+            "custom-edid-preset" => Self::CustomEdidPreset(parsed(key, value, CustomEdidPreset::VALID_VALUES)?),
+            "input-limit" => Self::InputLimit(parsed(key, value, InputLimit::VALID_VALUES)?),
+            "audio-input" => Self::AudioInput(parsed(key, value, AudioInput::VALID_VALUES)?),
+            "video-scaler" => Self::VideoScaler(parsed(key, value, VideoScaler::VALID_VALUES)?),
+            "usb-speed" => Self::UsbSpeed(parsed(key, value, UsbSpeed::VALID_VALUES)?),
+            _ => {
+                let control = parsed(key, key, PictureControl::VALID_VALUES)?;
+                Self::Picture(control, parsed(key, value, "an integer within the range the device reports")?)
+            }
+        })
+    }
+
+    /// The setting's key, e.g. `hdr-map`.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::HdmiRange(_) => "hdmi-range",
+            Self::EdidSource(_) => "edid-source",
+            Self::HdrMapping(_) => "hdr-map",
+            Self::CustomEdid(_) => "custom-edid",
+            Self::CustomEdidPreset(_) => "custom-edid-preset",
+            Self::InputLimit(_) => "input-limit",
+            Self::Picture(PictureControl::Brightness, _) => "brightness",
+            Self::Picture(PictureControl::Contrast, _) => "contrast",
+            Self::Picture(PictureControl::Hue, _) => "hue",
+            Self::Picture(PictureControl::Saturation, _) => "saturation",
+            Self::AudioInput(_) => "audio-input",
+            Self::VideoScaler(_) => "video-scaler",
+            Self::UsbSpeed(_) => "usb-speed",
+        }
+    }
+
+    /// The value as [`parse`](Self::parse) accepts it, e.g. `on`.
+    pub fn value(&self) -> String {
+        let on_off = |on: bool| if on { "on" } else { "off" };
+        match self {
+            Self::HdmiRange(EdidRangePolicy::Expand) => "expand".into(),
+            Self::HdmiRange(EdidRangePolicy::Shrink) => "shrink".into(),
+            Self::HdmiRange(EdidRangePolicy::Auto) => "auto".into(),
+            Self::EdidSource(EdidSource::Display) => "display".into(),
+            Self::EdidSource(EdidSource::Merged) => "merged".into(),
+            Self::EdidSource(EdidSource::Internal) => "internal".into(),
+            Self::HdrMapping(mode) => on_off(*mode == HdrToneMapping::On).into(),
+            Self::CustomEdid(mode) => on_off(*mode == CustomEdidMode::On).into(),
+            Self::CustomEdidPreset(preset) => preset.index().to_string(),
+            Self::InputLimit(limit) => limit.to_string().to_lowercase(),
+            Self::Picture(_, level) => level.to_string(),
+            Self::AudioInput(AudioInput::Embedded) => "embedded".into(),
+            Self::AudioInput(AudioInput::Analog) => "analog".into(),
+            Self::VideoScaler(scaler) => on_off(*scaler == VideoScaler::On).into(),
+            Self::UsbSpeed(UsbSpeed::FiveGbps) => "5g".into(),
+            Self::UsbSpeed(UsbSpeed::TenGbps) => "10g".into(),
+        }
+    }
+
+    /// Human-readable name of the setting, e.g. `HDR tone mapping`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::HdmiRange(_) => "HDMI color range",
+            Self::EdidSource(_) => "EDID source",
+            Self::HdrMapping(_) => "HDR tone mapping",
+            Self::CustomEdid(_) => "custom EDID",
+            Self::CustomEdidPreset(_) => "custom EDID preset",
+            Self::InputLimit(_) => "input limit",
+            Self::Picture(..) => self.key(),
+            Self::AudioInput(_) => "audio input",
+            Self::VideoScaler(_) => "video scaler",
+            Self::UsbSpeed(_) => "USB speed",
+        }
+    }
+
+    /// The new value for people, e.g. `Expand (Full)`.
+    pub fn display_value(&self) -> String {
+        match self {
+            Self::HdmiRange(v) => v.to_string(),
+            Self::EdidSource(v) => v.to_string(),
+            Self::HdrMapping(v) => v.to_string(),
+            Self::CustomEdid(v) => v.to_string(),
+            Self::CustomEdidPreset(v) => v.to_string(),
+            Self::InputLimit(v) => format!("{} (EDID preset {})", v, v.preset()),
+            Self::Picture(_, level) => level.to_string(),
+            Self::AudioInput(v) => v.to_string(),
+            Self::VideoScaler(v) => v.to_string(),
+            Self::UsbSpeed(v) => v.to_string(),
+        }
+    }
+
+    /// Every writable setting `status` has a known value for, ready to be
+    /// written back.
+    ///
+    /// The USB speed is left out: applying it re-enumerates the card even
+    /// when the mode doesn't change.
+    pub fn from_status(status: &DeviceStatus) -> Vec<Self> {
+        fn known<T: Copy>(field: &StatusField<ReadValue<T>>) -> Option<T> {
+            match field.value()? {
+                ReadValue::Known(v) => Some(*v),
+                ReadValue::Unknown(_) => None,
+            }
+        }
+
+        let custom_edid = status.custom_edid.value().and_then(|edid| match *edid {
+            CustomEdidStatus::Off => Some(Self::CustomEdid(CustomEdidMode::Off)),
+            CustomEdidStatus::On { preset_index } => CustomEdidPreset::new(preset_index).map(Self::CustomEdidPreset),
+        });
+        [
+            known(&status.edid_source).map(Self::EdidSource),
+            custom_edid,
+            known(&status.hdmi_color_range).map(Self::HdmiRange),
+            known(&status.hdr_tone_mapping).map(Self::HdrMapping),
+            known(&status.audio_input).map(Self::AudioInput),
+            known(&status.video_scaler).map(Self::VideoScaler),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Position in a profile's apply order.
+    ///
+    /// EDID changes go first, since the source renegotiates after them and
+    /// may reset its output; signal processing and audio follow; the USB
+    /// speed goes last because the card re-enumerates and drops off the bus.
+    fn apply_order(&self) -> u8 {
+        match self {
+            Self::EdidSource(_) => 0,
+            Self::CustomEdid(_) => 1,
+            Self::CustomEdidPreset(_) | Self::InputLimit(_) => 2,
+            Self::HdmiRange(_) => 3,
+            Self::HdrMapping(_) => 4,
+            Self::VideoScaler(_) => 5,
+            Self::Picture(..) => 6,
+            Self::AudioInput(_) => 7,
+            Self::UsbSpeed(_) => 8,
+        }
+    }
+}
+
+/// Shows the setting and its new value, e.g. `HDR tone mapping: On`.
+impl fmt::Display for SettingChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.label(), self.display_value())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Profiles
+// ---------------------------------------------------------------------------
+
+/// A named group of settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Profile {
+    name: String,
+    settings: Vec<SettingChange>,
+}
+
+impl Profile {
+    /// An empty profile, or [`ProfileError::InvalidName`] if `name` isn't
+    /// letters, digits, `-` and `_`.
+    pub fn new(name: &str) -> Result<Self, ProfileError> {
+        let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(ProfileError::InvalidName(name.to_string()));
+        }
+        Ok(Self { name: name.to_string(), settings: Vec::new() })
+    }
+
+    /// A profile holding `settings`, refusing duplicates like [`push`](Self::push).
+    pub fn with_settings(
+        name: &str,
+        settings: impl IntoIterator<Item = SettingChange>,
+    ) -> Result<Self, ProfileError> {
+        let mut profile = Self::new(name)?;
+        for change in settings {
+            profile.push(change)?;
+        }
+        Ok(profile)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Settings in the order they were written.
+    pub fn settings(&self) -> &[SettingChange] {
+        &self.settings
+    }
+
+    /// Add a setting, refusing a second value for the same key.
+    pub fn push(&mut self, change: SettingChange) -> Result<(), ProfileError> {
+        if self.settings.iter().any(|s| s.key() == change.key()) {
+            return Err(ProfileError::DuplicateSetting {
+                profile: self.name.clone(),
+                key: change.key(),
+            });
+        }
+        self.settings.push(change);
+        Ok(())
+    }
+
+    /// Settings in the order they should be applied: EDID first, USB speed
+    /// last.  Settings of the same kind keep their written order.
+    pub fn apply_order(&self) -> Vec<SettingChange> {
+        let mut settings = self.settings.clone();
+        settings.sort_by_key(SettingChange::apply_order);
+        settings
+    }
+}
+
+/// Deserialized through [`Profile::with_settings`], so a bad name or a
+/// repeated setting fails.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Profile {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct Fields {
+            name: String,
+            settings: Vec<SettingChange>,
+        }
+        let Fields { name, settings } = Fields::deserialize(deserializer)?;
+        Self::with_settings(&name, settings).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Writes the profile as a `[name]` section.
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "[{}]", self.name)?;
+        for setting in &self.settings {
+            writeln!(f, "{} = {}", setting.key(), setting.value())?;
+        }
+        Ok(())
+    }
+}
+
+/// The name in a `[name]` header line.
+fn header_name(line: &str) -> Option<&str> {
+    line.trim().strip_prefix('[')?.strip_suffix(']').map(str::trim)
+}
+
+/// The contents of a profile file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileFile {
+    profiles: Vec<Profile>,
+}
+
+impl ProfileFile {
+    /// Parse a profile file.  Errors carry the 1-based line number.
+    pub fn parse(text: &str) -> Result<Self, ProfileError> {
+        let mut file = Self::default();
+        for (index, line) in text.lines().enumerate() {
+            file.parse_line(line.trim())
+                .map_err(|error| ProfileError::AtLine { line: index + 1, error: Box::new(error) })?;
+        }
+        Ok(file)
+    }
+
+    fn parse_line(&mut self, line: &str) -> Result<(), ProfileError> {
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            return Ok(());
+        }
+        if let Some(name) = header_name(line) {
+            if self.get(name).is_some() {
+                return Err(ProfileError::DuplicateProfile(name.to_string()));
+            }
+            self.profiles.push(Profile::new(name)?);
+            return Ok(());
+        }
+        let (key, value) = line.split_once('=').ok_or_else(|| ProfileError::Syntax(line.to_string()))?;
+        let value = value.trim();
+        let value = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value);
+        let change = SettingChange::parse(key.trim(), value)?;
+        self.profiles.last_mut().ok_or(ProfileError::OutsideProfile)?.push(change)
+    }
+
+    /// `text` with `profile`'s section replaced, or appended if the file
+    /// has none.  The rest of the file, comments included, is kept as
+    /// written; `text` must parse, so a broken file isn't made worse.
+    pub fn upsert(text: &str, profile: &Profile) -> Result<String, ProfileError> {
+        Self::parse(text)?;
+
+        let lines: Vec<&str> = text.lines().collect();
+        let is_header = |line: &str| header_name(line).is_some();
+        let is_setting = |line: &str| {
+            let line = line.trim();
+            !line.is_empty() && !line.starts_with('#') && !line.starts_with(';')
+        };
+        let start = lines.iter().position(|l| header_name(l) == Some(profile.name.as_str()));
+
+        let mut out = String::new();
+        match start {
+            Some(start) => {
+                let next = lines[start + 1..].iter().position(|l| is_header(l)).map_or(lines.len(), |i| start + 1 + i);
+                // Comments and blank lines after the last setting belong to
+                // whatever follows, so they stay.
+                let end = lines[start + 1..next].iter().rposition(|l| is_setting(l)).map_or(start + 1, |i| start + 2 + i);
+                for line in &lines[..start] {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+                out.push_str(&profile.to_string());
+                for line in &lines[end..] {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+            }
+            None => {
+                out.push_str(text);
+                if !out.is_empty() && !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                if !out.is_empty() {
+                    out.push('\n');
+                }
+                out.push_str(&profile.to_string());
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn profiles(&self) -> &[Profile] {
+        &self.profiles
+    }
+
+    /// The profile called `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    /// Like [`get`](Self::get), but a missing profile is an error listing
+    /// the ones that exist.
+    pub fn find(&self, name: &str) -> Result<&Profile, ProfileError> {
+        self.get(name).ok_or_else(|| ProfileError::NotFound {
+            name: name.to_string(),
+            available: if self.profiles.is_empty() {
+                "none".to_string()
+            } else {
+                self.profiles.iter().map(|p| p.name.as_str()).collect::<Vec<_>>().join(", ")
+            },
+        })
+    }
+}
+
+impl fmt::Display for ProfileFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, profile) in self.profiles.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", profile)?;
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+# Capture setups
+[gaming]
+hdr-map = on
+hdmi-range = \"auto\"
+
+; desk
+[desktop]
+usb-speed = 10g
+hdmi-range = expand
+edid-source = display
+brightness = -5
+";
+
+    #[test]
+    fn parses_sections_and_settings() {
+        let file = ProfileFile::parse(SAMPLE).unwrap();
+        assert_eq!(file.profiles().len(), 2);
+        let gaming = file.get("gaming").unwrap();
+        assert_eq!(
+            gaming.settings(),
+            &[SettingChange::HdrMapping(HdrToneMapping::On), SettingChange::HdmiRange(EdidRangePolicy::Auto)]
+        );
+        let desktop = file.get("desktop").unwrap();
+        assert_eq!(desktop.settings()[3], SettingChange::Picture(PictureControl::Brightness, -5));
+    }
+
+    #[test]
+    fn apply_order_puts_edid_first_and_usb_speed_last() {
+        let file = ProfileFile::parse(SAMPLE).unwrap();
+        let keys: Vec<&str> = file.get("desktop").unwrap().apply_order().iter().map(|s| s.key()).collect();
+        assert_eq!(keys, ["edid-source", "hdmi-range", "brightness", "usb-speed"]);
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let file = ProfileFile::parse(SAMPLE).unwrap();
+        let written = file.to_string();
+        assert!(written.starts_with("[gaming]\nhdr-map = on\nhdmi-range = auto\n\n[desktop]\n"));
+        assert_eq!(ProfileFile::parse(&written).unwrap(), file);
+    }
+
+    #[test]
+    fn every_value_round_trips() {
+        let changes = [
+            SettingChange::HdmiRange(EdidRangePolicy::Shrink),
+            SettingChange::EdidSource(EdidSource::Merged),
+            SettingChange::CustomEdid(CustomEdidMode::Off),
+            SettingChange::CustomEdidPreset(CustomEdidPreset::new(12).unwrap()),
+            SettingChange::InputLimit(InputLimit::Slot1080pMobile),
+            SettingChange::Picture(PictureControl::Hue, 3),
+            SettingChange::AudioInput(AudioInput::Analog),
+            SettingChange::VideoScaler(VideoScaler::On),
+            SettingChange::UsbSpeed(UsbSpeed::FiveGbps),
+        ];
+        for change in changes {
+            assert_eq!(SettingChange::parse(change.key(), &change.value()), Ok(change));
+        }
+    }
+
+    #[test]
+    fn valid_keys_lists_every_key() {
+        assert_eq!(SettingChange::VALID_KEYS, SettingChange::KEYS.join(", "));
+    }
+
+    #[test]
+    fn edid_range_alias_is_accepted() {
+        assert_eq!(
+            SettingChange::parse("edid-range", "full"),
+            Ok(SettingChange::HdmiRange(EdidRangePolicy::Expand))
+        );
+    }
+
+    #[test]
+    fn errors_carry_line_numbers() {
+        let err = ProfileFile::parse("[a]\nhdr-map = maybe\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: Invalid value 'maybe' for hdr-map.\nValid values: on, off");
+        assert!(matches!(
+            ProfileFile::parse("hdr-map = on"),
+            Err(ProfileError::AtLine { line: 1, error }) if *error == ProfileError::OutsideProfile
+        ));
+        assert!(matches!(
+            ProfileFile::parse("[a]\n\nvolume = 3"),
+            Err(ProfileError::AtLine { line: 3, error }) if matches!(*error, ProfileError::UnknownSetting(_))
+        ));
+        assert!(matches!(
+            ProfileFile::parse("[a]\njunk"),
+            Err(ProfileError::AtLine { line: 2, error }) if matches!(*error, ProfileError::Syntax(_))
+        ));
+    }
+
+    #[test]
+    fn duplicates_are_rejected() {
+        assert!(ProfileFile::parse("[a]\n[a]").is_err());
+        assert!(ProfileFile::parse("[a]\nhdr-map = on\nhdr-map = off").is_err());
+    }
+
+    #[test]
+    fn profile_names_are_restricted() {
+        assert!(Profile::new("late-night_2").is_ok());
+        assert_eq!(Profile::new("a b"), Err(ProfileError::InvalidName("a b".into())));
+        assert!(Profile::new("").is_err());
+    }
+
+    #[test]
+    fn from_status_keeps_known_writable_values() {
+        let status = DeviceStatus {
+            hdmi_color_range: StatusField::Value(ReadValue::Known(EdidRangePolicy::Expand)),
+            hdr_tone_mapping: StatusField::Value(ReadValue::Unknown(0x07)),
+            edid_source: StatusField::ReadFailed("timeout".into()),
+            usb_speed: StatusField::Value(ReadValue::Known(crate::status::UsbSpeedStatus::FiveGbps)),
+            custom_edid: StatusField::Value(CustomEdidStatus::On { preset_index: 12 }),
+            video_scaler: StatusField::Value(ReadValue::Known(VideoScaler::On)),
+            ..Default::default()
+        };
+        let profile = Profile::with_settings("live", SettingChange::from_status(&status)).unwrap();
+        assert_eq!(
+            profile.to_string(),
+            "[live]\ncustom-edid-preset = 12\nhdmi-range = expand\nvideo-scaler = on\n"
+        );
+    }
+
+    #[test]
+    fn upsert_replaces_only_the_named_section() {
+        let text = "# mine\n[gaming]\nhdr-map = off\n\n# the desk\n[desktop]\nhdr-map = on\n";
+        let mut profile = Profile::new("gaming").unwrap();
+        profile.push(SettingChange::HdrMapping(HdrToneMapping::On)).unwrap();
+        profile.push(SettingChange::HdmiRange(EdidRangePolicy::Auto)).unwrap();
+        assert_eq!(
+            ProfileFile::upsert(text, &profile).unwrap(),
+            "# mine\n[gaming]\nhdr-map = on\nhdmi-range = auto\n\n# the desk\n[desktop]\nhdr-map = on\n"
+        );
+    }
+
+    #[test]
+    fn upsert_appends_new_sections() {
+        let profile = Profile::new("new").unwrap();
+        assert_eq!(ProfileFile::upsert("", &profile).unwrap(), "[new]\n");
+        assert_eq!(ProfileFile::upsert("[a]\nhdr-map = on", &profile).unwrap(), "[a]\nhdr-map = on\n\n[new]\n");
+        assert!(ProfileFile::upsert("junk", &profile).is_err());
+    }
+
+    #[test]
+    fn find_lists_available_profiles() {
+        let file = ProfileFile::parse(SAMPLE).unwrap();
+        assert_eq!(
+            file.find("streaming").unwrap_err().to_string(),
+            "No profile named 'streaming'. Available: gaming, desktop"
+        );
+        assert!(ProfileFile::default().find("x").unwrap_err().to_string().ends_with("Available: none"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn profile_serde_checks_name_and_settings() {
+        let profile = Profile::with_settings(
+            "gaming",
+            [SettingChange::HdrMapping(HdrToneMapping::On), SettingChange::Picture(PictureControl::Brightness, -5)],
+        )
+        .unwrap();
+        let json = serde_json::to_string(&profile).unwrap();
+        assert_eq!(serde_json::from_str::<Profile>(&json).unwrap(), profile);
+
+        let bad_name = r#"{"name":"my profile","settings":[]}"#;
+        assert!(serde_json::from_str::<Profile>(bad_name).is_err());
+        let repeated = r#"{"name":"x","settings":[{"HdrMapping":"On"},{"HdrMapping":"Off"}]}"#;
+        assert!(serde_json::from_str::<Profile>(repeated).is_err());
+    }
+}
@@ -23,6 +23,11 @@ pub const PIDS_4KS: &[(u16, &str)] = &[
     (0x00ae, "USB 2.0"),
 ];
 
+/// Product IDs the cards fall back to when they only get a USB 2.0 link
+/// (bad cable, USB 2.0 port or hub).  Video capture needs USB 3 bandwidth,
+/// so a device in one of these modes is treated as degraded.
+pub const PIDS_USB2_FALLBACK: &[u16] = &[0x009d, 0x00ae];
+
 // ---------------------------------------------------------------------------
 // HID protocol (4K S) — SET_REPORT / GET_REPORT on Interface 7
 // ---------------------------------------------------------------------------
@@ -77,6 +82,10 @@ pub const SUBCMD_EDID_MODE: u8 = 0x12;
 // single packet — no commit step is needed.
 /// Video scaler — `GetVideoScalerEnabled` / `SetVideoScalerEnabled`, 1 byte.
 pub const SUBCMD_VIDEO_SCALER: u8 = 0x19;
+/// Write sub-commands that reset, reboot, or hang the MCU (see
+/// docs/LOW_CONFIDENCE_COMMANDS.md).  Raw writes refuse these.
+#[cfg(feature = "unsafe-raw")]
+pub const HID_SUBCMDS_DENIED: &[u8] = &[0x09, 0x13, 0x14, 0x1a, 0x1b, 0x22, 0x23, 0x24];
 
 // ---------------------------------------------------------------------------
 // UVC Extension Unit protocol (4K X)
@@ -93,6 +102,9 @@ pub const UVC_GET_CUR: u8 = 0x81;
 /// GET_LEN bRequest — queries the current descriptor length for a selector.
 /// The device dynamically changes this after a SET_CUR to reflect the response size.
 pub const UVC_GET_LEN: u8 = 0x85;
+/// GET_INFO bRequest — 1-byte capability bitmap of a control
+/// (bit 0 = GET supported, bit 1 = SET supported).
+pub const UVC_GET_INFO: u8 = 0x86;
 /// UVC interface number for Extension Unit #4.
 pub const UVC_INTERFACE: u16 = 0;
 /// Extension Unit entity ID (XU #4, GUID 961073c7-49f7-44f2-ab42-e940405940c2).
@@ -114,11 +126,69 @@ pub const UVC_SUBCMD_EDID_RANGE_READ: u8 = 0x91;
 /// Sub-command: read HDR tone mapping state (family 0x06).
 /// Response byte[4]: 0x01=On, 0x00=Off.
 pub const UVC_SUBCMD_HDR_READ: u8 = 0x90;
+/// Sub-command: set EDID Range Policy (family 0x08, params `[01, value]`).
+/// Value: 0x00=Auto, 0x03=Expand, 0x04=Shrink.
+pub const UVC_SUBCMD_EDID_RANGE: u8 = 0x7c;
+/// Sub-command: set EDID source (family 0x0a, params `[source, 00, 00, 00]`).
+/// Source: 0x00=Internal, 0x01=Display, 0x04=Merged.
+pub const UVC_SUBCMD_EDID_SOURCE: u8 = 0x4d;
+/// Sub-command: set HDR tone mapping (family 0x07, param 0x01=On, 0x00=Off).
+pub const UVC_SUBCMD_HDR_TONEMAPPING: u8 = 0x1f;
+/// Sub-command: custom EDID preset select (family 0x0a).
+/// Payload byte[9] is the preset slot; byte[8] and bytes[10-11] are `00`, `80 00`.
+pub const UVC_SUBCMD_CUSTOM_EDID: u8 = 0x54;
+/// Custom EDID preset slots as labelled in the Elgato software.
+/// Slot 0 is the card's default EDID, which is what "custom EDID off" selects.
+pub const CUSTOM_EDID_PRESETS: &[(u8, &str)] = &[
+    (0, "Game Capture 4K X (Default)"),
+    (1, "1080p"),
+    (2, "1080p120 for mobile"),
+    (3, "1080p for Steam Deck"),
+    (4, "1080p HDR"),
+    (5, "1280x800 for Steam Deck"),
+    (6, "1440p"),
+    (7, "1440p HDR"),
+    (8, "3440x1440"),
+    (9, "3440x1440 HDR"),
+    (10, "4K X 2560x1080"),
+    (11, "4K X 3440x1440"),
+    (12, "Custom (user-uploaded)"),
+    (13, "Unknown (possibly second custom slot)"),
+];
 /// AT command ID for setting USB speed (4K X only, used with send_at_command).
 /// From RTICE_SDK_X64: `rtk_sendATCommand(0x8e, &local_418, local_218, 8)`.
 /// Payload: `[01 00 00 00, speed_value 00 00 00]` where speed=0x00 (5G) or 0x03 (10G).
 pub const AT_CMD_SET_USB_SPEED: u32 = 0x8e;
 
+// ---------------------------------------------------------------------------
+// UVC Processing Unit (4K X) — standard UVC 1.1 picture controls
+// ---------------------------------------------------------------------------
+
+/// GET_MIN bRequest.
+pub const UVC_GET_MIN: u8 = 0x82;
+/// GET_MAX bRequest.
+pub const UVC_GET_MAX: u8 = 0x83;
+/// GET_RES bRequest (step size).
+pub const UVC_GET_RES: u8 = 0x84;
+/// GET_DEF bRequest.
+pub const UVC_GET_DEF: u8 = 0x87;
+/// bDescriptorType of class-specific interface descriptors.
+pub const UVC_CS_INTERFACE: u8 = 0x24;
+/// bDescriptorSubtype of a Processing Unit descriptor.
+/// Layout: `[bLength, 0x24, 0x05, bUnitID, bSourceID, wMaxMultiplier(2), bControlSize, bmControls...]`.
+pub const UVC_VC_PROCESSING_UNIT: u8 = 0x05;
+/// bDescriptorSubtype of an Extension Unit descriptor.
+/// Layout: `[bLength, 0x24, 0x06, bUnitID, guid(16), bNumControls, bNrInPins, baSourceID(p), bControlSize, bmControls...]`.
+pub const UVC_VC_EXTENSION_UNIT: u8 = 0x06;
+/// PU selector: brightness (signed, bmControls bit 0).
+pub const UVC_PU_BRIGHTNESS: u8 = 0x02;
+/// PU selector: contrast (unsigned, bmControls bit 1).
+pub const UVC_PU_CONTRAST: u8 = 0x03;
+/// PU selector: hue (signed, bmControls bit 2).
+pub const UVC_PU_HUE: u8 = 0x06;
+/// PU selector: saturation (unsigned, bmControls bit 3).
+pub const UVC_PU_SATURATION: u8 = 0x07;
+
 // ---------------------------------------------------------------------------
 // BCD validation constants (for firmware version decoding)
 // ---------------------------------------------------------------------------
@@ -134,5 +204,9 @@ pub const BCD_MAX_DAY: u8 = 0x31;
 
 /// Default USB control transfer timeout.
 pub const USB_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+/// Pause before retrying a control transfer that timed out or hit an I/O error.
+pub const RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(50);
+/// Delay between consecutive setting changes to give the device time to process.
+pub const SETTING_APPLY_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
 /// Delay after HID read request before GET_REPORT.
 pub const HID_READ_DELAY: std::time::Duration = std::time::Duration::from_millis(10);
@@ -0,0 +1,144 @@
+//! Device self-test behind `elgato4k-linux selftest`.
+//!
+//! Where [`diagnose`](crate::diagnose) looks at the host's view of a card,
+//! the self-test talks to it: every readable setting is read, then HDR tone
+//! mapping is written back with the value it already has and read again.
+//! Each step is timed, so a card that answers slowly stands out too.
+
+use std::time::{Duration, Instant};
+
+use crate::device::ElgatoDevice;
+use crate::doctor::CheckStatus;
+use crate::error::ElgatoError;
+use crate::protocol::SETTING_APPLY_DELAY;
+use crate::settings::HdrToneMapping;
+use crate::status::{ReadValue, Setting, SettingValue};
+
+/// One step of [`ElgatoDevice::self_test`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TestStep {
+    /// What was done, e.g. `read hdr-map` or `write hdr-map`.
+    pub name: String,
+    pub status: CheckStatus,
+    /// The value read or written, or what went wrong.
+    pub detail: String,
+    /// How long the step took, in microseconds.
+    pub elapsed_us: u64,
+}
+
+impl TestStep {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>, elapsed: Duration) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+            elapsed_us: elapsed.as_micros().try_into().unwrap_or(u64::MAX),
+        }
+    }
+}
+
+impl ElgatoDevice {
+    /// Read every setting the model reports, then write HDR tone mapping
+    /// with its current value and read it back, timing each step.
+    ///
+    /// Settings the model can't report are left out; one it should report
+    /// but returned nothing for is a failed step.
+    ///
+    /// Never fails: problems are reported as failed steps.  The only write
+    /// repeats a value the card already has, so nothing changes.  The write
+    /// is skipped (with a warning) when the current value couldn't be read.
+    pub fn self_test(&self) -> Vec<TestStep> {
+        let mut steps = Vec::new();
+        let mut hdr = None;
+        for setting in Setting::ALL {
+            let name = format!("read {}", setting);
+            let (result, elapsed) = timed(|| self.read_setting(setting));
+            match result {
+                Ok(None) if !self.can_read(setting) => {}
+                Ok(None) => {
+                    steps.push(TestStep::new(name, CheckStatus::Fail, "the device returned no value", elapsed));
+                }
+                Ok(Some(value)) => {
+                    if let SettingValue::HdrToneMapping(ReadValue::Known(mode)) = value {
+                        hdr = Some(mode);
+                    }
+                    steps.push(TestStep::new(name, CheckStatus::Pass, value.to_string(), elapsed));
+                }
+                Err(e) => steps.push(TestStep::new(name, CheckStatus::Fail, e.to_string(), elapsed)),
+            }
+        }
+
+        let name = format!("write {}", Setting::HdrToneMapping);
+        let Some(mode) = hdr else {
+            let detail = "skipped: the current value wasn't read, so there is nothing safe to write";
+            steps.push(TestStep::new(name, CheckStatus::Warn, detail, Duration::ZERO));
+            return steps;
+        };
+        let (result, elapsed) = timed(|| self.set_hdr_mapping(mode));
+        if let Err(e) = result {
+            steps.push(TestStep::new(name, CheckStatus::Fail, e.to_string(), elapsed));
+            return steps;
+        }
+        steps.push(TestStep::new(name, CheckStatus::Pass, format!("{} (unchanged)", mode), elapsed));
+
+        std::thread::sleep(SETTING_APPLY_DELAY);
+        let (result, elapsed) = timed(|| self.read_setting(Setting::HdrToneMapping));
+        let (status, detail) = readback(mode, result);
+        steps.push(TestStep::new(format!("read back {}", Setting::HdrToneMapping), status, detail, elapsed));
+        steps
+    }
+}
+
+/// Run `f`, returning its result and how long it took.
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// Judge the HDR tone mapping read after writing `written`.
+fn readback(written: HdrToneMapping, read: Result<Option<SettingValue>, ElgatoError>) -> (CheckStatus, String) {
+    match read {
+        Ok(Some(SettingValue::HdrToneMapping(ReadValue::Known(mode)))) if mode == written => {
+            (CheckStatus::Pass, mode.to_string())
+        }
+        Ok(Some(value)) => (CheckStatus::Fail, format!("read {} after writing {}", value, written)),
+        Ok(None) => (CheckStatus::Fail, "the setting was readable before the write but not after".to_string()),
+        Err(e) => (CheckStatus::Fail, e.to_string()),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readback_matches_written_value() {
+        let read = |mode| Ok(Some(SettingValue::HdrToneMapping(ReadValue::Known(mode))));
+        assert_eq!(readback(HdrToneMapping::On, read(HdrToneMapping::On)), (CheckStatus::Pass, "On".into()));
+        let (status, detail) = readback(HdrToneMapping::On, read(HdrToneMapping::Off));
+        assert_eq!(status, CheckStatus::Fail);
+        assert_eq!(detail, "read Off after writing On");
+    }
+
+    #[test]
+    fn readback_failures() {
+        let unknown = Ok(Some(SettingValue::HdrToneMapping(ReadValue::Unknown(0x07))));
+        assert_eq!(readback(HdrToneMapping::Off, unknown).0, CheckStatus::Fail);
+        assert_eq!(readback(HdrToneMapping::Off, Ok(None)).0, CheckStatus::Fail);
+        let (status, detail) = readback(HdrToneMapping::Off, Err(ElgatoError::DeviceNotFound));
+        assert_eq!(status, CheckStatus::Fail);
+        assert!(detail.contains("not found"));
+    }
+
+    #[test]
+    fn step_records_microseconds() {
+        let step = TestStep::new("read hdr-map", CheckStatus::Pass, "On", Duration::from_micros(2500));
+        assert_eq!(step.elapsed_us, 2500);
+    }
+}
@@ -11,9 +11,11 @@ use std::fmt;
 use std::str::FromStr;
 
 use crate::protocol::*;
+use crate::uvc::UvcFrame;
 
 /// Which device model we're talking to.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DeviceModel {
     Elgato4KX,
     Elgato4KS,
@@ -28,13 +30,28 @@ impl fmt::Display for DeviceModel {
     }
 }
 
+impl FromStr for DeviceModel {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace([' ', '-'], "").as_str() {
+            "4kx" => Ok(Self::Elgato4KX),
+            "4ks" => Ok(Self::Elgato4KS),
+            _ => Err(()),
+        }
+    }
+}
+
+impl DeviceModel {
+    pub const VALID_VALUES: &str = "4kx, 4ks";
+}
+
 // ---------------------------------------------------------------------------
 // Helper: build a 255-byte HID write packet from header + sub_cmd + value
 // ---------------------------------------------------------------------------
 
 /// Build a single HID settings write packet: `[06 06 06 55 02] [sub_cmd] [value]`
 /// padded to [`HID_PACKET_SIZE`].
-fn hid_write_packet(sub_cmd: u8, value: u8) -> [u8; HID_PACKET_SIZE] {
+pub(crate) fn hid_write_packet(sub_cmd: u8, value: u8) -> [u8; HID_PACKET_SIZE] {
     let mut pkt = [0u8; HID_PACKET_SIZE];
     pkt[..HID_WRITE_HEADER.len()].copy_from_slice(&HID_WRITE_HEADER);
     pkt[HID_WRITE_HEADER.len()] = sub_cmd;
@@ -52,6 +69,7 @@ fn hid_write_packet(sub_cmd: u8, value: u8) -> [u8; HID_PACKET_SIZE] {
 /// EDID Range Policy via the `a1 08 ... 7c` payload family (11 bytes).
 /// The official Elgato software labels this as "HDMI Color Range" in the UI.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EdidRangePolicy {
     /// Full range (0–255).
     Expand,
@@ -86,12 +104,14 @@ impl FromStr for EdidRangePolicy {
 impl EdidRangePolicy {
     pub const VALID_VALUES: &str = "expand, shrink, auto";
 
-    pub fn payload_4kx(&self) -> &'static [u8] {
-        match self {
-            Self::Auto   => &[0xa1, 0x08, 0x00, 0x00, 0x7c, 0x00, 0x00, 0x00, 0x01, 0x00, 0xda],
-            Self::Expand => &[0xa1, 0x08, 0x00, 0x00, 0x7c, 0x00, 0x00, 0x00, 0x01, 0x03, 0xd7],
-            Self::Shrink => &[0xa1, 0x08, 0x00, 0x00, 0x7c, 0x00, 0x00, 0x00, 0x01, 0x04, 0xd6],
-        }
+    /// `a1 08 00 00 7c 00 00 00 01 [value] LRC`.
+    pub fn payload_4kx(&self) -> Vec<u8> {
+        let value = match self {
+            Self::Auto   => 0x00,
+            Self::Expand => 0x03,
+            Self::Shrink => 0x04,
+        };
+        UvcFrame::new(UVC_SUBCMD_EDID_RANGE.into()).params(&[0x01, value]).build()
     }
 
     pub fn payload_4ks(&self) -> [u8; HID_PACKET_SIZE] {
@@ -110,6 +130,7 @@ impl EdidRangePolicy {
 
 /// EDID source selection.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EdidSource {
     /// Passthrough monitor's EDID.
     Display,
@@ -144,12 +165,14 @@ impl FromStr for EdidSource {
 impl EdidSource {
     pub const VALID_VALUES: &str = "display, merged, internal";
 
-    pub fn payload_4kx(&self) -> &'static [u8] {
-        match self {
-            Self::Display  => &[0xa1, 0x0a, 0x00, 0x00, 0x4d, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x07],
-            Self::Merged   => &[0xa1, 0x0a, 0x00, 0x00, 0x4d, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x04],
-            Self::Internal => &[0xa1, 0x0a, 0x00, 0x00, 0x4d, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08],
-        }
+    /// `a1 0a 00 00 4d 00 00 00 [source] 00 00 00 LRC`.
+    pub fn payload_4kx(&self) -> Vec<u8> {
+        let value = match self {
+            Self::Internal => 0x00,
+            Self::Display  => 0x01,
+            Self::Merged   => 0x04,
+        };
+        UvcFrame::new(UVC_SUBCMD_EDID_SOURCE.into()).params(&[value, 0x00, 0x00, 0x00]).build()
     }
 
     /// EDID source uses a single HID packet (no commit needed).
@@ -170,6 +193,7 @@ impl EdidSource {
 
 /// HDR tone mapping toggle.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HdrToneMapping {
     On,
     Off,
@@ -198,11 +222,13 @@ impl FromStr for HdrToneMapping {
 impl HdrToneMapping {
     pub const VALID_VALUES: &str = "on, off";
 
-    pub fn payload_4kx(&self) -> &'static [u8] {
-        match self {
-            Self::On  => &[0xa1, 0x07, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00, 0x01, 0x38],
-            Self::Off => &[0xa1, 0x07, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00, 0x00, 0x39],
-        }
+    /// `a1 07 00 00 1f 00 00 00 [on] LRC`.
+    pub fn payload_4kx(&self) -> Vec<u8> {
+        let value = match self {
+            Self::On  => 0x01,
+            Self::Off => 0x00,
+        };
+        UvcFrame::new(UVC_SUBCMD_HDR_TONEMAPPING.into()).param(value).build()
     }
 
     pub fn payload_4ks(&self) -> [u8; HID_PACKET_SIZE] {
@@ -220,6 +246,7 @@ impl HdrToneMapping {
 
 /// Custom EDID preset toggle (4K X only).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CustomEdidMode {
     Off,
     On,
@@ -248,14 +275,158 @@ impl FromStr for CustomEdidMode {
 impl CustomEdidMode {
     pub const VALID_VALUES: &str = "on, off";
 
-    pub fn payload_4kx(&self) -> &'static [u8] {
+    /// Off and on are preset slots 0 and 1, see [`CustomEdidPreset`].
+    pub fn payload_4kx(&self) -> Vec<u8> {
+        let slot = match self {
+            Self::Off => 0,
+            Self::On  => 1,
+        };
+        CustomEdidPreset(slot).payload_4kx()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Custom EDID preset slot (4K X only)
+// ---------------------------------------------------------------------------
+
+/// Custom EDID preset slot selection (4K X only, family 0x0a cmd 0x54).
+///
+/// Slots 0–13 were observed in USB captures of the Elgato software; slot 0
+/// is the default EDID and slot 12 holds a user-uploaded EDID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CustomEdidPreset(u8);
+
+impl CustomEdidPreset {
+    pub const VALID_VALUES: &str = "0-13 (0 = default, 12 = user-uploaded)";
+
+    /// Create a preset selection, returning `None` for slots outside 0–13.
+    pub fn new(index: u8) -> Option<Self> {
+        CUSTOM_EDID_PRESETS
+            .iter()
+            .any(|&(slot, _)| slot == index)
+            .then_some(Self(index))
+    }
+
+    /// Every slot, in index order.
+    pub fn all() -> impl Iterator<Item = Self> {
+        CUSTOM_EDID_PRESETS.iter().map(|&(slot, _)| Self(slot))
+    }
+
+    /// The preset slot index.
+    pub fn index(&self) -> u8 {
+        self.0
+    }
+
+    /// The preset name as shown in the Elgato software.
+    pub fn name(&self) -> &'static str {
+        CUSTOM_EDID_PRESETS
+            .iter()
+            .find(|&&(slot, _)| slot == self.0)
+            .map_or("Unknown", |&(_, name)| name)
+    }
+
+    /// `a1 0a 00 00 54 00 00 00 00 [slot] 80 00 LRC`.
+    pub fn payload_4kx(&self) -> Vec<u8> {
+        UvcFrame::new(UVC_SUBCMD_CUSTOM_EDID.into()).params(&[0x00, self.0, 0x80, 0x00]).build()
+    }
+}
+
+impl fmt::Display for CustomEdidPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.0, self.name())
+    }
+}
+
+impl FromStr for CustomEdidPreset {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim().parse().ok().and_then(Self::new).ok_or(())
+    }
+}
+
+/// Serialized as the bare slot index; out-of-range slots fail to deserialize.
+#[cfg(feature = "serde")]
+impl serde::Serialize for CustomEdidPreset {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CustomEdidPreset {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let index = u8::deserialize(deserializer)?;
+        Self::new(index).ok_or_else(|| {
+            serde::de::Error::custom(format!("invalid custom EDID preset {}, expected {}", index, Self::VALID_VALUES))
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Input Limit (4K X only)
+// ---------------------------------------------------------------------------
+
+/// Restrict the modes the source is offered to one of the card's built-in
+/// EDID preset slots (4K X only).
+///
+/// Useful on 5Gbps links, which can't carry the modes of the default EDID.
+/// Variants are named after the slot labels in the Elgato software.  The
+/// mode list behind each slot is Elgato's and can't be read back, so a
+/// label like "1440p" says what the slot is for, not the highest mode it
+/// offers.  There is no slot for a 1440p120 or 4K60 cap (see
+/// docs/BLOCKED_FEATURES.md).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputLimit {
+    /// The default EDID (slot 0).
+    Off,
+    /// The "1440p" slot (6).
+    Slot1440p,
+    /// The "1080p120 for mobile" slot (2).
+    Slot1080pMobile,
+    /// The "1080p" slot (1).
+    Slot1080p,
+}
+
+impl fmt::Display for InputLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Off => &[0xa1, 0x0a, 0x00, 0x00, 0x54, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x81],
-            Self::On  => &[0xa1, 0x0a, 0x00, 0x00, 0x54, 0x00, 0x00, 0x00, 0x00, 0x01, 0x80, 0x00, 0x80],
+            Self::Off => write!(f, "Off"),
+            Self::Slot1440p => write!(f, "1440p"),
+            Self::Slot1080pMobile => write!(f, "1080p-mobile"),
+            Self::Slot1080p => write!(f, "1080p"),
         }
     }
 }
 
+impl FromStr for InputLimit {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" | "none" => Ok(Self::Off),
+            "1440p" => Ok(Self::Slot1440p),
+            "1080p-mobile" => Ok(Self::Slot1080pMobile),
+            "1080p" => Ok(Self::Slot1080p),
+            _ => Err(()),
+        }
+    }
+}
+
+impl InputLimit {
+    pub const VALID_VALUES: &str = "off, 1440p, 1080p-mobile, 1080p";
+
+    /// The built-in EDID preset slot this selects.
+    pub fn preset(&self) -> CustomEdidPreset {
+        let slot = match self {
+            Self::Off => 0,
+            Self::Slot1440p => 6,
+            Self::Slot1080pMobile => 2,
+            Self::Slot1080p => 1,
+        };
+        CustomEdidPreset(slot)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Audio Input (4K S only)
 // ---------------------------------------------------------------------------
@@ -265,6 +436,7 @@ impl CustomEdidMode {
 /// Discovered via decompilation of EGAVDeviceSupport.dll.
 /// Function: `CCamLinkSupport::SetAudioInputSelection`
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AudioInput {
     /// HDMI embedded audio (default).
     Embedded,
@@ -313,6 +485,7 @@ impl AudioInput {
 /// Discovered via decompilation of EGAVDeviceSupport.dll.
 /// Function: `CCamLinkSupport::SetVideoScalerEnabled`
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VideoScaler {
     On,
     Off,
@@ -356,6 +529,7 @@ impl VideoScaler {
 
 /// USB speed mode (4K X only, AT command 0x8e).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UsbSpeed {
     FiveGbps,
     TenGbps,
@@ -402,6 +576,205 @@ impl UsbSpeed {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Setting bundles
+// ---------------------------------------------------------------------------
+
+/// A named set of settings for a common setup, applied with
+/// [`ElgatoDevice::apply_bundle`](crate::ElgatoDevice::apply_bundle).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Bundle {
+    /// HDR console capture: HDR passed through, auto range, card EDID.
+    ConsoleHdr,
+    /// Desktop PC in SDR: full range, the monitor's EDID, stray HDR tone-mapped.
+    PcSdr,
+    /// Retro consoles through HDMI adapters: limited range, card EDID,
+    /// and the 4K S scaler on for low-resolution signals.
+    Retro,
+}
+
+impl fmt::Display for Bundle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Bundle {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|b| b.name() == s.to_lowercase()).ok_or(())
+    }
+}
+
+impl Bundle {
+    pub const VALID_VALUES: &str = "console-hdr, pc-sdr, retro";
+
+    pub const ALL: [Self; 3] = [Self::ConsoleHdr, Self::PcSdr, Self::Retro];
+
+    /// CLI name, e.g. `console-hdr`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ConsoleHdr => "console-hdr",
+            Self::PcSdr => "pc-sdr",
+            Self::Retro => "retro",
+        }
+    }
+
+    /// One-line description for listings.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::ConsoleHdr => "HDR consoles: tone mapping off, auto range, internal EDID",
+            Self::PcSdr => "Desktop PCs: tone mapping on, full range, display EDID",
+            Self::Retro => "Retro consoles: limited range, internal EDID, scaler on (4K S)",
+        }
+    }
+
+    pub fn hdmi_range(&self) -> EdidRangePolicy {
+        match self {
+            Self::ConsoleHdr => EdidRangePolicy::Auto,
+            Self::PcSdr => EdidRangePolicy::Expand,
+            Self::Retro => EdidRangePolicy::Shrink,
+        }
+    }
+
+    pub fn edid_source(&self) -> EdidSource {
+        match self {
+            Self::ConsoleHdr | Self::Retro => EdidSource::Internal,
+            Self::PcSdr => EdidSource::Display,
+        }
+    }
+
+    pub fn hdr_mapping(&self) -> HdrToneMapping {
+        match self {
+            Self::ConsoleHdr | Self::Retro => HdrToneMapping::Off,
+            Self::PcSdr => HdrToneMapping::On,
+        }
+    }
+
+    /// Video scaler state, or `None` to leave it alone.  Only applied on
+    /// the 4K S.
+    pub fn video_scaler(&self) -> Option<VideoScaler> {
+        match self {
+            Self::Retro => Some(VideoScaler::On),
+            Self::ConsoleHdr | Self::PcSdr => None,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Picture controls (4K X only)
+// ---------------------------------------------------------------------------
+
+/// Standard UVC Processing Unit picture control (4K X only).
+///
+/// Unlike the other settings these are plain UVC 1.1 controls, the same
+/// ones `v4l2-ctl` exposes; the valid range comes from the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PictureControl {
+    Brightness,
+    Contrast,
+    Hue,
+    Saturation,
+}
+
+impl fmt::Display for PictureControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for PictureControl {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "brightness" => Ok(Self::Brightness),
+            "contrast" => Ok(Self::Contrast),
+            "hue" => Ok(Self::Hue),
+            "saturation" => Ok(Self::Saturation),
+            _ => Err(()),
+        }
+    }
+}
+
+impl PictureControl {
+    pub const VALID_VALUES: &str = "brightness, contrast, hue, saturation";
+
+    /// Every picture control, in selector order.
+    pub const ALL: [Self; 4] = [Self::Brightness, Self::Contrast, Self::Hue, Self::Saturation];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Brightness => "Brightness",
+            Self::Contrast => "Contrast",
+            Self::Hue => "Hue",
+            Self::Saturation => "Saturation",
+        }
+    }
+
+    /// PU control selector.
+    pub(crate) fn selector(&self) -> u8 {
+        match self {
+            Self::Brightness => UVC_PU_BRIGHTNESS,
+            Self::Contrast => UVC_PU_CONTRAST,
+            Self::Hue => UVC_PU_HUE,
+            Self::Saturation => UVC_PU_SATURATION,
+        }
+    }
+
+    /// Bit in the Processing Unit's `bmControls` that advertises this control.
+    pub(crate) fn control_bit(&self) -> u32 {
+        match self {
+            Self::Brightness => 0,
+            Self::Contrast => 1,
+            Self::Hue => 2,
+            Self::Saturation => 3,
+        }
+    }
+
+    /// Decode a 2-byte control value; brightness and hue are signed.
+    pub(crate) fn decode(&self, bytes: [u8; 2]) -> i32 {
+        match self {
+            Self::Brightness | Self::Hue => i16::from_le_bytes(bytes) as i32,
+            Self::Contrast | Self::Saturation => u16::from_le_bytes(bytes) as i32,
+        }
+    }
+
+    /// Encode a control value, which must already be within the device range.
+    pub(crate) fn encode(&self, value: i32) -> [u8; 2] {
+        match self {
+            Self::Brightness | Self::Hue => (value as i16).to_le_bytes(),
+            Self::Contrast | Self::Saturation => (value as u16).to_le_bytes(),
+        }
+    }
+}
+
+/// Range of a picture control as reported by the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControlRange {
+    pub min: i32,
+    pub max: i32,
+    /// Step size (GET_RES).
+    pub step: i32,
+    /// Factory default (GET_DEF).
+    pub default: i32,
+}
+
+impl ControlRange {
+    /// Whether `value` lies within `min..=max`.
+    pub fn contains(&self, value: i32) -> bool {
+        (self.min..=self.max).contains(&value)
+    }
+}
+
+impl fmt::Display for ControlRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..={} (step {}, default {})", self.min, self.max, self.step, self.default)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -471,6 +844,67 @@ mod tests {
         assert_eq!(CustomEdidMode::Off.payload_4kx()[0], 0xa1);
     }
 
+    #[test]
+    fn custom_edid_preset_from_str() {
+        assert_eq!("12".parse::<CustomEdidPreset>().map(|p| p.index()), Ok(12));
+        assert!("14".parse::<CustomEdidPreset>().is_err());
+        assert!("x".parse::<CustomEdidPreset>().is_err());
+        let slots: Vec<u8> = CustomEdidPreset::all().map(|p| p.index()).collect();
+        assert_eq!(slots, (0..=13).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn custom_edid_preset_matches_on_off_payloads() {
+        // Slot 0 and slot 1 are exactly the existing off/on payloads
+        assert_eq!(CustomEdidPreset::new(0).unwrap().payload_4kx(), CustomEdidMode::Off.payload_4kx());
+        assert_eq!(CustomEdidPreset::new(1).unwrap().payload_4kx(), CustomEdidMode::On.payload_4kx());
+        let payload = CustomEdidPreset::new(12).unwrap().payload_4kx();
+        assert_eq!(payload[9], 12);
+        let total: u8 = payload.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+        assert_eq!(total, 0);
+    }
+
+    #[test]
+    fn payloads_4kx_match_captures() {
+        // Byte-for-byte from the Windows pcaps the hand-written arrays came from
+        let cases: [(Vec<u8>, &[u8]); 9] = [
+            (EdidRangePolicy::Auto.payload_4kx(), &[0xa1, 0x08, 0x00, 0x00, 0x7c, 0x00, 0x00, 0x00, 0x01, 0x00, 0xda]),
+            (EdidRangePolicy::Expand.payload_4kx(), &[0xa1, 0x08, 0x00, 0x00, 0x7c, 0x00, 0x00, 0x00, 0x01, 0x03, 0xd7]),
+            (EdidRangePolicy::Shrink.payload_4kx(), &[0xa1, 0x08, 0x00, 0x00, 0x7c, 0x00, 0x00, 0x00, 0x01, 0x04, 0xd6]),
+            (EdidSource::Display.payload_4kx(), &[0xa1, 0x0a, 0x00, 0x00, 0x4d, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x07]),
+            (EdidSource::Merged.payload_4kx(), &[0xa1, 0x0a, 0x00, 0x00, 0x4d, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x04]),
+            (EdidSource::Internal.payload_4kx(), &[0xa1, 0x0a, 0x00, 0x00, 0x4d, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08]),
+            (HdrToneMapping::On.payload_4kx(), &[0xa1, 0x07, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00, 0x01, 0x38]),
+            (HdrToneMapping::Off.payload_4kx(), &[0xa1, 0x07, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00, 0x00, 0x39]),
+            (CustomEdidMode::Off.payload_4kx(), &[0xa1, 0x0a, 0x00, 0x00, 0x54, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x81]),
+        ];
+        for (built, captured) in cases {
+            assert_eq!(built, captured);
+        }
+        assert_eq!(
+            CustomEdidMode::On.payload_4kx(),
+            [0xa1, 0x0a, 0x00, 0x00, 0x54, 0x00, 0x00, 0x00, 0x00, 0x01, 0x80, 0x00, 0x80]
+        );
+    }
+
+    #[test]
+    fn input_limit_from_str() {
+        assert_eq!("off".parse(), Ok(InputLimit::Off));
+        assert_eq!("1440P".parse(), Ok(InputLimit::Slot1440p));
+        assert_eq!("1080p-mobile".parse(), Ok(InputLimit::Slot1080pMobile));
+        assert_eq!("1080p".parse(), Ok(InputLimit::Slot1080p));
+        assert!("1080p120".parse::<InputLimit>().is_err());
+        assert!("4k".parse::<InputLimit>().is_err());
+    }
+
+    #[test]
+    fn input_limit_selects_preset_slot() {
+        assert_eq!(InputLimit::Off.preset().payload_4kx(), CustomEdidMode::Off.payload_4kx());
+        assert_eq!(InputLimit::Slot1440p.preset().name(), "1440p");
+        assert_eq!(InputLimit::Slot1080pMobile.preset().name(), "1080p120 for mobile");
+        assert_eq!(InputLimit::Slot1080p.preset().name(), "1080p");
+    }
+
     #[test]
     fn hid_packets_are_correct_size() {
         assert_eq!(EdidRangePolicy::Expand.payload_4ks().len(), HID_PACKET_SIZE);
@@ -529,4 +963,56 @@ mod tests {
         assert_eq!(DeviceModel::Elgato4KX.to_string(), "4K X");
         assert_eq!(DeviceModel::Elgato4KS.to_string(), "4K S");
     }
+
+    #[test]
+    fn picture_control_from_str() {
+        assert_eq!("Brightness".parse(), Ok(PictureControl::Brightness));
+        assert_eq!("saturation".parse(), Ok(PictureControl::Saturation));
+        assert!("gamma".parse::<PictureControl>().is_err());
+    }
+
+    #[test]
+    fn picture_control_value_encoding() {
+        assert_eq!(PictureControl::Brightness.encode(-10), [0xf6, 0xff]);
+        assert_eq!(PictureControl::Brightness.decode([0xf6, 0xff]), -10);
+        assert_eq!(PictureControl::Contrast.decode([0xff, 0xff]), 65535);
+        assert_eq!(PictureControl::Saturation.encode(200), [0xc8, 0x00]);
+    }
+
+    #[test]
+    fn control_range_contains() {
+        let range = ControlRange { min: -64, max: 64, step: 1, default: 0 };
+        assert!(range.contains(-64) && range.contains(64));
+        assert!(!range.contains(65));
+    }
+
+    #[test]
+    fn bundle_from_str_roundtrips_names() {
+        for bundle in Bundle::ALL {
+            assert_eq!(bundle.name().parse(), Ok(bundle));
+        }
+        assert_eq!("Console-HDR".parse(), Ok(Bundle::ConsoleHdr));
+        assert!("console".parse::<Bundle>().is_err());
+        let names: Vec<&str> = Bundle::ALL.iter().map(|b| b.name()).collect();
+        assert_eq!(Bundle::VALID_VALUES, names.join(", "));
+    }
+
+    #[test]
+    fn console_hdr_bundle_settings() {
+        let b = Bundle::ConsoleHdr;
+        assert_eq!(b.hdr_mapping(), HdrToneMapping::Off);
+        assert_eq!(b.hdmi_range(), EdidRangePolicy::Auto);
+        assert_eq!(b.edid_source(), EdidSource::Internal);
+        assert_eq!(b.video_scaler(), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn custom_edid_preset_serde_checks_slot() {
+        let preset = CustomEdidPreset::new(12).unwrap();
+        assert_eq!(serde_json::to_string(&preset).unwrap(), "12");
+        assert_eq!(serde_json::from_str::<CustomEdidPreset>("12").unwrap(), preset);
+        assert!(serde_json::from_str::<CustomEdidPreset>("14").is_err());
+    }
+
 }
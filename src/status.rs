@@ -8,9 +8,13 @@
 //! Discovered by decompiling EGAVDeviceSupport.dll (CCamLinkSupport class).
 
 use std::fmt;
+use std::str::FromStr;
+use std::sync::MutexGuard;
+use std::time::{Duration, Instant};
 
-use crate::device::ElgatoDevice;
+use crate::device::{ElgatoDevice, HardwareRevision};
 use crate::error::ElgatoError;
+use crate::profile::SettingChange;
 use crate::protocol::*;
 use crate::settings::{
     AudioInput, DeviceModel, EdidRangePolicy, EdidSource, HdrToneMapping, VideoScaler,
@@ -23,6 +27,7 @@ use crate::settings::{
 /// A value read from the device that may be a known enum variant or an
 /// unrecognized raw byte.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ReadValue<T> {
     /// A recognized, strongly-typed value.
     Known(T),
@@ -39,8 +44,77 @@ impl<T: fmt::Display> fmt::Display for ReadValue<T> {
     }
 }
 
-/// USB speed mode reported by the device (derived from product ID).
+/// One [`DeviceStatus`] field: a value, or why there isn't one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StatusField<T> {
+    /// This model can't report the field (or its read isn't decoded yet).
+    #[default]
+    NotSupported,
+    /// The read failed, e.g. a USB transfer error or an empty response.
+    ReadFailed(String),
+    /// The value read from the device.
+    Value(T),
+}
+
+impl<T> StatusField<T> {
+    /// The value, if one was read.
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Self::Value(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Convert into the value, discarding why there isn't one.
+    pub fn ok(self) -> Option<T> {
+        match self {
+            Self::Value(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Whether this model reports the field at all.
+    pub fn is_supported(&self) -> bool {
+        !matches!(self, Self::NotSupported)
+    }
+
+    /// The field with its value displayed, so fields of different types
+    /// can be handled alike.
+    fn shown(&self) -> StatusField<String>
+    where
+        T: fmt::Display,
+    {
+        match self {
+            Self::NotSupported => StatusField::NotSupported,
+            Self::ReadFailed(e) => StatusField::ReadFailed(e.clone()),
+            Self::Value(v) => StatusField::Value(v.to_string()),
+        }
+    }
+
+    /// A field read by a reader returning `Ok(None)` for "no usable response".
+    fn from_read(result: Result<Option<T>, ElgatoError>) -> Self {
+        match result {
+            Ok(Some(v)) => Self::Value(v),
+            Ok(None) => Self::ReadFailed("no usable response".to_string()),
+            Err(e) => Self::ReadFailed(e.to_string()),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for StatusField<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotSupported => write!(f, "Not supported"),
+            Self::ReadFailed(e) => write!(f, "Read failed ({})", e),
+            Self::Value(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+/// USB speed of a device, from the product ID (4K X) or the enumerated link.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UsbSpeedStatus {
     /// USB 2.0 High-Speed (480 Mbps).
     Usb2,
@@ -62,6 +136,7 @@ impl fmt::Display for UsbSpeedStatus {
 
 /// Custom EDID preset state as read from the device (4K X only).
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CustomEdidStatus {
     /// Custom EDID is disabled.
     Off,
@@ -81,63 +156,456 @@ impl fmt::Display for CustomEdidStatus {
     }
 }
 
+/// Firmware version, which on both models is the build date `YY.MM.DD`.
+///
+/// Versions compare by date, so callers can gate on a minimum firmware:
+///
+/// ```
+/// use elgato4k_linux::FirmwareVersion;
+///
+/// let minimum: FirmwareVersion = "25.02.10".parse().unwrap();
+/// assert!(FirmwareVersion::new(24, 11, 30) < minimum);
+/// assert_eq!(minimum.to_string(), "25.02.10");
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FirmwareVersion {
+    /// Two-digit year, e.g. 25 for 2025.
+    pub year: u8,
+    pub month: u8,
+    pub day: u8,
+    /// Bytes the version was decoded from (ASCII digits on the 4K X, BCD on
+    /// the 4K S); empty when built by hand.  Ignored by comparisons.
+    pub raw: Vec<u8>,
+}
+
+impl FirmwareVersion {
+    pub fn new(year: u8, month: u8, day: u8) -> Self {
+        Self { year, month, day, raw: Vec::new() }
+    }
+
+    fn date(&self) -> (u8, u8, u8) {
+        (self.year, self.month, self.day)
+    }
+
+    /// Decode the AT command 0x77 response (4K X).
+    ///
+    /// The 133-byte response has header `a1 80 81 00` then ASCII YYMMDD at
+    /// bytes 4–9 (e.g. "250210" = firmware version 25.02.10).
+    fn from_4kx_response(data: &[u8]) -> Option<Self> {
+        let digits = data.get(4..10)?;
+        if !digits.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        let pair = |i: usize| (digits[i] - b'0') * 10 + (digits[i + 1] - b'0');
+        let (year, month, day) = (pair(0), pair(2), pair(4));
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+        Some(Self { year, month, day, raw: digits.to_vec() })
+    }
+
+    /// Decode the HID 0x55/0x02 response (4K S).
+    ///
+    /// The 8-byte response contains the version in bytes 3–5 as DateThreeBytes
+    /// (versionFormat 1): `[YY, MM, DD]` in BCD encoding.
+    fn from_4ks_response(data: &[u8]) -> Option<Self> {
+        let bcd = data.get(3..6)?;
+        let (yy, mm, dd) = (bcd[0], bcd[1], bcd[2]);
+        let valid = is_valid_bcd(yy) && is_valid_bcd(mm) && is_valid_bcd(dd)
+            && (1..=BCD_MAX_MONTH).contains(&mm)
+            && (1..=BCD_MAX_DAY).contains(&dd);
+        let decimal = |b: u8| (b >> 4) * 10 + (b & 0x0f);
+        valid.then(|| Self { year: decimal(yy), month: decimal(mm), day: decimal(dd), raw: bcd.to_vec() })
+    }
+}
+
+impl PartialEq for FirmwareVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.date() == other.date()
+    }
+}
+
+impl Eq for FirmwareVersion {}
+
+impl PartialOrd for FirmwareVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FirmwareVersion {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.date().cmp(&other.date())
+    }
+}
+
+impl fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}.{:02}.{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for FirmwareVersion {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('.').map(|p| p.parse::<u8>().map_err(|_| ()));
+        let (year, month, day) = (parts.next().ok_or(())??, parts.next().ok_or(())??, parts.next().ok_or(())??);
+        if parts.next().is_some() || year > 99 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return Err(());
+        }
+        Ok(Self::new(year, month, day))
+    }
+}
+
+/// A single readable setting, for [`ElgatoDevice::read_setting`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Setting {
+    FirmwareVersion,
+    UsbSpeed,
+    HdmiColorRange,
+    HdrToneMapping,
+    EdidSource,
+    AudioInput,
+    VideoScaler,
+}
+
+impl Setting {
+    pub const VALID_VALUES: &str =
+        "firmware-version, usb-speed, hdmi-range, hdr-map, edid-source, audio-input, video-scaler";
+
+    pub const ALL: [Self; 7] = [
+        Self::FirmwareVersion,
+        Self::UsbSpeed,
+        Self::HdmiColorRange,
+        Self::HdrToneMapping,
+        Self::EdidSource,
+        Self::AudioInput,
+        Self::VideoScaler,
+    ];
+
+    /// Name matching the CLI flag that sets it, e.g. `hdr-map`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::FirmwareVersion => "firmware-version",
+            Self::UsbSpeed => "usb-speed",
+            Self::HdmiColorRange => "hdmi-range",
+            Self::HdrToneMapping => "hdr-map",
+            Self::EdidSource => "edid-source",
+            Self::AudioInput => "audio-input",
+            Self::VideoScaler => "video-scaler",
+        }
+    }
+}
+
+impl fmt::Display for Setting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Setting {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+        Self::ALL.into_iter().find(|setting| setting.name() == s).ok_or(())
+    }
+}
+
+/// The value of one [`Setting`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SettingValue {
+    FirmwareVersion(FirmwareVersion),
+    UsbSpeed(ReadValue<UsbSpeedStatus>),
+    HdmiColorRange(ReadValue<EdidRangePolicy>),
+    HdrToneMapping(ReadValue<HdrToneMapping>),
+    EdidSource(ReadValue<EdidSource>),
+    AudioInput(ReadValue<AudioInput>),
+    VideoScaler(ReadValue<VideoScaler>),
+}
+
+impl fmt::Display for SettingValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FirmwareVersion(v) => write!(f, "{}", v),
+            Self::UsbSpeed(v) => write!(f, "{}", v),
+            Self::HdmiColorRange(v) => write!(f, "{}", v),
+            Self::HdrToneMapping(v) => write!(f, "{}", v),
+            Self::EdidSource(v) => write!(f, "{}", v),
+            Self::AudioInput(v) => write!(f, "{}", v),
+            Self::VideoScaler(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl SettingValue {
+    /// The value as the CLI spells it, e.g. `on`, `expand`, `10g`, or
+    /// `25.02.10` for the firmware version.  `None` for a byte the device
+    /// sent that isn't recognized.
+    pub fn token(&self) -> Option<String> {
+        fn known<T: Copy>(value: &ReadValue<T>, change: impl Fn(T) -> SettingChange) -> Option<String> {
+            match value {
+                ReadValue::Known(v) => Some(change(*v).value()),
+                ReadValue::Unknown(_) => None,
+            }
+        }
+        match self {
+            Self::FirmwareVersion(v) => Some(v.to_string()),
+            Self::UsbSpeed(ReadValue::Known(speed)) => Some(
+                match speed {
+                    UsbSpeedStatus::Usb2 => "usb2",
+                    UsbSpeedStatus::FiveGbps => "5g",
+                    UsbSpeedStatus::TenGbps => "10g",
+                }
+                .into(),
+            ),
+            Self::UsbSpeed(ReadValue::Unknown(_)) => None,
+            Self::HdmiColorRange(v) => known(v, SettingChange::HdmiRange),
+            Self::HdrToneMapping(v) => known(v, SettingChange::HdrMapping),
+            Self::EdidSource(v) => known(v, SettingChange::EdidSource),
+            Self::AudioInput(v) => known(v, SettingChange::AudioInput),
+            Self::VideoScaler(v) => known(v, SettingChange::VideoScaler),
+        }
+    }
+}
+
 /// All readable settings from a device.
 ///
-/// Fields are `None` when a setting is not applicable to the device model
-/// (e.g. `audio_input` is only available on the 4K S) or when the device
-/// returned an unexpected/unreadable response.
+/// Each device-read field is a [`StatusField`]: `NotSupported` when the
+/// setting isn't readable on the model (e.g. `audio_input` is only available
+/// on the 4K S), `ReadFailed` when the read went wrong, so a flaky cable
+/// doesn't look like a missing feature.
 ///
 /// **4K X:** Firmware version, USB speed, HDMI color range, and HDR tone
 /// mapping are readable. EDID source, custom EDID, audio input, and video
 /// scaler are not readable.
-#[derive(Debug, Clone)]
+///
+/// With the `serde` feature, fields missing from the input deserialize to
+/// their defaults, so snapshots written by older versions still load.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct DeviceStatus {
-    /// Firmware version string (e.g. "25.02.10").
-    pub firmware_version: String,
-    /// USB speed mode (4K X only).
-    pub usb_speed: Option<ReadValue<UsbSpeedStatus>>,
+    /// Firmware version.  `ReadFailed` also covers a device reporting no
+    /// version or an undecodable one.
+    pub firmware_version: StatusField<FirmwareVersion>,
+    /// USB product ID, which also encodes the 4K X speed mode.
+    pub pid: u16,
+    /// Hardware revision from the USB `bcdDevice` field.
+    pub revision: HardwareRevision,
+    /// Whether the device enumerated in USB 2.0 fallback mode (009d/00ae).
+    pub degraded: bool,
+    /// USB speed mode (4K X from the product ID; 4K S from the enumerated
+    /// link, as it has no speed setting).
+    pub usb_speed: StatusField<ReadValue<UsbSpeedStatus>>,
+    /// Speed the USB link actually negotiated (both models), as reported by
+    /// the host rather than read from the device; `None` if it doesn't know.
+    pub link_speed: Option<UsbSpeedStatus>,
     /// HDMI color range (4K X via AT cmd 0x91 family 0x07; 4K S via HID).
-    pub hdmi_color_range: Option<ReadValue<EdidRangePolicy>>,
+    pub hdmi_color_range: StatusField<ReadValue<EdidRangePolicy>>,
     /// HDR tone mapping (4K X via AT cmd 0x90; 4K S via HID).
-    pub hdr_tone_mapping: Option<ReadValue<HdrToneMapping>>,
+    pub hdr_tone_mapping: StatusField<ReadValue<HdrToneMapping>>,
     /// EDID source selection (4K S only; not readable on 4K X).
-    pub edid_source: Option<ReadValue<EdidSource>>,
+    pub edid_source: StatusField<ReadValue<EdidSource>>,
     /// Custom EDID preset state (not currently readable).
-    pub custom_edid: Option<CustomEdidStatus>,
+    pub custom_edid: StatusField<CustomEdidStatus>,
     /// Audio input source (4K S only).
-    pub audio_input: Option<ReadValue<AudioInput>>,
+    pub audio_input: StatusField<ReadValue<AudioInput>>,
     /// Video scaler state (4K S only).
-    pub video_scaler: Option<ReadValue<VideoScaler>>,
+    pub video_scaler: StatusField<ReadValue<VideoScaler>>,
+}
+
+impl DeviceStatus {
+    /// Version of the serialized layout, written as `schema_version` by
+    /// [`StatusSnapshot`].  New fields are added without a bump; it only
+    /// changes when a field is removed, renamed, or changes meaning.
+    pub const SCHEMA_VERSION: u32 = 2;
+
+    /// Whether the negotiated link is slower than the configured USB mode,
+    /// e.g. a 4K X in 10Gbps mode plugged into a 5Gbps port.  The USB 2.0
+    /// fallback case is reported by [`degraded`](Self::degraded) instead.
+    pub fn link_mismatch(&self) -> bool {
+        matches!(
+            (&self.usb_speed, self.link_speed),
+            (StatusField::Value(ReadValue::Known(mode)), Some(link)) if *mode != link && !self.degraded
+        )
+    }
 }
 
 impl fmt::Display for DeviceStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "Firmware version: {}", self.firmware_version)?;
-        if let Some(v) = &self.usb_speed {
-            writeln!(f, "USB speed: {}", v)?;
-        }
-        if let Some(v) = &self.hdmi_color_range {
-            writeln!(f, "HDMI color range: {}", v)?;
-        }
-        if let Some(v) = &self.hdr_tone_mapping {
-            writeln!(f, "HDR tone mapping: {}", v)?;
-        }
-        if let Some(v) = &self.edid_source {
-            writeln!(f, "EDID source: {}", v)?;
+        /// One `label: value` line, skipped for fields the model doesn't report.
+        fn line<T: fmt::Display>(f: &mut fmt::Formatter<'_>, label: &str, field: &StatusField<T>) -> fmt::Result {
+            if field.is_supported() {
+                writeln!(f, "{}: {}", label, field)?;
+            }
+            Ok(())
         }
-        if let Some(v) = &self.custom_edid {
-            writeln!(f, "Custom EDID: {}", v)?;
+
+        line(f, "Firmware version", &self.firmware_version)?;
+        writeln!(f, "Hardware: {:04x}:{:04x}, revision {}", VENDOR_ID, self.pid, self.revision)?;
+        if self.degraded {
+            writeln!(f, "Link: USB 2.0 fallback (degraded) — check the cable and use a USB 3 port")?;
         }
-        if let Some(v) = &self.audio_input {
-            writeln!(f, "Audio input: {}", v)?;
+        line(f, "USB speed", &self.usb_speed)?;
+        if let Some(v) = &self.link_speed {
+            writeln!(f, "USB link: {}", v)?;
         }
-        if let Some(v) = &self.video_scaler {
-            writeln!(f, "Video scaler: {}", v)?;
+        if let (true, StatusField::Value(ReadValue::Known(mode)), Some(link)) = (self.link_mismatch(), &self.usb_speed, self.link_speed) {
+            writeln!(f, "WARNING: configured for {} but the link is {} — check the cable and port", mode, link)?;
         }
+        line(f, "HDMI color range", &self.hdmi_color_range)?;
+        line(f, "HDR tone mapping", &self.hdr_tone_mapping)?;
+        line(f, "EDID source", &self.edid_source)?;
+        line(f, "Custom EDID", &self.custom_edid)?;
+        line(f, "Audio input", &self.audio_input)?;
+        line(f, "Video scaler", &self.video_scaler)?;
         Ok(())
     }
 }
 
+/// One field that differs between two [`DeviceStatus`] snapshots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StatusChange {
+    /// Field label as shown by `--status`, e.g. `HDR tone mapping`.
+    pub field: &'static str,
+    /// Value in the earlier snapshot, `None` if it wasn't read.
+    pub before: Option<String>,
+    /// Value in the later snapshot, `None` if it wasn't read.
+    pub after: Option<String>,
+}
+
+impl fmt::Display for StatusChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |v: &Option<String>| v.clone().unwrap_or_else(|| "(not read)".to_string());
+        write!(f, "{}: {} -> {}", self.field, show(&self.before), show(&self.after))
+    }
+}
+
+impl DeviceStatus {
+    /// Every field as a (label, displayed value) pair, for [`diff`](Self::diff).
+    fn fields(&self) -> [(&'static str, Option<String>); 12] {
+        self.labelled(false)
+    }
+
+    /// Every field as a (label, value) row, with the value as `--status`
+    /// shows it (`Read failed (...)` included), or `None` for fields the
+    /// model can't report.  For tables with one column per card.
+    pub fn rows(&self) -> [(&'static str, Option<String>); 12] {
+        self.labelled(true)
+    }
+
+    /// [`fields`](Self::fields), or with `failures`, [`rows`](Self::rows).
+    fn labelled(&self, failures: bool) -> [(&'static str, Option<String>); 12] {
+        let show = |field: &StatusField<String>| match field {
+            StatusField::Value(v) => Some(v.clone()),
+            StatusField::ReadFailed(_) if failures => Some(field.to_string()),
+            _ => None,
+        };
+        [
+            ("Firmware version", show(&self.firmware_version.shown())),
+            ("Product ID", Some(format!("{:04x}", self.pid))),
+            ("Hardware revision", Some(self.revision.to_string())),
+            ("USB 2.0 fallback", Some(if self.degraded { "Yes" } else { "No" }.to_string())),
+            ("USB speed", show(&self.usb_speed.shown())),
+            ("USB link", self.link_speed.map(|v| v.to_string())),
+            ("HDMI color range", show(&self.hdmi_color_range.shown())),
+            ("HDR tone mapping", show(&self.hdr_tone_mapping.shown())),
+            ("EDID source", show(&self.edid_source.shown())),
+            ("Custom EDID", show(&self.custom_edid.shown())),
+            ("Audio input", show(&self.audio_input.shown())),
+            ("Video scaler", show(&self.video_scaler.shown())),
+        ]
+    }
+
+    /// Fields whose value changed from `self` (the earlier snapshot) to `later`.
+    ///
+    /// Pair with the `serde` feature to keep a snapshot on disk, e.g. before
+    /// experimenting with settings.
+    pub fn diff(&self, later: &DeviceStatus) -> Vec<StatusChange> {
+        self.fields()
+            .into_iter()
+            .zip(later.fields())
+            .filter(|((_, before), (_, after))| before != after)
+            .map(|((field, before), (_, after))| StatusChange { field, before, after })
+            .collect()
+    }
+}
+
+/// A [`DeviceStatus`] tagged with its schema version, as written by
+/// `--status --json` and `--status --save`.
+///
+/// ```
+/// use elgato4k_linux::{DeviceStatus, StatusSnapshot};
+///
+/// let json = r#"{"schema_version":2,"pid":156,"degraded":false}"#;
+/// let snapshot: StatusSnapshot = serde_json::from_str(json)?;
+/// assert!(snapshot.is_supported());
+/// assert_eq!(snapshot.status.pid, 0x009c);
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StatusSnapshot {
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub status: DeviceStatus,
+}
+
+#[cfg(feature = "serde")]
+impl StatusSnapshot {
+    /// Tag `status` with the current [`DeviceStatus::SCHEMA_VERSION`].
+    pub fn new(status: DeviceStatus) -> Self {
+        Self { schema_version: DeviceStatus::SCHEMA_VERSION, status }
+    }
+
+    /// Whether this library understands the snapshot's schema, i.e. it
+    /// was written with the current [`DeviceStatus::SCHEMA_VERSION`].
+    pub fn is_supported(&self) -> bool {
+        self.schema_version == DeviceStatus::SCHEMA_VERSION
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Status cache
+// ---------------------------------------------------------------------------
+
+/// Last [`DeviceStatus`] read, kept for [`ElgatoDevice::set_status_cache_ttl`].
+#[derive(Debug, Default)]
+pub(crate) struct StatusCache {
+    ttl: Option<Duration>,
+    entry: Option<(Instant, DeviceStatus)>,
+}
+
+impl StatusCache {
+    /// The cached status, if caching is on and it is younger than the TTL.
+    fn get(&self, now: Instant) -> Option<DeviceStatus> {
+        let ttl = self.ttl?;
+        self.entry
+            .as_ref()
+            .filter(|(read_at, _)| now.saturating_duration_since(*read_at) < ttl)
+            .map(|(_, status)| status.clone())
+    }
+
+    fn store(&mut self, now: Instant, status: &DeviceStatus) {
+        if self.ttl.is_some() {
+            self.entry = Some((now, status.clone()));
+        }
+    }
+
+    fn set_ttl(&mut self, ttl: Option<Duration>) {
+        self.ttl = ttl;
+        self.entry = None;
+    }
+
+    pub(crate) fn invalidate(&mut self) {
+        self.entry = None;
+    }
+}
+
 // ---------------------------------------------------------------------------
 // BCD validation
 // ---------------------------------------------------------------------------
@@ -198,6 +666,15 @@ fn decode_video_scaler(v: u8) -> ReadValue<VideoScaler> {
     }
 }
 
+/// USB speed of a 4K S: USB 2.0 when on the fallback PID, else the link speed.
+fn decode_usb_speed_4ks(degraded: bool, link: Option<UsbSpeedStatus>) -> Option<ReadValue<UsbSpeedStatus>> {
+    if degraded {
+        Some(ReadValue::Known(UsbSpeedStatus::Usb2))
+    } else {
+        link.map(ReadValue::Known)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // ElgatoDevice status methods
 // ---------------------------------------------------------------------------
@@ -208,92 +685,101 @@ impl ElgatoDevice {
     /// Read all available settings from the device.
     ///
     /// Returns a [`DeviceStatus`] struct with all readable fields populated.
-    /// Fields that are not applicable to the device model are set to `None`.
+    /// Fields the device model can't report are [`StatusField::NotSupported`];
+    /// a field whose read failed is [`StatusField::ReadFailed`] with the
+    /// reason, and doesn't fail the whole status.
+    ///
+    /// With a cache TTL set (see [`set_status_cache_ttl`](Self::set_status_cache_ttl)),
+    /// a status younger than the TTL is returned without touching the device.
     pub fn read_status(&self) -> Result<DeviceStatus, ElgatoError> {
-        match self.model {
+        if let Some(status) = self.status_cache().get(Instant::now()) {
+            return Ok(status);
+        }
+        let status = match self.model {
             DeviceModel::Elgato4KX => self.read_status_4kx(),
             DeviceModel::Elgato4KS => self.read_status_4ks(),
-        }
+        }?;
+        self.status_cache().store(Instant::now(), &status);
+        Ok(status)
     }
 
-    /// Read the firmware version as a string.
+    /// Read one setting without paying for a full [`read_status`](Self::read_status).
     ///
-    /// - **4K X:** AT command 0x77 via `a1 06` family probe. Response is 133 bytes
-    ///   with ASCII version string at bytes 4–9 (e.g. "250210" = 25.02.10).
-    /// - **4K S:** HID read command 0x55/0x02 (BCD DateThreeBytes).
-    pub fn read_firmware_version(&self) -> Result<String, ElgatoError> {
-        match self.model {
-            DeviceModel::Elgato4KX => {
-                let data = self.read_at_command(UVC_SUBCMD_FIRMWARE_VERSION)?;
-                if data.len() >= 10 {
-                    Ok(Self::format_firmware_version_4kx(&data))
-                } else {
-                    Ok(format!("Unexpected response ({} bytes): {:02x?}", data.len(), data))
-                }
-            }
-            DeviceModel::Elgato4KS => {
-                let data = self.read_hid_data(HID_READ_CMD, SUBCMD_FIRMWARE_VERSION, 8)?;
-                if data.len() >= 5 {
-                    Ok(Self::format_firmware_version_4ks(&data))
-                } else {
-                    Ok(format!("Unexpected response ({} bytes): {:02x?}", data.len(), data))
-                }
+    /// Returns `Ok(None)` when the setting isn't readable on this model
+    /// (see [`DeviceStatus`] for which are) or the 4K S gave no answer.  A
+    /// failed transfer is an error, not `None`.
+    pub fn read_setting(&self, setting: Setting) -> Result<Option<SettingValue>, ElgatoError> {
+        Ok(match setting {
+            Setting::FirmwareVersion => self.read_firmware_version()?.map(SettingValue::FirmwareVersion),
+            Setting::UsbSpeed => match self.model {
+                DeviceModel::Elgato4KX => self.read_usb_speed_4kx().ok().map(SettingValue::UsbSpeed),
+                DeviceModel::Elgato4KS => self.read_usb_speed_4ks().ok().map(SettingValue::UsbSpeed),
+            },
+            Setting::HdmiColorRange => self.current_hdmi_range()?.map(SettingValue::HdmiColorRange),
+            Setting::HdrToneMapping => self.current_hdr_mapping()?.map(SettingValue::HdrToneMapping),
+            Setting::EdidSource => self.current_edid_source()?.map(SettingValue::EdidSource),
+            Setting::AudioInput => self.current_audio_input()?.map(SettingValue::AudioInput),
+            Setting::VideoScaler => self.current_video_scaler()?.map(SettingValue::VideoScaler),
+        })
+    }
+
+    /// Whether this model reports `setting`, so that
+    /// [`read_setting`](Self::read_setting) returning `Ok(None)` for it means
+    /// the device gave no answer rather than that there is nothing to read.
+    pub(crate) fn can_read(&self, setting: Setting) -> bool {
+        match (setting, self.model) {
+            (Setting::FirmwareVersion, _) => true,
+            (Setting::UsbSpeed, DeviceModel::Elgato4KX) => self.read_usb_speed_4kx().is_supported(),
+            (Setting::UsbSpeed, DeviceModel::Elgato4KS) => self.read_usb_speed_4ks().is_supported(),
+            (Setting::HdmiColorRange | Setting::HdrToneMapping, _) => true,
+            (Setting::EdidSource | Setting::AudioInput | Setting::VideoScaler, model) => {
+                model == DeviceModel::Elgato4KS
             }
         }
     }
 
-    // --- Internal: firmware version formatting ---
-
-    /// Format firmware version from AT command 0x77 response (4K X).
+    /// Cache [`read_status`](Self::read_status) results for `ttl`, or turn
+    /// caching off with `None` (the default).
     ///
-    /// The 133-byte response has header `a1 80 81 00` then ASCII YYMMDD at
-    /// bytes 4–9 (e.g. "250210" = firmware version 25.02.10).
-    fn format_firmware_version_4kx(data: &[u8]) -> String {
-        // Extract ASCII version string starting at byte 4
-        let version_bytes = &data[4..];
-        // Find end of ASCII digits
-        let end = version_bytes.iter().position(|&b| b == 0 || !b.is_ascii_digit()).unwrap_or(version_bytes.len());
-        let version_str = std::str::from_utf8(&version_bytes[..end]).unwrap_or("");
-
-        if version_str.is_empty() || version_str == "0" {
-            return format!("Unknown (raw: {:02x?})", &data[..std::cmp::min(16, data.len())]);
-        }
-
-        // Parse YYMMDD
-        if let Ok(version) = version_str.parse::<u32>() {
-            let yy = version / 10000;
-            let mm = (version / 100) % 100;
-            let dd = version % 100;
+    /// A full status read takes a dozen control transfers, so UIs polling
+    /// often should set this.  Writes through this handle drop the cache;
+    /// changes made elsewhere (another process, the device itself) show up
+    /// once the TTL runs out or after [`invalidate_status_cache`](Self::invalidate_status_cache).
+    pub fn set_status_cache_ttl(&self, ttl: Option<Duration>) {
+        self.status_cache().set_ttl(ttl);
+    }
 
-            if (1..=12).contains(&mm) && (1..=31).contains(&dd) {
-                return format!("{:02}.{:02}.{:02}", yy, mm, dd);
-            }
-        }
+    /// Drop any cached status so the next [`read_status`](Self::read_status)
+    /// goes to the device.
+    pub fn invalidate_status_cache(&self) {
+        self.status_cache().invalidate();
+    }
 
-        format!("Raw: {}", version_str)
+    /// Lock the status cache.  A panic while holding it can't leave the
+    /// cache inconsistent, so poisoning is ignored.
+    pub(crate) fn status_cache(&self) -> MutexGuard<'_, StatusCache> {
+        self.status_cache.lock().unwrap_or_else(|e| e.into_inner())
     }
 
-    /// Format firmware version from HID response (4K S).
+    /// Read the firmware version.
     ///
-    /// The 8-byte response contains the version in bytes 3–5 as DateThreeBytes
-    /// (versionFormat 1): `[YY, MM, DD]` in BCD encoding.
-    fn format_firmware_version_4ks(data: &[u8]) -> String {
-        let yy = data[3];
-        let mm = data[4];
-        let dd = data[5];
-
-        if yy == 0 && mm == 0 && dd == 0 {
-            return "Unknown (no version reported)".to_string();
-        }
-
-        if is_valid_bcd(yy) && is_valid_bcd(mm) && is_valid_bcd(dd)
-            && (1..=BCD_MAX_MONTH).contains(&mm)
-            && (1..=BCD_MAX_DAY).contains(&dd)
-        {
-            format!("{:02x}.{:02x}.{:02x}", yy, mm, dd)
-        } else {
-            format!("Raw: {:02x?}", &data[..std::cmp::min(8, data.len())])
-        }
+    /// - **4K X:** AT command 0x77 via `a1 06` family probe. Response is 133 bytes
+    ///   with ASCII version string at bytes 4–9 (e.g. "250210" = 25.02.10).
+    /// - **4K S:** HID read command 0x55/0x02 (BCD DateThreeBytes).
+    ///
+    /// Returns `Ok(None)` if the device reported no version (all zeros) or one
+    /// that doesn't decode as a date.
+    pub fn read_firmware_version(&self) -> Result<Option<FirmwareVersion>, ElgatoError> {
+        Ok(match self.model {
+            DeviceModel::Elgato4KX => {
+                let data = self.read_at_command(UVC_SUBCMD_FIRMWARE_VERSION)?;
+                FirmwareVersion::from_4kx_response(&data)
+            }
+            DeviceModel::Elgato4KS => {
+                let data = self.read_hid_data(HID_READ_CMD, SUBCMD_FIRMWARE_VERSION, 8)?;
+                FirmwareVersion::from_4ks_response(&data)
+            }
+        })
     }
 
     // --- Internal: generic typed readers ---
@@ -313,19 +799,29 @@ impl ElgatoDevice {
 
     // --- Internal: 4K S status reading ---
 
+    /// Determine the 4K S USB speed.
+    ///
+    /// No HID command reporting it is known, so this uses the 00ae USB 2.0
+    /// fallback PID and otherwise the speed the link enumerated at.
+    fn read_usb_speed_4ks(&self) -> StatusField<ReadValue<UsbSpeedStatus>> {
+        decode_usb_speed_4ks(self.is_degraded(), self.link_speed).map_or(StatusField::NotSupported, StatusField::Value)
+    }
+
     /// Read all 4K S settings into a DeviceStatus.
     fn read_status_4ks(&self) -> Result<DeviceStatus, ElgatoError> {
-        let firmware_version = self.read_firmware_version()?;
-
         Ok(DeviceStatus {
-            firmware_version,
-            usb_speed: None,
-            hdr_tone_mapping: self.read_hid_typed(SUBCMD_HDR_TONEMAPPING, decode_hdr)?,
-            hdmi_color_range: self.read_hid_typed(SUBCMD_COLOR_RANGE, decode_color_range)?,
-            edid_source: self.read_hid_typed(SUBCMD_EDID_MODE, decode_edid_mode)?,
-            custom_edid: None,
-            audio_input: self.read_hid_typed(SUBCMD_AUDIO_INPUT, decode_audio_input)?,
-            video_scaler: self.read_hid_typed(SUBCMD_VIDEO_SCALER, decode_video_scaler)?,
+            firmware_version: StatusField::from_read(self.read_firmware_version()),
+            pid: self.pid,
+            revision: self.revision,
+            degraded: self.is_degraded(),
+            usb_speed: self.read_usb_speed_4ks(),
+            link_speed: self.link_speed,
+            hdr_tone_mapping: StatusField::from_read(self.read_hid_typed(SUBCMD_HDR_TONEMAPPING, decode_hdr)),
+            hdmi_color_range: StatusField::from_read(self.read_hid_typed(SUBCMD_COLOR_RANGE, decode_color_range)),
+            edid_source: StatusField::from_read(self.read_hid_typed(SUBCMD_EDID_MODE, decode_edid_mode)),
+            custom_edid: StatusField::NotSupported,
+            audio_input: StatusField::from_read(self.read_hid_typed(SUBCMD_AUDIO_INPUT, decode_audio_input)),
+            video_scaler: StatusField::from_read(self.read_hid_typed(SUBCMD_VIDEO_SCALER, decode_video_scaler)),
         })
     }
 
@@ -337,12 +833,12 @@ impl ElgatoDevice {
     /// - 0x009b = 10 Gbps (SuperSpeed+)
     /// - 0x009c = 5 Gbps (SuperSpeed)
     /// - 0x009d = USB 2.0
-    fn read_usb_speed_4kx(&self) -> Option<ReadValue<UsbSpeedStatus>> {
-        Some(match self.pid {
+    fn read_usb_speed_4kx(&self) -> StatusField<ReadValue<UsbSpeedStatus>> {
+        StatusField::Value(match self.pid {
             0x009b => ReadValue::Known(UsbSpeedStatus::TenGbps),
             0x009c => ReadValue::Known(UsbSpeedStatus::FiveGbps),
             0x009d => ReadValue::Known(UsbSpeedStatus::Usb2),
-            _ => return None,
+            _ => return StatusField::NotSupported,
         })
     }
 
@@ -351,50 +847,110 @@ impl ElgatoDevice {
     /// Uses the `a1 07` family (10-byte probe with param byte 0x01).
     /// Response byte[4] mirrors the `0x7c` write byte[9]:
     /// 0x00=Auto, 0x03=Expand, 0x04=Shrink.
-    fn read_color_range_4kx(&self) -> Option<ReadValue<EdidRangePolicy>> {
-        match self.read_at_command_family07(UVC_SUBCMD_EDID_RANGE_READ, 0x01) {
-            Ok(data) if data.len() > 4 => {
-                Some(match data[4] {
-                    0x00 => ReadValue::Known(EdidRangePolicy::Auto),
-                    0x03 => ReadValue::Known(EdidRangePolicy::Expand),
-                    0x04 => ReadValue::Known(EdidRangePolicy::Shrink),
-                    v => ReadValue::Unknown(v),
-                })
-            }
-            _ => None,
-        }
+    ///
+    /// A failed or short read is an error.
+    fn read_color_range_4kx(&self) -> Result<ReadValue<EdidRangePolicy>, ElgatoError> {
+        let data = self.read_at_command_family07(UVC_SUBCMD_EDID_RANGE_READ, 0x01)?;
+        let value = *data.get(4).ok_or_else(|| short_response("color range", data.len()))?;
+        Ok(match value {
+            0x00 => ReadValue::Known(EdidRangePolicy::Auto),
+            0x03 => ReadValue::Known(EdidRangePolicy::Expand),
+            0x04 => ReadValue::Known(EdidRangePolicy::Shrink),
+            v => ReadValue::Unknown(v),
+        })
     }
 
     /// Read HDR tone mapping state from the 4K X via AT command 0x90.
     ///
     /// Standard `a1 06` family probe. Response byte[4]: 0x01=On, 0x00=Off.
-    fn read_hdr_4kx(&self) -> Option<ReadValue<HdrToneMapping>> {
-        match self.read_at_command(UVC_SUBCMD_HDR_READ) {
-            Ok(data) if data.len() > 4 => Some(decode_hdr(data[4])),
-            _ => None,
-        }
+    ///
+    /// A failed or short read is an error.
+    fn read_hdr_4kx(&self) -> Result<ReadValue<HdrToneMapping>, ElgatoError> {
+        let data = self.read_at_command(UVC_SUBCMD_HDR_READ)?;
+        let value = *data.get(4).ok_or_else(|| short_response("HDR", data.len()))?;
+        Ok(decode_hdr(value))
     }
 
     /// Read all 4K X settings into a DeviceStatus.
     fn read_status_4kx(&self) -> Result<DeviceStatus, ElgatoError> {
-        let firmware_version = self.read_firmware_version()?;
+        let firmware_version = StatusField::from_read(self.read_firmware_version());
         let usb_speed = self.read_usb_speed_4kx();
-        let hdmi_color_range = self.read_color_range_4kx();
-        let hdr_tone_mapping = self.read_hdr_4kx();
+        let hdmi_color_range = StatusField::from_read(self.read_color_range_4kx().map(Some));
+        let hdr_tone_mapping = StatusField::from_read(self.read_hdr_4kx().map(Some));
 
         Ok(DeviceStatus {
             firmware_version,
+            pid: self.pid,
+            revision: self.revision,
+            degraded: self.is_degraded(),
             usb_speed,
+            link_speed: self.link_speed,
             hdmi_color_range,
             hdr_tone_mapping,
-            edid_source: None,
-            custom_edid: None,
-            audio_input: None,
-            video_scaler: None,
+            edid_source: StatusField::NotSupported,
+            custom_edid: StatusField::NotSupported,
+            audio_input: StatusField::NotSupported,
+            video_scaler: StatusField::NotSupported,
         })
     }
 }
 
+// ---------------------------------------------------------------------------
+// Current-value reads for the `set_*_if_changed` setters
+// ---------------------------------------------------------------------------
+
+/// The error for a 4K X read response too short to hold the value byte.
+fn short_response(what: &str, len: usize) -> ElgatoError {
+    ElgatoError::UvcTransfer(format!("{} read returned {} bytes", what, len))
+}
+
+/// Whether a setting needs writing: anything but a confirmed match does.
+pub(crate) fn needs_write<T: PartialEq>(current: &Option<ReadValue<T>>, wanted: &T) -> bool {
+    !matches!(current, Some(ReadValue::Known(v)) if v == wanted)
+}
+
+impl ElgatoDevice {
+    /// Current HDMI color range, if this device can report it.
+    pub(crate) fn current_hdmi_range(&self) -> Result<Option<ReadValue<EdidRangePolicy>>, ElgatoError> {
+        match self.model {
+            DeviceModel::Elgato4KX => self.read_color_range_4kx().map(Some),
+            DeviceModel::Elgato4KS => self.read_hid_typed(SUBCMD_COLOR_RANGE, decode_color_range),
+        }
+    }
+
+    /// Current HDR tone mapping state, if this device can report it.
+    pub(crate) fn current_hdr_mapping(&self) -> Result<Option<ReadValue<HdrToneMapping>>, ElgatoError> {
+        match self.model {
+            DeviceModel::Elgato4KX => self.read_hdr_4kx().map(Some),
+            DeviceModel::Elgato4KS => self.read_hid_typed(SUBCMD_HDR_TONEMAPPING, decode_hdr),
+        }
+    }
+
+    /// Current EDID source (4K S only; not readable on the 4K X).
+    pub(crate) fn current_edid_source(&self) -> Result<Option<ReadValue<EdidSource>>, ElgatoError> {
+        match self.model {
+            DeviceModel::Elgato4KX => Ok(None),
+            DeviceModel::Elgato4KS => self.read_hid_typed(SUBCMD_EDID_MODE, decode_edid_mode),
+        }
+    }
+
+    /// Current audio input (4K S only).
+    pub(crate) fn current_audio_input(&self) -> Result<Option<ReadValue<AudioInput>>, ElgatoError> {
+        match self.model {
+            DeviceModel::Elgato4KX => Ok(None),
+            DeviceModel::Elgato4KS => self.read_hid_typed(SUBCMD_AUDIO_INPUT, decode_audio_input),
+        }
+    }
+
+    /// Current video scaler state (4K S only).
+    pub(crate) fn current_video_scaler(&self) -> Result<Option<ReadValue<VideoScaler>>, ElgatoError> {
+        match self.model {
+            DeviceModel::Elgato4KX => Ok(None),
+            DeviceModel::Elgato4KS => self.read_hid_typed(SUBCMD_VIDEO_SCALER, decode_video_scaler),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -403,6 +959,24 @@ impl ElgatoDevice {
 mod tests {
     use super::*;
 
+    /// A 4K X status for tests to start from with struct-update syntax.
+    fn sample_status() -> DeviceStatus {
+        DeviceStatus {
+            firmware_version: StatusField::Value(FirmwareVersion::new(25, 2, 10)),
+            pid: 0x009b,
+            revision: HardwareRevision { bcd_device: 0x0100 },
+            degraded: false,
+            usb_speed: StatusField::NotSupported,
+            link_speed: None,
+            hdmi_color_range: StatusField::NotSupported,
+            hdr_tone_mapping: StatusField::Value(ReadValue::Known(HdrToneMapping::On)),
+            edid_source: StatusField::NotSupported,
+            custom_edid: StatusField::NotSupported,
+            audio_input: StatusField::NotSupported,
+            video_scaler: StatusField::NotSupported,
+        }
+    }
+
     // --- HID decode tests ---
 
     #[test]
@@ -443,6 +1017,15 @@ mod tests {
         assert_eq!(decode_video_scaler(0x02), ReadValue::Unknown(0x02));
     }
 
+    #[test]
+    fn decode_usb_speed_4ks_values() {
+        let five = Some(UsbSpeedStatus::FiveGbps);
+        assert_eq!(decode_usb_speed_4ks(false, five), Some(ReadValue::Known(UsbSpeedStatus::FiveGbps)));
+        // The fallback PID wins even if the link speed is unknown
+        assert_eq!(decode_usb_speed_4ks(true, None), Some(ReadValue::Known(UsbSpeedStatus::Usb2)));
+        assert_eq!(decode_usb_speed_4ks(false, None), None);
+    }
+
     // --- Firmware version tests ---
 
     #[test]
@@ -451,46 +1034,69 @@ mod tests {
         let mut data = vec![0xa1, 0x80, 0x81, 0x00];
         data.extend_from_slice(b"250210");
         data.resize(133, 0x00);
-        let result = ElgatoDevice::format_firmware_version_4kx(&data);
-        assert_eq!(result, "25.02.10");
+        let version = FirmwareVersion::from_4kx_response(&data).unwrap();
+        assert_eq!(version, FirmwareVersion::new(25, 2, 10));
+        assert_eq!(version.to_string(), "25.02.10");
+        assert_eq!(version.raw, b"250210");
     }
 
     #[test]
     fn firmware_version_4kx_all_zero() {
         let mut data = vec![0xa1, 0x80, 0x81, 0x00];
         data.resize(133, 0x00);
-        let result = ElgatoDevice::format_firmware_version_4kx(&data);
-        assert!(result.starts_with("Unknown"));
+        assert_eq!(FirmwareVersion::from_4kx_response(&data), None);
+    }
+
+    #[test]
+    fn firmware_version_4kx_short_response() {
+        assert_eq!(FirmwareVersion::from_4kx_response(&[0xa1, 0x80, 0x81, 0x00, b'2']), None);
     }
 
     #[test]
     fn firmware_version_4ks_valid() {
         // BCD: year 0x25, month 0x12 (December), day 0x03
         let data = [0x00, 0x00, 0x00, 0x25, 0x12, 0x03, 0x00, 0x00];
-        let result = ElgatoDevice::format_firmware_version_4ks(&data);
-        assert_eq!(result, "25.12.03");
+        let version = FirmwareVersion::from_4ks_response(&data).unwrap();
+        assert_eq!(version, FirmwareVersion::new(25, 12, 3));
+        assert_eq!(version.to_string(), "25.12.03");
+        assert_eq!(version.raw, [0x25, 0x12, 0x03]);
     }
 
     #[test]
     fn firmware_version_4ks_zero() {
-        let data = [0x00; 8];
-        let result = ElgatoDevice::format_firmware_version_4ks(&data);
-        assert_eq!(result, "Unknown (no version reported)");
+        assert_eq!(FirmwareVersion::from_4ks_response(&[0x00; 8]), None);
     }
 
     #[test]
     fn firmware_version_4ks_invalid_month() {
         let data = [0x00, 0x00, 0x00, 0x25, 0x15, 0x03, 0x00, 0x00];
-        let result = ElgatoDevice::format_firmware_version_4ks(&data);
-        assert!(result.starts_with("Raw:"));
+        assert_eq!(FirmwareVersion::from_4ks_response(&data), None);
     }
 
     #[test]
     fn firmware_version_4ks_invalid_bcd_nibble() {
         // 0x0A has nibble A which is not valid BCD (digits must be 0-9)
         let data = [0x00, 0x00, 0x00, 0x25, 0x0A, 0x03, 0x00, 0x00];
-        let result = ElgatoDevice::format_firmware_version_4ks(&data);
-        assert!(result.starts_with("Raw:"));
+        assert_eq!(FirmwareVersion::from_4ks_response(&data), None);
+    }
+
+    #[test]
+    fn firmware_version_orders_by_date() {
+        assert!(FirmwareVersion::new(24, 12, 31) < FirmwareVersion::new(25, 1, 1));
+        assert!(FirmwareVersion::new(25, 2, 10) < FirmwareVersion::new(25, 2, 11));
+        // Raw bytes don't affect comparison
+        let read = FirmwareVersion { raw: b"250210".to_vec(), ..FirmwareVersion::new(25, 2, 10) };
+        assert_eq!(read, FirmwareVersion::new(25, 2, 10));
+    }
+
+    #[test]
+    fn firmware_version_from_str() {
+        assert_eq!("25.02.10".parse(), Ok(FirmwareVersion::new(25, 2, 10)));
+        assert_eq!("25.2.10".parse(), Ok(FirmwareVersion::new(25, 2, 10)));
+        assert_eq!("25.13.10".parse::<FirmwareVersion>(), Err(()));
+        assert_eq!("25.02".parse::<FirmwareVersion>(), Err(()));
+        assert_eq!("25.02.10.1".parse::<FirmwareVersion>(), Err(()));
+        assert_eq!("v25.02.10".parse::<FirmwareVersion>(), Err(()));
     }
 
     // --- BCD validation tests ---
@@ -522,6 +1128,30 @@ mod tests {
         assert_eq!(format!("{}", v), "Unknown (0xab)");
     }
 
+    // --- StatusField tests ---
+
+    #[test]
+    fn status_field_from_read() {
+        assert_eq!(StatusField::from_read(Ok(Some(3))), StatusField::Value(3));
+        assert_eq!(StatusField::<u8>::from_read(Ok(None)), StatusField::ReadFailed("no usable response".to_string()));
+        let failed = StatusField::<u8>::from_read(Err(ElgatoError::HidTransfer("timeout".to_string())));
+        assert!(matches!(&failed, StatusField::ReadFailed(e) if e.contains("timeout")));
+        assert!(failed.is_supported());
+        assert_eq!(failed.ok(), None);
+    }
+
+    #[test]
+    fn device_status_display_separates_failures_from_unsupported() {
+        let status = DeviceStatus {
+            hdmi_color_range: StatusField::ReadFailed("GET_CUR failed: Pipe error".to_string()),
+            ..sample_status()
+        };
+        let text = status.to_string();
+        assert!(text.contains("HDMI color range: Read failed (GET_CUR failed: Pipe error)"));
+        assert!(text.contains("HDR tone mapping: On"));
+        assert!(!text.contains("Audio input"));
+    }
+
     // --- CustomEdidStatus Display tests ---
 
     #[test]
@@ -530,6 +1160,39 @@ mod tests {
         assert_eq!(format!("{}", CustomEdidStatus::On { preset_index: 3 }), "On (preset index 3)");
     }
 
+    // --- DeviceStatus Display tests ---
+
+    #[test]
+    fn device_status_display_degraded() {
+        let status = DeviceStatus {
+            degraded: true,
+            usb_speed: StatusField::Value(ReadValue::Known(UsbSpeedStatus::Usb2)),
+            link_speed: Some(UsbSpeedStatus::Usb2),
+            ..sample_status()
+        };
+        let text = status.to_string();
+        assert!(text.contains("USB 2.0 fallback (degraded)"));
+        assert!(text.contains("Hardware: 0fd9:009b, revision 1.00"));
+        assert!(!DeviceStatus { degraded: false, ..status }.to_string().contains("degraded"));
+    }
+
+    #[test]
+    fn device_status_flags_link_slower_than_mode() {
+        let status = DeviceStatus {
+            usb_speed: StatusField::Value(ReadValue::Known(UsbSpeedStatus::TenGbps)),
+            link_speed: Some(UsbSpeedStatus::FiveGbps),
+            ..sample_status()
+        };
+        assert!(status.link_mismatch());
+        assert!(status.to_string().contains("configured for 10Gbps (SuperSpeed+) but the link is 5Gbps (SuperSpeed)"));
+
+        let matched = DeviceStatus { link_speed: Some(UsbSpeedStatus::TenGbps), ..status.clone() };
+        assert!(!matched.link_mismatch());
+        assert!(!matched.to_string().contains("WARNING"));
+        let unknown = DeviceStatus { link_speed: None, ..status };
+        assert!(!unknown.link_mismatch());
+    }
+
     // --- UsbSpeedStatus Display tests ---
 
     #[test]
@@ -538,4 +1201,147 @@ mod tests {
         assert_eq!(format!("{}", UsbSpeedStatus::FiveGbps), "5Gbps (SuperSpeed)");
         assert_eq!(format!("{}", UsbSpeedStatus::TenGbps), "10Gbps (SuperSpeed+)");
     }
+
+    #[test]
+    fn needs_write_only_skips_confirmed_match() {
+        let on = HdrToneMapping::On;
+        assert!(!needs_write(&Some(ReadValue::Known(on)), &on));
+        assert!(needs_write(&Some(ReadValue::Known(HdrToneMapping::Off)), &on));
+        assert!(needs_write(&Some(ReadValue::Unknown(0x7f)), &on));
+        assert!(needs_write(&None, &on));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn device_status_serde_roundtrip() {
+        let status = DeviceStatus {
+            edid_source: StatusField::Value(ReadValue::Unknown(0x07)),
+            ..sample_status()
+        };
+        let json = serde_json::to_string(&StatusSnapshot::new(status.clone())).unwrap();
+        assert!(json.starts_with(r#"{"schema_version":2,"#));
+        let back = serde_json::from_str::<StatusSnapshot>(&json).unwrap().status;
+        assert_eq!(back.firmware_version, status.firmware_version);
+        assert_eq!(back.hdr_tone_mapping, status.hdr_tone_mapping);
+        assert_eq!(back.edid_source, status.edid_source);
+        assert_eq!(serde_json::to_string(&StatusSnapshot::new(back)).unwrap(), json);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn status_snapshot_schema_checks() {
+        // Fields added later fall back to their defaults
+        let old: StatusSnapshot = serde_json::from_str(r#"{"schema_version":2,"degraded":true}"#).unwrap();
+        assert!(old.is_supported());
+        assert!(old.status.degraded);
+        assert_eq!(old.status.firmware_version, StatusField::NotSupported);
+
+        for version in [1, 3] {
+            let other: StatusSnapshot = serde_json::from_str(&format!(r#"{{"schema_version":{}}}"#, version)).unwrap();
+            assert!(!other.is_supported());
+        }
+        assert!(serde_json::from_str::<StatusSnapshot>("{}").is_err());
+    }
+
+    #[test]
+    fn device_status_diff_lists_changed_fields() {
+        let before = sample_status();
+        assert!(before.diff(&before).is_empty());
+
+        let after = DeviceStatus {
+            hdr_tone_mapping: StatusField::Value(ReadValue::Known(HdrToneMapping::Off)),
+            hdmi_color_range: StatusField::Value(ReadValue::Known(EdidRangePolicy::Expand)),
+            ..sample_status()
+        };
+        let changes = before.diff(&after);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].to_string(), "HDMI color range: (not read) -> Expand (Full)");
+        assert_eq!(
+            changes[1],
+            StatusChange {
+                field: "HDR tone mapping",
+                before: Some("On".to_string()),
+                after: Some("Off".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn device_status_rows_show_failures_but_not_unsupported() {
+        let status = DeviceStatus { hdmi_color_range: StatusField::ReadFailed("timeout".into()), ..sample_status() };
+        let rows = status.rows();
+        let row = |label: &str| rows.iter().find(|(l, _)| *l == label).unwrap().1.clone();
+        assert_eq!(row("HDMI color range").as_deref(), Some("Read failed (timeout)"));
+        assert_eq!(row("HDR tone mapping").as_deref(), Some("On"));
+        assert_eq!(row("Audio input"), None);
+        // The diff treats the failure as "not read".
+        assert_eq!(sample_status().diff(&status), []);
+    }
+
+    // --- StatusCache tests ---
+
+    #[test]
+    fn status_cache_disabled_by_default() {
+        let mut cache = StatusCache::default();
+        let now = Instant::now();
+        cache.store(now, &sample_status());
+        assert!(cache.get(now).is_none());
+    }
+
+    #[test]
+    fn status_cache_expires_after_ttl() {
+        let mut cache = StatusCache::default();
+        cache.set_ttl(Some(Duration::from_secs(1)));
+        let now = Instant::now();
+        cache.store(now, &sample_status());
+        assert!(cache.get(now + Duration::from_millis(500)).is_some());
+        assert!(cache.get(now + Duration::from_secs(1)).is_none());
+    }
+
+    #[test]
+    fn status_cache_invalidate_and_ttl_change_clear_entry() {
+        let mut cache = StatusCache::default();
+        cache.set_ttl(Some(Duration::from_secs(60)));
+        let now = Instant::now();
+        cache.store(now, &sample_status());
+        cache.invalidate();
+        assert!(cache.get(now).is_none());
+
+        cache.store(now, &sample_status());
+        cache.set_ttl(Some(Duration::from_secs(30)));
+        assert!(cache.get(now).is_none());
+    }
+
+    // --- Setting tests ---
+
+    #[test]
+    fn setting_names_roundtrip() {
+        for setting in Setting::ALL {
+            assert_eq!(setting.name().parse(), Ok(setting));
+        }
+        assert_eq!("HDR-MAP".parse(), Ok(Setting::HdrToneMapping));
+        assert!("status".parse::<Setting>().is_err());
+        let names: Vec<&str> = Setting::ALL.iter().map(|s| s.name()).collect();
+        assert_eq!(Setting::VALID_VALUES, names.join(", "));
+    }
+
+    #[test]
+    fn setting_value_display() {
+        let v = SettingValue::HdrToneMapping(ReadValue::Known(HdrToneMapping::On));
+        assert_eq!(v.to_string(), "On");
+        assert_eq!(SettingValue::VideoScaler(ReadValue::Unknown(0x05)).to_string(), "Unknown (0x05)");
+    }
+
+    #[test]
+    fn setting_value_tokens_match_cli_values() {
+        let token = |v: SettingValue| v.token();
+        assert_eq!(token(SettingValue::HdrToneMapping(ReadValue::Known(HdrToneMapping::On))).as_deref(), Some("on"));
+        assert_eq!(
+            token(SettingValue::HdmiColorRange(ReadValue::Known(EdidRangePolicy::Expand))).as_deref(),
+            Some("expand")
+        );
+        assert_eq!(token(SettingValue::UsbSpeed(ReadValue::Known(UsbSpeedStatus::Usb2))).as_deref(), Some("usb2"));
+        assert_eq!(token(SettingValue::FirmwareVersion(FirmwareVersion::new(25, 2, 10))).as_deref(), Some("25.02.10"));
+        assert_eq!(token(SettingValue::EdidSource(ReadValue::Unknown(0x07))), None);
+    }
 }
@@ -0,0 +1,154 @@
+//! The [`Transport`] trait: the USB operations an
+//! [`ElgatoDevice`](crate::ElgatoDevice) is built on.
+//!
+//! Everything the UVC and HID code sends goes through a `Transport`, so the
+//! protocol logic doesn't depend on libusb.  [`UsbTransport`] is the rusb
+//! backend that [`ElgatoDevice::open`](crate::ElgatoDevice::open) and
+//! friends use; another backend, or a mock that records transfers in tests,
+//! can be plugged in with
+//! [`ElgatoDevice::with_transport`](crate::ElgatoDevice::with_transport).
+
+use std::time::Duration;
+
+use rusb::{Context, DeviceHandle};
+
+use crate::error::ElgatoError;
+
+/// Control transfers and interface ownership for one card.
+///
+/// Errors use [`rusb::Error`] whatever the backend, since that is what
+/// [`ElgatoError::Usb`] carries and what the retry logic in
+/// [`TransferPolicy`](crate::TransferPolicy) looks at.
+pub trait Transport: Send + Sync {
+    /// Host-to-device control transfer.  Returns the number of bytes sent.
+    fn write_control(
+        &self,
+        request_type: u8,
+        request: u8,
+        value: u16,
+        index: u16,
+        data: &[u8],
+        timeout: Duration,
+    ) -> rusb::Result<usize>;
+
+    /// Device-to-host control transfer into `buf`.  Returns the number of
+    /// bytes read.
+    fn read_control(
+        &self,
+        request_type: u8,
+        request: u8,
+        value: u16,
+        index: u16,
+        buf: &mut [u8],
+        timeout: Duration,
+    ) -> rusb::Result<usize>;
+
+    /// Claim `interface`.  A kernel driver bound to it is detached when
+    /// `detach_kernel_driver` is set; otherwise the claim fails with
+    /// [`ElgatoError::KernelDriverActive`].
+    fn claim(&mut self, interface: u8, detach_kernel_driver: bool) -> Result<(), ElgatoError>;
+
+    /// Release `interface`, binding the kernel driver again if
+    /// `reattach_kernel_driver` is set.  Called on drop, so it can't fail;
+    /// problems are logged.
+    fn release(&mut self, interface: u8, reattach_kernel_driver: bool);
+
+    /// The class-specific descriptor bytes of each alternate setting of
+    /// `interface`, as the UVC code needs to find the Extension and
+    /// Processing Units.  Backends without descriptors return
+    /// [`rusb::Error::NotSupported`], the default.
+    fn interface_extra_descriptors(&self, interface: u8) -> rusb::Result<Vec<Vec<u8>>> {
+        let _ = interface;
+        Err(rusb::Error::NotSupported)
+    }
+
+    /// Reset the USB port, as
+    /// [`reset_usb`](crate::ElgatoDevice::reset_usb) does.  Backends that
+    /// can't return [`rusb::Error::NotSupported`], the default.
+    fn reset(&mut self) -> rusb::Result<()> {
+        Err(rusb::Error::NotSupported)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// rusb backend
+// ---------------------------------------------------------------------------
+
+/// [`Transport`] over a libusb device handle.
+pub struct UsbTransport {
+    handle: DeviceHandle<Context>,
+}
+
+impl UsbTransport {
+    /// Wrap an opened handle.  The interface is claimed by
+    /// [`ElgatoDevice::with_transport`](crate::ElgatoDevice::with_transport),
+    /// not here.
+    pub fn new(handle: DeviceHandle<Context>) -> Self {
+        Self { handle }
+    }
+}
+
+impl Transport for UsbTransport {
+    fn write_control(
+        &self,
+        request_type: u8,
+        request: u8,
+        value: u16,
+        index: u16,
+        data: &[u8],
+        timeout: Duration,
+    ) -> rusb::Result<usize> {
+        self.handle.write_control(request_type, request, value, index, data, timeout)
+    }
+
+    fn read_control(
+        &self,
+        request_type: u8,
+        request: u8,
+        value: u16,
+        index: u16,
+        buf: &mut [u8],
+        timeout: Duration,
+    ) -> rusb::Result<usize> {
+        self.handle.read_control(request_type, request, value, index, buf, timeout)
+    }
+
+    fn claim(&mut self, interface: u8, detach_kernel_driver: bool) -> Result<(), ElgatoError> {
+        if self.handle.kernel_driver_active(interface)? {
+            if !detach_kernel_driver {
+                return Err(ElgatoError::KernelDriverActive { interface: interface as u16 });
+            }
+            self.handle.detach_kernel_driver(interface)?;
+            log::debug!("Detached kernel driver from interface {}", interface);
+        }
+        self.handle.claim_interface(interface)?;
+        Ok(())
+    }
+
+    fn release(&mut self, interface: u8, reattach_kernel_driver: bool) {
+        let _ = self.handle.release_interface(interface);
+
+        if !reattach_kernel_driver {
+            log::debug!("Leaving interface {} without a kernel driver", interface);
+            return;
+        }
+        // Best-effort reattach — will fail on platforms without kernel drivers
+        if let Err(e) = self.handle.attach_kernel_driver(interface) {
+            log::debug!("Could not reattach kernel driver to interface {}: {}", interface, e);
+        }
+    }
+
+    fn interface_extra_descriptors(&self, interface: u8) -> rusb::Result<Vec<Vec<u8>>> {
+        let config = self.handle.device().active_config_descriptor()?;
+        Ok(config
+            .interfaces()
+            .filter(|i| i.number() == interface)
+            .flat_map(|i| i.descriptors())
+            .map(|d| d.extra().to_vec())
+            .collect())
+    }
+
+    fn reset(&mut self) -> rusb::Result<()> {
+        self.handle.reset()
+    }
+}
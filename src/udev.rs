@@ -0,0 +1,49 @@
+//! udev rules for non-root access, behind `elgato4k-linux udev`.
+//!
+//! The rules are generated from the product ID tables in `protocol`, so a
+//! newly supported PID (or speed mode) is covered without editing them.
+
+use crate::protocol::{PIDS_4KS, PIDS_4KX, VENDOR_ID};
+
+/// Where `udev --install` puts the rules.
+pub const UDEV_RULES_PATH: &str = "/etc/udev/rules.d/99-elgato-capture.rules";
+
+/// Rules giving the `plugdev` group, and nobody else, read/write access to
+/// every known PID of both cards, including the 4K X's 10Gbps and USB 2.0
+/// modes.
+pub fn udev_rules() -> String {
+    let mut rules = String::from("# Elgato 4K X / 4K S capture cards, generated by elgato4k-linux udev\n");
+    for (model, pids) in [("4K X", PIDS_4KX), ("4K S", PIDS_4KS)] {
+        rules.push_str(&format!("\n# Elgato {} (all speed modes)\n", model));
+        for (pid, mode) in pids {
+            // udev only allows comments on lines of their own.
+            rules.push_str(&format!(
+                "# {}\nSUBSYSTEM==\"usb\", ATTR{{idVendor}}==\"{:04x}\", ATTR{{idProduct}}==\"{:04x}\", \
+                 MODE=\"0660\", GROUP=\"plugdev\"\n",
+                mode, VENDOR_ID, pid
+            ));
+        }
+    }
+    rules
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_cover_every_known_pid() {
+        let rules = udev_rules();
+        for (pid, _) in PIDS_4KX.iter().chain(PIDS_4KS) {
+            assert!(rules.contains(&format!("ATTR{{idProduct}}==\"{:04x}\"", pid)), "missing {:04x}", pid);
+        }
+        assert!(rules.contains(
+            "SUBSYSTEM==\"usb\", ATTR{idVendor}==\"0fd9\", ATTR{idProduct}==\"009b\", MODE=\"0660\", GROUP=\"plugdev\"\n"
+        ));
+        assert!(rules.lines().all(|line| line.is_empty() || line.starts_with('#') || !line.contains('#')));
+    }
+}
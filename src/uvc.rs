@@ -10,51 +10,231 @@
 //!   3. GET_LEN sel 1 (query response buffer size — changes dynamically)
 //!   4. GET_CUR sel 1 (read response with exact length from GET_LEN)
 
+use std::fmt;
+
 use crate::device::ElgatoDevice;
 use crate::error::ElgatoError;
 use crate::protocol::*;
 use crate::settings::DeviceModel;
 
 // ---------------------------------------------------------------------------
-// AT command framing (pure functions, testable without hardware)
+// Payload framing (pure functions, testable without hardware)
 // ---------------------------------------------------------------------------
 
 /// Compute the LRC (Longitudinal Redundancy Check) for a byte slice.
 ///
 /// LRC = two's complement of the sum of all bytes (mod 256).
-fn lrc(data: &[u8]) -> u8 {
+pub fn lrc(data: &[u8]) -> u8 {
     let sum: u8 = data.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
     0u8.wrapping_sub(sum)
 }
 
+/// Builder for framed `a1` payloads on the 4K X.
+///
+/// Setting writes, read probes, and AT commands all share one layout:
+/// `[a1, family, 00, 00, cmd(4B LE), params..., LRC]`.  The family byte
+/// follows from the parameter count (none → `06`, one → `07`, two → `08`,
+/// four → `0a`): it is really a length indicator, the byte count after the
+/// header plus two.  The LRC is appended by [`build`](Self::build).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UvcFrame {
+    cmd: u32,
+    params: Vec<u8>,
+}
+
+impl UvcFrame {
+    pub(crate) fn new(cmd: u32) -> Self {
+        Self { cmd, params: Vec::new() }
+    }
+
+    /// Append one parameter byte.
+    pub(crate) fn param(mut self, byte: u8) -> Self {
+        self.params.push(byte);
+        self
+    }
+
+    /// Append several parameter bytes.
+    pub(crate) fn params(mut self, bytes: &[u8]) -> Self {
+        self.params.extend_from_slice(bytes);
+        self
+    }
+
+    /// The family / length indicator byte this frame will carry.
+    pub(crate) fn family(&self) -> u8 {
+        ((4 + self.params.len() + 2) & 0x7f) as u8
+    }
+
+    /// Serialize, appending the LRC.
+    pub(crate) fn build(&self) -> Vec<u8> {
+        let mut payload = vec![0xa1, self.family(), 0x00, 0x00];
+        payload.extend_from_slice(&self.cmd.to_le_bytes());
+        payload.extend_from_slice(&self.params);
+        payload.push(lrc(&payload));
+        payload
+    }
+}
+
 /// Build a framed AT command payload for the Realtek UVC protocol.
 ///
 /// Returns `[0xa1, length_indicator, 0x00, 0x00, cmd_id(4B LE), input..., LRC]`.
 pub(crate) fn frame_at_command(cmd_id: u32, input: &[u8]) -> Vec<u8> {
-    // Combined data: [cmd_id as u32 LE] + [input_data]
-    let mut data = cmd_id.to_le_bytes().to_vec();
-    data.extend_from_slice(input);
-
-    // Frame: [0xa1, length_indicator, 0x00, 0x00, data..., LRC]
-    let length_indicator = ((data.len() + 2) & 0x7f) as u8;
-    let mut payload = vec![0xa1, length_indicator, 0x00, 0x00];
-    payload.extend_from_slice(&data);
+    UvcFrame::new(cmd_id).params(input).build()
+}
+
+/// One-line breakdown of a framed `a1` payload, for dry runs.
+pub(crate) fn describe_uvc_frame(payload: &[u8]) -> String {
+    if payload.len() < 9 || payload[0] != 0xa1 {
+        return "unframed payload".to_string();
+    }
+    let cmd = u32::from_le_bytes([payload[4], payload[5], payload[6], payload[7]]);
+    let params: Vec<String> = payload[8..payload.len() - 1].iter().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "a1 frame, length 0x{:02x}, command 0x{:02x}, params [{}], LRC 0x{:02x}",
+        payload[1],
+        cmd,
+        params.join(" "),
+        payload[payload.len() - 1]
+    )
+}
+
+/// Frame a raw command body (usually `[cmd, 00, 00, 00, params...]`) as
+/// `[a1, length_indicator, 00, 00, body..., LRC]`.
+///
+/// The body is framed as given, so it may be 1 to 125 bytes; it isn't
+/// checked to start with a command ID.
+#[cfg(feature = "unsafe-raw")]
+pub fn raw_uvc_payload(body: &[u8]) -> Result<Vec<u8>, ElgatoError> {
+    if body.is_empty() || body.len() + 2 > 0x7f {
+        return Err(ElgatoError::RawCommandRejected(format!(
+            "body must be 1 to {} bytes, got {}",
+            0x7f - 2,
+            body.len()
+        )));
+    }
+    let mut payload = vec![0xa1, (body.len() + 2) as u8, 0x00, 0x00];
+    payload.extend_from_slice(body);
     payload.push(lrc(&payload));
-    payload
+    Ok(payload)
+}
+
+/// Check a payload that is sent as given, framing and all.  The longest
+/// frame the length indicator can describe is `0x7f + 3` bytes.
+#[cfg(feature = "unsafe-raw")]
+pub(crate) fn check_raw_uvc_bytes(payload: &[u8]) -> Result<(), ElgatoError> {
+    if payload.is_empty() || payload.len() > 0x7f + 3 {
+        return Err(ElgatoError::RawCommandRejected(format!(
+            "payload must be 1 to {} bytes, got {}",
+            0x7f + 3,
+            payload.len()
+        )));
+    }
+    Ok(())
 }
 
 /// Build a family 0x06 AT read probe: `[a1, 06, 00, 00, sub_cmd, 00, 00, 00, LRC]`.
 pub(crate) fn frame_at_read_probe(sub_cmd: u8) -> Vec<u8> {
-    let mut payload = vec![0xa1, 0x06, 0x00, 0x00, sub_cmd, 0x00, 0x00, 0x00];
-    payload.push(lrc(&payload));
-    payload
+    UvcFrame::new(sub_cmd.into()).build()
 }
 
 /// Build a family 0x07 AT read probe: `[a1, 07, 00, 00, sub_cmd, 00, 00, 00, param, LRC]`.
 pub(crate) fn frame_at_read_probe_family07(sub_cmd: u8, param: u8) -> Vec<u8> {
-    let mut payload = vec![0xa1, 0x07, 0x00, 0x00, sub_cmd, 0x00, 0x00, 0x00, param];
-    payload.push(lrc(&payload));
-    payload
+    UvcFrame::new(sub_cmd.into()).param(param).build()
+}
+
+/// Find the Processing Unit in a VideoControl interface's class-specific
+/// descriptors, returning its unit ID and `bmControls` bitmap.
+pub(crate) fn find_processing_unit(extra: &[u8]) -> Option<(u8, u32)> {
+    let mut i = 0;
+    while i + 2 < extra.len() {
+        let len = extra[i] as usize;
+        if len < 3 || i + len > extra.len() {
+            return None;
+        }
+        let desc = &extra[i..i + len];
+        if desc[1] == UVC_CS_INTERFACE && desc[2] == UVC_VC_PROCESSING_UNIT && len >= 8 {
+            let control_size = (desc[7] as usize).min(4);
+            let controls = desc.get(8..8 + control_size)?;
+            let bitmap = controls.iter().rev().fold(0u32, |acc, &b| acc << 8 | b as u32);
+            return Some((desc[3], bitmap));
+        }
+        i += len;
+    }
+    None
+}
+
+/// Find the Extension Unit with `unit_id` in a VideoControl interface's
+/// class-specific descriptors, returning its `bmControls` bitmap.
+pub(crate) fn find_extension_unit(extra: &[u8], unit_id: u8) -> Option<u64> {
+    let mut i = 0;
+    while i + 2 < extra.len() {
+        let len = extra[i] as usize;
+        if len < 3 || i + len > extra.len() {
+            return None;
+        }
+        let desc = &extra[i..i + len];
+        if desc[1] == UVC_CS_INTERFACE && desc[2] == UVC_VC_EXTENSION_UNIT && desc.get(3) == Some(&unit_id) {
+            let pins = *desc.get(21)? as usize;
+            let control_size = (*desc.get(22 + pins)? as usize).min(8);
+            let controls = desc.get(23 + pins..23 + pins + control_size)?;
+            return Some(controls.iter().rev().fold(0u64, |acc, &b| acc << 8 | b as u64));
+        }
+        i += len;
+    }
+    None
+}
+
+/// What the firmware reports about one Extension Unit control, from the
+/// standard UVC GET_INFO/GET_LEN/GET_MIN/GET_MAX/GET_RES/GET_DEF queries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct XuControlInfo {
+    /// Control selector (0x01 = payload, 0x02 = trigger on the 4K X).
+    pub selector: u8,
+    /// GET_INFO capability bitmap.
+    pub info: u8,
+    /// GET_LEN: current control length in bytes.  On the 4K X this changes
+    /// after each write to reflect the pending response.
+    pub len: u16,
+    /// GET_MIN, or `None` if the firmware stalls the request.
+    pub min: Option<Vec<u8>>,
+    /// GET_MAX, or `None` if the firmware stalls the request.
+    pub max: Option<Vec<u8>>,
+    /// GET_RES, or `None` if the firmware stalls the request.
+    pub res: Option<Vec<u8>>,
+    /// GET_DEF, or `None` if the firmware stalls the request.
+    pub default: Option<Vec<u8>>,
+}
+
+impl XuControlInfo {
+    /// Whether GET_CUR is supported (GET_INFO bit 0).
+    pub fn supports_get(&self) -> bool {
+        self.info & 0x01 != 0
+    }
+
+    /// Whether SET_CUR is supported (GET_INFO bit 1).
+    pub fn supports_set(&self) -> bool {
+        self.info & 0x02 != 0
+    }
+}
+
+impl fmt::Display for XuControlInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let access = match (self.supports_get(), self.supports_set()) {
+            (true, true) => "GET/SET",
+            (true, false) => "GET",
+            (false, true) => "SET",
+            (false, false) => "none",
+        };
+        writeln!(f, "Selector 0x{:02x}: {} (info 0x{:02x}), length {}", self.selector, access, self.info, self.len)?;
+        for (name, value) in [("min", &self.min), ("max", &self.max), ("res", &self.res), ("def", &self.default)] {
+            match value {
+                Some(bytes) if bytes.len() > 16 => writeln!(f, "    {}: {:02x?} ... ({} bytes)", name, &bytes[..16], bytes.len())?,
+                Some(bytes) => writeln!(f, "    {}: {:02x?}", name, bytes)?,
+                None => writeln!(f, "    {}: not supported", name)?,
+            }
+        }
+        Ok(())
+    }
 }
 
 /// UVC Extension Unit protocol methods for the 4K X.
@@ -75,13 +255,12 @@ impl ElgatoDevice {
         let w_value = UVC_SELECTOR_TRIGGER << 8;
         let w_index = (UVC_ENTITY_ID << 8) | UVC_INTERFACE;
 
-        self.handle.write_control(
+        self.write_control(
             UVC_REQUEST_TYPE_OUT,
             UVC_SET_CUR,
             w_value,
             w_index,
             data,
-            USB_TIMEOUT,
         ).map_err(|e| ElgatoError::UvcTransfer(format!("trigger SET_CUR failed: {}", e)))?;
 
         Ok(())
@@ -92,13 +271,12 @@ impl ElgatoDevice {
         let w_value = UVC_SELECTOR_VALUE << 8;
         let w_index = (UVC_ENTITY_ID << 8) | UVC_INTERFACE;
 
-        self.handle.write_control(
+        self.write_control(
             UVC_REQUEST_TYPE_OUT,
             UVC_SET_CUR,
             w_value,
             w_index,
             payload,
-            USB_TIMEOUT,
         ).map_err(|e| ElgatoError::UvcTransfer(format!("payload SET_CUR failed: {}", e)))?;
 
         Ok(())
@@ -108,7 +286,16 @@ impl ElgatoDevice {
     ///
     /// The trigger announces the payload length as a u16 LE value, matching
     /// the Windows driver behavior observed in USB captures.
+    ///
+    /// Drops the cached status, since the write may change it.
     pub(crate) fn set_uvc_setting(&self, payload: &[u8]) -> Result<(), ElgatoError> {
+        self.status_cache().invalidate();
+        self.send_uvc_two_step(payload)
+    }
+
+    /// Length trigger + payload, without touching the status cache.
+    fn send_uvc_two_step(&self, payload: &[u8]) -> Result<(), ElgatoError> {
+        log::trace!("UVC write {:02x?}", payload);
         let trigger = (payload.len() as u16).to_le_bytes();
         self.send_uvc_trigger_data(&trigger)?;
         self.send_uvc_payload(payload)?;
@@ -125,13 +312,12 @@ impl ElgatoDevice {
         let w_index = (UVC_ENTITY_ID << 8) | UVC_INTERFACE;
         let mut buf = [0u8; 2];
 
-        let len = self.handle.read_control(
+        let len = self.read_control(
             UVC_REQUEST_TYPE_IN,
             UVC_GET_LEN,
             w_value,
             w_index,
             &mut buf,
-            USB_TIMEOUT,
         ).map_err(|e| ElgatoError::UvcTransfer(format!("GET_LEN failed: {}", e)))?;
 
         if len < 2 {
@@ -147,16 +333,16 @@ impl ElgatoDevice {
         let w_index = (UVC_ENTITY_ID << 8) | UVC_INTERFACE;
         let mut buf = vec![0u8; length];
 
-        let len = self.handle.read_control(
+        let len = self.read_control(
             UVC_REQUEST_TYPE_IN,
             UVC_GET_CUR,
             w_value,
             w_index,
             &mut buf,
-            USB_TIMEOUT,
         ).map_err(|e| ElgatoError::UvcTransfer(format!("GET_CUR failed: {}", e)))?;
 
         buf.truncate(len);
+        log::trace!("UVC read {:02x?}", buf);
         Ok(buf)
     }
 
@@ -180,13 +366,12 @@ impl ElgatoDevice {
         let w_index = (UVC_ENTITY_ID << 8) | UVC_INTERFACE;
         let mut buf = vec![0u8; response_len];
 
-        let len = self.handle.read_control(
+        let len = self.read_control(
             UVC_REQUEST_TYPE_IN,
             UVC_GET_CUR,
             w_value,
             w_index,
             &mut buf,
-            USB_TIMEOUT,
         ).map_err(|e| ElgatoError::UvcTransfer(format!("status GET_CUR failed: {}", e)))?;
 
         buf.truncate(len);
@@ -202,13 +387,111 @@ impl ElgatoDevice {
     ///   4. GET_LEN sel 1 (query dynamic response size)
     ///   5. GET_CUR sel 1 (read response)
     pub(crate) fn probe_uvc_setting(&self, probe: &[u8]) -> Result<Vec<u8>, ElgatoError> {
-        self.set_uvc_setting(probe)?;
+        self.send_uvc_two_step(probe)?;
         // Poll sel 2 status — matches Windows behavior and gives the device
         // time to process the command before we query GET_LEN on sel 1
         self.poll_uvc_status()?;
         self.read_uvc_setting()
     }
 
+    // --- Extension Unit capability queries ---
+
+    /// `bmControls` of XU #4, read from the VideoControl interface descriptors.
+    pub(crate) fn extension_unit_controls(&self) -> Result<u64, ElgatoError> {
+        self.transport
+            .interface_extra_descriptors(UVC_INTERFACE as u8)?
+            .iter()
+            .find_map(|extra| find_extension_unit(extra, UVC_ENTITY_ID as u8))
+            .ok_or_else(|| ElgatoError::UvcTransfer("no Extension Unit #4 in the video control descriptors".to_string()))
+    }
+
+    /// Issue a GET request (GET_INFO/MIN/MAX/RES/DEF) of `length` bytes to
+    /// an XU selector.
+    pub(crate) fn get_xu_control(&self, selector: u8, request: u8, length: usize) -> Result<Vec<u8>, ElgatoError> {
+        let w_value = (selector as u16) << 8;
+        let w_index = (UVC_ENTITY_ID << 8) | UVC_INTERFACE;
+        let mut buf = vec![0u8; length];
+
+        let len = self.read_control(
+            UVC_REQUEST_TYPE_IN,
+            request,
+            w_value,
+            w_index,
+            &mut buf,
+        ).map_err(|e| ElgatoError::UvcTransfer(format!("XU GET 0x{:02x} failed: {}", request, e)))?;
+
+        buf.truncate(len);
+        Ok(buf)
+    }
+
+    /// Query everything the firmware reports about an XU selector.
+    ///
+    /// GET_INFO and GET_LEN must succeed; GET_MIN/MAX/RES/DEF are optional
+    /// in UVC and are recorded as `None` when they fail.
+    pub(crate) fn query_xu_control(&self, selector: u8) -> Result<XuControlInfo, ElgatoError> {
+        let info = self.get_xu_control(selector, UVC_GET_INFO, 1)?;
+        let info = *info.first().ok_or_else(|| ElgatoError::UvcTransfer("GET_INFO returned 0 bytes".to_string()))?;
+        let len = self.get_uvc_len(selector as u16)?;
+        let get = |request| self.get_xu_control(selector, request, len as usize).ok();
+        Ok(XuControlInfo {
+            selector,
+            info,
+            len,
+            min: get(UVC_GET_MIN),
+            max: get(UVC_GET_MAX),
+            res: get(UVC_GET_RES),
+            default: get(UVC_GET_DEF),
+        })
+    }
+
+    // --- Processing Unit (standard UVC picture controls) ---
+
+    /// Unit ID and `bmControls` of the Processing Unit, read from the
+    /// VideoControl interface descriptors.
+    pub(crate) fn processing_unit(&self) -> Result<(u8, u32), ElgatoError> {
+        self.transport
+            .interface_extra_descriptors(UVC_INTERFACE as u8)?
+            .iter()
+            .find_map(|extra| find_processing_unit(extra))
+            .ok_or_else(|| ElgatoError::UvcTransfer("no Processing Unit in the video control descriptors".to_string()))
+    }
+
+    /// Issue a 2-byte GET request (GET_CUR/MIN/MAX/RES/DEF) to a PU control.
+    pub(crate) fn get_pu_control(&self, unit_id: u8, selector: u8, request: u8) -> Result<[u8; 2], ElgatoError> {
+        let w_value = (selector as u16) << 8;
+        let w_index = ((unit_id as u16) << 8) | UVC_INTERFACE;
+        let mut buf = [0u8; 2];
+
+        let len = self.read_control(
+            UVC_REQUEST_TYPE_IN,
+            request,
+            w_value,
+            w_index,
+            &mut buf,
+        ).map_err(|e| ElgatoError::UvcTransfer(format!("PU GET 0x{:02x} failed: {}", request, e)))?;
+
+        if len < 2 {
+            return Err(ElgatoError::UvcTransfer(format!("PU GET 0x{:02x} returned {} bytes", request, len)));
+        }
+        Ok(buf)
+    }
+
+    /// SET_CUR on a PU control.
+    pub(crate) fn set_pu_control(&self, unit_id: u8, selector: u8, value: [u8; 2]) -> Result<(), ElgatoError> {
+        let w_value = (selector as u16) << 8;
+        let w_index = ((unit_id as u16) << 8) | UVC_INTERFACE;
+
+        self.write_control(
+            UVC_REQUEST_TYPE_OUT,
+            UVC_SET_CUR,
+            w_value,
+            w_index,
+            &value,
+        ).map_err(|e| ElgatoError::UvcTransfer(format!("PU SET_CUR failed: {}", e)))?;
+
+        Ok(())
+    }
+
     // --- AT Command framing ---
     //
     // The ITE UB700E chip uses "AT commands" internally. In the UVC protocol,
@@ -236,6 +519,7 @@ impl ElgatoDevice {
             });
         }
 
+        self.status_cache().invalidate();
         let payload = frame_at_command(cmd_id, input);
         self.probe_uvc_setting(&payload)
     }
@@ -280,12 +564,37 @@ impl ElgatoDevice {
 mod tests {
     use super::*;
 
+    #[test]
+    fn describe_frame_breaks_down_fields() {
+        let frame = UvcFrame::new(0x7c).params(&[0x01, 0x03]).build();
+        assert_eq!(
+            describe_uvc_frame(&frame),
+            format!("a1 frame, length 0x08, command 0x7c, params [01 03], LRC 0x{:02x}", frame[10])
+        );
+        assert_eq!(describe_uvc_frame(&[0x01, 0x02]), "unframed payload");
+    }
+
     #[test]
     fn lrc_checksum() {
         // LRC = two's complement of byte sum (mod 256)
         assert_eq!(lrc(&[0xa1, 0x06, 0x00, 0x00, 0x77, 0x00, 0x00, 0x00]), 0xe2);
     }
 
+    #[test]
+    fn uvc_frame_family_follows_param_count() {
+        assert_eq!(UvcFrame::new(0x90).family(), 0x06);
+        assert_eq!(UvcFrame::new(0x1f).param(1).family(), 0x07);
+        assert_eq!(UvcFrame::new(0x7c).params(&[1, 3]).family(), 0x08);
+        assert_eq!(UvcFrame::new(0x54).params(&[0, 12, 0x80, 0]).family(), 0x0a);
+
+        let frame = UvcFrame::new(0x1f).param(1);
+        let payload = frame.build();
+        assert_eq!(payload[..4], [0xa1, frame.family(), 0x00, 0x00]);
+        assert_eq!(payload[4..8], 0x1f_u32.to_le_bytes());
+        assert_eq!(payload[8], 1);
+        assert_eq!(payload.iter().fold(0u8, |a, &b| a.wrapping_add(b)), 0);
+    }
+
     #[test]
     fn frame_at_read_probe_firmware() {
         // Firmware version read: AT cmd 0x77 via a1 06 family
@@ -360,4 +669,78 @@ mod tests {
         let total: u8 = payload.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
         assert_eq!(total, 0);
     }
+
+    #[test]
+    fn processing_unit_found_after_other_units() {
+        let extra = [
+            // VC header (truncated), input terminal, then PU #2 with 2-byte bmControls
+            0x05, 0x24, 0x01, 0x10, 0x01,
+            0x08, 0x24, 0x02, 0x01, 0x01, 0x02, 0x00, 0x00,
+            0x0b, 0x24, 0x05, 0x02, 0x01, 0x00, 0x00, 0x02, 0x0f, 0x01, 0x00,
+        ];
+        assert_eq!(find_processing_unit(&extra), Some((2, 0x010f)));
+    }
+
+    #[test]
+    fn processing_unit_missing_or_malformed() {
+        assert_eq!(find_processing_unit(&[0x05, 0x24, 0x01, 0x10, 0x01]), None);
+        assert_eq!(find_processing_unit(&[0x0b, 0x24, 0x05, 0x02]), None);
+        assert_eq!(find_processing_unit(&[]), None);
+    }
+
+    #[test]
+    fn extension_unit_controls_by_unit_id() {
+        let mut xu = vec![0x1b, 0x24, 0x06, 0x04];
+        xu.extend_from_slice(&[0xaa; 16]); // GUID
+        xu.extend_from_slice(&[0x02, 0x01, 0x03, 0x02, 0x03, 0x00, 0x00]);
+        let mut extra = vec![0x05, 0x24, 0x01, 0x10, 0x01];
+        extra.extend_from_slice(&xu);
+        assert_eq!(find_extension_unit(&extra, 4), Some(0x0003));
+        assert_eq!(find_extension_unit(&extra, 5), None);
+        assert_eq!(find_extension_unit(&xu[..22], 4), None);
+    }
+
+    #[test]
+    fn xu_control_info_display() {
+        let info = XuControlInfo {
+            selector: 1,
+            info: 0x03,
+            len: 133,
+            min: None,
+            max: Some(vec![0xff; 133]),
+            res: None,
+            default: Some(vec![0x00, 0x01]),
+        };
+        assert!(info.supports_get() && info.supports_set());
+        let text = info.to_string();
+        assert!(text.starts_with("Selector 0x01: GET/SET (info 0x03), length 133\n"));
+        assert!(text.contains("min: not supported"));
+        assert!(text.contains("... (133 bytes)"));
+        assert!(text.contains("def: [00, 01]"));
+    }
+
+    #[cfg(feature = "unsafe-raw")]
+    #[test]
+    fn raw_payload_matches_typed_setting() {
+        let typed = crate::settings::HdrToneMapping::On.payload_4kx();
+        assert_eq!(raw_uvc_payload(&[0x1f, 0x00, 0x00, 0x00, 0x01]).unwrap(), typed);
+        assert!(raw_uvc_payload(&[]).is_err());
+        assert!(raw_uvc_payload(&[0; 126]).is_err());
+    }
+
+    #[cfg(feature = "unsafe-raw")]
+    #[test]
+    fn raw_body_shorter_than_a_command_id_is_framed_as_given() {
+        let payload = raw_uvc_payload(&[0x42]).unwrap();
+        assert_eq!(payload[..5], [0xa1, 0x03, 0x00, 0x00, 0x42]);
+        assert_eq!(payload[5], lrc(&payload[..5]));
+    }
+
+    #[cfg(feature = "unsafe-raw")]
+    #[test]
+    fn raw_bytes_length_is_checked() {
+        assert!(check_raw_uvc_bytes(&crate::settings::HdrToneMapping::On.payload_4kx()).is_ok());
+        assert!(check_raw_uvc_bytes(&[]).is_err());
+        assert!(check_raw_uvc_bytes(&[0; 131]).is_err());
+    }
 }
@@ -55,17 +55,549 @@ fn help_lists_supported_devices() {
 
 #[test]
 fn unknown_flag_exits_nonzero() {
-    // NOTE: The CLI opens the USB device before validating most args,
-    // so this will fail with "device not found" rather than "unknown option".
-    // Either way it must exit non-zero.
+    // Setter flags are checked before the USB device is opened.
     let out = run(&["--bogus-flag", "value"]);
     assert!(!out.status.success());
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Unknown option '--bogus-flag'"));
 }
 
 #[test]
 fn missing_value_exits_nonzero() {
-    // A known flag with no value should error out (device-not-found or
-    // missing-argument, depending on arg order vs device open).
+    // A known flag with no value should error out before the device is opened.
     let out = run(&["--hdr-map"]);
     assert!(!out.status.success());
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--hdr-map requires a value"));
+}
+
+#[test]
+fn open_flags_are_checked_before_opening_device() {
+    let out = run(&["--status", "--retry", "many"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Invalid value 'many' for --retry"));
+
+    let out = run(&["--status", "--usb-timeout", "0s"]);
+    assert_eq!(out.status.code(), Some(2));
+
+    // Taken out like the other global flags, so the setters still parse.
+    let out = run(&[
+        "--usb-timeout", "3s", "--no-detach", "--hdr-map", "on", "--retry", "2", "--no-reattach", "--model", "4ks",
+        "--dry-run",
+    ]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+}
+
+// ── set KEY=VALUE ────────────────────────────────────────────────────
+
+#[test]
+fn set_plans_pairs_in_order() {
+    let out = run(&["set", "hdr-map=on", "hdmi-range=auto", "--model", "4ks", "--dry-run"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let hdr = stdout.find("06 06 06 55 02 0a 01").expect("hdr-map write");
+    let range = stdout.find("06 06 06 55 02 0b 00").expect("hdmi-range write");
+    assert!(hdr < range, "stdout: {}", stdout);
+}
+
+#[test]
+fn set_rejects_unknown_key_before_opening_device() {
+    let out = run(&["set", "hdr=on"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Unknown setting 'hdr'"));
+}
+
+#[test]
+fn set_needs_key_value_pairs() {
+    let out = run(&["set", "hdr-map", "on"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("KEY=VALUE"));
+}
+
+#[test]
+fn all_rejects_single_device_queries() {
+    let out = run(&["--get", "hdr-map", "--all"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--all only applies"));
+}
+
+#[test]
+fn watch_checks_interval_before_opening_device() {
+    let out = run(&["watch", "--interval", "soon"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("500ms"));
+
+    let out = run(&["watch", "--all"]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn get_is_a_query() {
+    let out = run(&["get", "hdr-map", "--dry-run"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--dry-run only applies"));
+
+    let out = run(&["get", "hdr-map", "--all"]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn format_is_checked_before_opening_device() {
+    let out = run(&["list", "--format", "yaml"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("table, plain, json"));
+
+    let out = run(&["--status", "--json", "--format", "table"]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+// ── Diagnostics ──────────────────────────────────────────────────────
+
+#[test]
+fn version_prints_tool_version_without_device() {
+    // No card is not an error here: the report is what goes in a bug report.
+    let out = run(&["version"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(out.status.success(), "stderr: {}", String::from_utf8_lossy(&out.stderr));
+    assert!(stdout.starts_with(&format!("elgato4k-linux {} ", env!("CARGO_PKG_VERSION"))), "stdout: {}", stdout);
+    assert!(stdout.contains("Kernel: "));
+    assert!(stdout.contains("No devices found"), "stdout: {}", stdout);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn version_json_reports_without_device() {
+    let out = run(&["version", "--json"]);
+    assert!(out.status.success(), "stderr: {}", String::from_utf8_lossy(&out.stderr));
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).expect("stdout is JSON");
+    assert_eq!(report["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(report["devices"], serde_json::json!([]));
+}
+
+#[test]
+fn selftest_is_single_device() {
+    let out = run(&["selftest", "--all"]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn reset_usb_takes_no_arguments() {
+    let out = run(&["reset-usb", "--hard"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--hard"));
+
+    let out = run(&["reset-usb", "--dry-run"]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn gui_options_are_checked_before_opening_device() {
+    for args in [&["gui", "--fullscreen"][..], &["gui", "--all"], &["gui", "--dry-run"], &["gui", "--json"]] {
+        let out = run(args);
+        assert_eq!(out.status.code(), Some(2), "{:?}", args);
+    }
+}
+
+#[cfg(not(feature = "gui"))]
+#[test]
+fn gui_needs_the_feature() {
+    let out = run(&["gui"]);
+    assert_eq!(out.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("`gui` feature"));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn doctor_reports_checks_as_json() {
+    // Passes or fails depending on the machine, but always reports.
+    let out = run(&["doctor", "--json"]);
+    let checks: serde_json::Value = serde_json::from_slice(&out.stdout).expect("stdout is JSON");
+    let checks = checks.as_array().unwrap();
+    assert!(!checks.is_empty());
+    for check in checks {
+        assert!(matches!(check["status"].as_str(), Some("pass" | "warn" | "fail")), "{}", check);
+    }
+    assert_eq!(out.status.success(), checks.iter().all(|c| c["status"] != "fail"));
+}
+
+#[test]
+fn udev_print_covers_10g_mode() {
+    let out = run(&["udev", "--print"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("ATTR{idProduct}==\"009b\""), "stdout: {}", stdout);
+    assert!(stdout.contains("ATTR{idProduct}==\"00ae\""), "stdout: {}", stdout);
+}
+
+#[test]
+fn udev_needs_a_mode() {
+    let out = run(&["udev"]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+// ── Setting bundles ──────────────────────────────────────────────────
+
+#[test]
+fn preset_lists_bundles_without_device() {
+    let out = run(&["preset"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("console-hdr"));
+    assert!(stdout.contains("retro"));
+}
+
+#[test]
+fn unknown_bundle_rejected_before_opening_device() {
+    let out = run(&["preset", "nope"]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("console-hdr, pc-sdr, retro"), "stderr: {}", stderr);
+}
+
+/// A path in the temp dir unique to this test process.
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("elgato4k-cli-{}-{}", std::process::id(), name))
+}
+
+// ── Profiles (no hardware needed) ────────────────────────────────────
+
+#[test]
+fn apply_lists_profiles_without_device() {
+    let path = temp_path("profiles-list.conf");
+    std::fs::write(&path, "[gaming]\nhdr-map = on\nhdmi-range = auto\n").unwrap();
+    let out = run(&["apply", "--config", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).ok();
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("gaming"), "stdout: {}", stdout);
+    assert!(stdout.contains("hdr-map on, hdmi-range auto"), "stdout: {}", stdout);
+}
+
+#[test]
+fn unknown_profile_rejected_before_opening_device() {
+    let path = temp_path("profiles-missing.conf");
+    std::fs::write(&path, "[gaming]\nhdr-map = on\n").unwrap();
+    let out = run(&["apply", "--profile", "desktop", "--config", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).ok();
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("desktop") && stderr.contains("gaming"), "stderr: {}", stderr);
+}
+
+#[test]
+fn save_profile_rejects_bad_name_before_opening_device() {
+    let out = run(&["save-profile", "my profile"]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("my profile"), "stderr: {}", stderr);
+}
+
+#[test]
+fn bad_profile_file_reports_line() {
+    let path = temp_path("profiles-bad.conf");
+    std::fs::write(&path, "[gaming]\nhdr-map = maybe\n").unwrap();
+    let out = run(&["apply", "--profile", "gaming", "--config", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).ok();
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("line 2: Invalid value 'maybe' for hdr-map"), "stderr: {}", stderr);
+}
+
+/// Helper: run the binary with `input` on stdin.
+#[cfg(feature = "json")]
+fn run_with_stdin(args: &[&str], input: &str) -> std::process::Output {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_elgato4k-linux"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to execute binary");
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn apply_stdin_takes_setting_keys() {
+    let out = run_with_stdin(&["apply", "--stdin", "--dry-run", "--model", "4ks"], r#"{"hdr-map": "on"}"#);
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stdout).contains("06 06 06 55 02 0a 01"));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn apply_stdin_takes_status_snapshot() {
+    let snapshot = r#"{"schema_version":2,"pid":175,"degraded":false,
+        "hdr_tone_mapping":{"Value":{"Known":"On"}},"usb_speed":{"Value":{"Known":"FiveGbps"}}}"#;
+    let out = run_with_stdin(&["apply", "--stdin", "--dry-run", "--model", "4ks"], snapshot);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("HDR tone mapping to On"), "stdout: {}", stdout);
+    assert!(!stdout.contains("USB speed"), "stdout: {}", stdout);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn apply_stdin_rejects_bad_input() {
+    for input in ["hdr-map=on", r#"["hdr-map"]"#, r#"{"hdr-mapping": "on"}"#, r#"{"hdr-map": "maybe"}"#] {
+        let out = run_with_stdin(&["apply", "--stdin", "--dry-run", "--model", "4ks"], input);
+        assert_eq!(out.status.code(), Some(2), "input: {}", input);
+    }
+}
+
+// ── Status snapshots (no hardware needed) ────────────────────────────
+
+#[cfg(feature = "json")]
+#[test]
+fn diff_compares_saved_snapshots() {
+    let before = temp_path("status-before.json");
+    let after = temp_path("status-after.json");
+    std::fs::write(&before, r#"{"schema_version":2,"pid":156,"degraded":false}"#).unwrap();
+    std::fs::write(&after, r#"{"schema_version":2,"pid":157,"degraded":true}"#).unwrap();
+    let (before, after) = (before.to_str().unwrap(), after.to_str().unwrap());
+    let text = run(&["diff", before, after]);
+    let json = run(&["diff", before, after, "--json"]);
+    let same = run(&["diff", before, before]);
+    std::fs::remove_file(before).ok();
+    std::fs::remove_file(after).ok();
+
+    assert!(text.status.success());
+    let stdout = String::from_utf8_lossy(&text.stdout);
+    assert!(stdout.contains("Product ID: 009c -> 009d"), "stdout: {}", stdout);
+    assert!(stdout.contains("USB 2.0 fallback: No -> Yes"), "stdout: {}", stdout);
+
+    let changes: serde_json::Value = serde_json::from_slice(&json.stdout).expect("stdout is JSON");
+    assert_eq!(changes[1]["field"], "USB 2.0 fallback");
+    assert_eq!(changes[1]["after"], "Yes");
+
+    assert!(String::from_utf8_lossy(&same.stdout).starts_with("No changes"));
+}
+
+#[test]
+fn diff_needs_two_files() {
+    let out = run(&["diff", "before.json"]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("diff <BEFORE> <AFTER>"));
+}
+
+// ── Dry runs (no hardware needed with --model) ───────────────────────
+
+#[test]
+fn dry_run_prints_hid_report() {
+    let out = run(&["--hdr-map", "on", "--model", "4ks", "--dry-run"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("06 06 06 55 02 0a 01"), "stdout: {}", stdout);
+}
+
+#[test]
+fn dry_run_prints_uvc_two_step_write() {
+    let out = run(&["--dry-run", "--model", "4kx", "--hdmi-range", "expand"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("selector 0x02: 0b 00"), "stdout: {}", stdout);
+    assert!(stdout.contains("command 0x7c"), "stdout: {}", stdout);
+}
+
+#[test]
+fn legacy_flags_match_set() {
+    // The original `--flag value` spelling must keep planning exactly what
+    // `set key=value` does, for every setting.
+    let cases = [
+        ("4kx", "hdmi-range", "expand"),
+        ("4kx", "edid-range", "shrink"),
+        ("4ks", "edid-source", "merged"),
+        ("4ks", "hdr-map", "on"),
+        ("4kx", "custom-edid", "off"),
+        ("4kx", "custom-edid-preset", "12"),
+        ("4kx", "input-limit", "1440p"),
+        ("4kx", "brightness", "-3"),
+        ("4ks", "audio-input", "analog"),
+        ("4ks", "video-scaler", "off"),
+        ("4kx", "usb-speed", "10g"),
+    ];
+    for (model, key, value) in cases {
+        let flag = format!("--{}", key);
+        let pair = format!("{}={}", key, value);
+        let legacy = run(&[&flag, value, "--model", model, "--dry-run"]);
+        let set = run(&["set", &pair, "--model", model, "--dry-run"]);
+        assert!(legacy.status.success(), "{} {}: {}", flag, value, String::from_utf8_lossy(&legacy.stderr));
+        assert_eq!(legacy.stdout, set.stdout, "{} {}", flag, value);
+    }
+}
+
+#[test]
+fn dry_run_rejects_unsupported_setting() {
+    let out = run(&["--audio-input", "analog", "--model", "4kx", "--dry-run"]);
+    assert!(!out.status.success());
+    assert_eq!(out.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("not supported on 4K X"));
+}
+
+#[test]
+fn model_needs_dry_run() {
+    let out = run(&["--hdr-map", "on", "--model", "4ks"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--dry-run"));
+}
+
+// ── Raw commands (no hardware needed with --dry-run) ─────────────────
+
+#[cfg(not(feature = "unsafe-raw"))]
+#[test]
+fn raw_needs_feature() {
+    let out = run(&["raw", "hid", "06", "06", "06", "55", "02", "0a", "01"]);
+    assert!(!out.status.success());
+    assert_eq!(out.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("unsafe-raw"));
+}
+
+#[cfg(feature = "unsafe-raw")]
+#[test]
+fn raw_uvc_frame_adds_header_and_lrc() {
+    let out = run(&["raw", "uvc", "1f", "00", "00", "00", "01", "--frame", "--dry-run"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("selector 0x01: a1 07 00 00 1f 00 00 00 01 38"), "stdout: {}", stdout);
+}
+
+#[cfg(feature = "unsafe-raw")]
+#[test]
+fn raw_uvc_lrc_matches_frame() {
+    let out = run(&["raw", "uvc", "a1070000", "1f000000", "01", "--lrc", "--dry-run"]);
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stdout).contains("a1 07 00 00 1f 00 00 00 01 38"));
+}
+
+#[cfg(feature = "unsafe-raw")]
+#[test]
+fn raw_hid_refuses_hang_command() {
+    let out = run(&["raw", "hid", "06", "06", "06", "55", "02", "13", "00", "--dry-run"]);
+    assert!(!out.status.success());
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Raw command rejected"));
+}
+
+#[cfg(feature = "unsafe-raw")]
+#[test]
+fn raw_rejects_bad_hex() {
+    let out = run(&["raw", "hid", "0x6", "--dry-run"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("0x6"));
+}
+
+// ── EDID tools (no hardware needed) ──────────────────────────────────
+
+#[test]
+fn edid_preset_lists_presets() {
+    let out = run(&["edid", "preset"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("ultrawide-144"));
+    assert!(stdout.contains("pc-444"));
+}
+
+#[test]
+fn edid_preset_writes_file() {
+    let path = temp_path("pc-444.bin");
+    let out = run(&["edid", "preset", "pc-444", "--out", path.to_str().unwrap()]);
+    assert!(out.status.success());
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    assert_eq!(bytes.len() % 128, 0);
+    assert_eq!(&bytes[..8], &[0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00]);
+}
+
+#[test]
+fn progress_goes_to_stderr_and_quiet_hides_it() {
+    let path = temp_path("quiet.bin");
+    let out = run(&["edid", "preset", "pc-444", "--out", path.to_str().unwrap()]);
+    assert!(out.status.success());
+    assert!(out.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Wrote pc-444"));
+
+    let out = run(&["--quiet", "edid", "preset", "pc-444", "--out", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).ok();
+    assert!(out.status.success());
+    assert!(out.stderr.is_empty(), "stderr: {}", String::from_utf8_lossy(&out.stderr));
+}
+
+#[test]
+fn edid_patch_rewrites_file() {
+    let input = temp_path("patch-in.bin");
+    let output = temp_path("patch-out.bin");
+    assert!(run(&["edid", "preset", "4k-120-hdr", "--out", input.to_str().unwrap()]).status.success());
+    let out = run(&[
+        "edid", "patch", input.to_str().unwrap(),
+        "--out", output.to_str().unwrap(),
+        "--audio", "stereo",
+    ]);
+    let written = std::fs::read(&output);
+    std::fs::remove_file(&input).ok();
+    std::fs::remove_file(&output).ok();
+    assert!(out.status.success());
+    assert_eq!(written.unwrap().len(), 256);
+}
+
+#[test]
+fn edid_slots_lists_card_presets() {
+    let out = run(&["edid", "slots"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains(" 0  Game Capture 4K X (Default)"), "stdout: {}", stdout);
+    assert!(stdout.contains("12  Custom (user-uploaded)"), "stdout: {}", stdout);
+}
+
+#[test]
+fn edid_unknown_preset_exits_nonzero() {
+    let out = run(&["edid", "preset", "bogus", "--out", "/dev/null"]);
+    assert!(!out.status.success());
+}
+
+// ── JSON output (no hardware needed) ─────────────────────────────────
+
+#[cfg(feature = "json")]
+#[test]
+fn preset_list_as_json() {
+    let out = run(&["preset", "--json"]);
+    assert!(out.status.success());
+    let list: serde_json::Value = serde_json::from_slice(&out.stdout).expect("stdout is JSON");
+    let names: Vec<&str> = list.as_array().unwrap().iter().map(|e| e["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["console-hdr", "pc-sdr", "retro"]);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn edid_preset_list_as_json() {
+    let out = run(&["--json", "edid", "preset"]);
+    assert!(out.status.success());
+    let list: serde_json::Value = serde_json::from_slice(&out.stdout).expect("stdout is JSON");
+    assert!(list.as_array().unwrap().iter().any(|e| e["name"] == "ultrawide-144"));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_flag_does_not_break_setter_pairs() {
+    // `--json` is pulled out before the flag/value pairs are read, so the
+    // bad value is still reported (or the device is missing) — never
+    // "--json requires a value".
+    let out = run(&["--hdr-map", "--json", "maybe"]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(!stderr.contains("--json"), "stderr: {}", stderr);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn dry_run_as_json() {
+    let out = run(&["preset", "retro", "--model", "4ks", "--dry-run", "--json"]);
+    assert!(out.status.success());
+    let list: serde_json::Value = serde_json::from_slice(&out.stdout).expect("stdout is JSON");
+    let list = list.as_array().unwrap();
+    assert_eq!(list.last().unwrap()["setting"], "video-scaler");
+    assert!(list[0]["transfers"][0]["data"].as_str().unwrap().starts_with("06 06 06 55 02"));
 }